@@ -0,0 +1,92 @@
+/*
+learn_rust 使用的过程宏
+
+`#[trace]` 记录函数每次调用的参数、返回值、耗时与嵌套深度，运行时的部分在 `learn_rust::call_trace`。
+只有打开 `enabled` feature（由 learn_rust 的 `trace` feature 打开）时才会改写函数，否则原样返回。
+ */
+
+use proc_macro::TokenStream;
+
+/// 跟踪函数的调用，可以用 `#[trace(skip(a, b))]` 跳过不需要显示或者没有实现 Debug 的参数
+#[proc_macro_attribute]
+pub fn trace(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if cfg!(feature = "enabled") {
+        expand::trace(attr.into(), item.into()).unwrap_or_else(syn::Error::into_compile_error).into()
+    } else {
+        item
+    }
+}
+
+mod expand {
+    use proc_macro2::{TokenStream, TokenTree};
+    use quote::{quote, ToTokens};
+    use syn::{FnArg, Ident, ItemFn, Pat, ReturnType};
+
+    pub fn trace(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+        let mut skip: Vec<Ident> = Vec::new();
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("skip") {
+                meta.parse_nested_meta(|name| {
+                    skip.push(name.path.require_ident()?.clone());
+                    Ok(())
+                })
+            } else {
+                Err(meta.error("expected `skip(...)`"))
+            }
+        });
+        syn::parse::Parser::parse2(parser, attr)?;
+
+        let ItemFn { attrs, vis, sig, block } = syn::parse2::<ItemFn>(item)?;
+        if let Some(asyncness) = &sig.asyncness {
+            return Err(syn::Error::new_spanned(asyncness, "#[trace] does not support async fn"));
+        }
+        if let Some(constness) = &sig.constness {
+            return Err(syn::Error::new_spanned(constness, "#[trace] does not support const fn"));
+        }
+
+        // self 和不是简单名字的参数（比如 (a, b): (i32, i32)）不显示
+        let args = sig.inputs.iter().filter_map(|arg| match arg {
+            FnArg::Typed(typed) => match &*typed.pat {
+                Pat::Ident(pat) if !skip.contains(&pat.ident) => Some(&pat.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        });
+        let args = args.map(|ident| {
+            let name = ident.to_string();
+            quote! { (#name, ::std::format!("{:?}", &#ident)) }
+        });
+
+        let name = sig.ident.to_string();
+        // 闭包的返回值不能写 impl Trait，这时只能让编译器推断
+        let output = match &sig.output {
+            ReturnType::Default => quote! { -> () },
+            ReturnType::Type(_, ty) if contains_impl(ty.to_token_stream()) => quote! {},
+            ReturnType::Type(_, ty) => quote! { -> #ty },
+        };
+        // 函数体放进闭包中执行，其中的 return 和 ? 只离开闭包，返回值仍然经过 exit。
+        // 闭包通过 call 以 FnOnce 调用，函数体才能像原来一样返回从参数中借出的 &mut。
+        // 返回 ! 的函数不会执行到 exit
+        Ok(quote! {
+            #(#attrs)*
+            #vis #sig {
+                let __trace_frame = ::learn_rust::call_trace::enter(#name, ::std::vec![#(#args),*]);
+                #[allow(clippy::needless_return, clippy::diverging_sub_expression)]
+                let __trace_result = ::learn_rust::call_trace::call(move || #output #block);
+                #[allow(unreachable_code)]
+                {
+                    __trace_frame.exit(&__trace_result);
+                    __trace_result
+                }
+            }
+        })
+    }
+
+    fn contains_impl(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == "impl",
+            TokenTree::Group(group) => contains_impl(group.stream()),
+            _ => false,
+        })
+    }
+}
@@ -0,0 +1,508 @@
+/*
+任意精度整数
+
+最大的原生整数是 i128/u128，超出范围的运算在调试模式下 panic，发布模式下回绕：
+
+    // let num = 4294967296i64 * 4294967296i64 * 4294967296i64;
+
+BigInt 用 “符号 + 绝对值” 保存，绝对值是一个 u32 数组，低位在前（每一位是 2^32 进制的一个数字），
+所以不会溢出，只受内存限制。除法与原生整数一样向零取整，余数的符号与被除数相同。
+ */
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    // 绝对值，低位在前，末尾没有多余的 0；零是空数组，并且不是负数
+    magnitude: Vec<u32>,
+}
+
+/// 解析字符串失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    Empty,
+    InvalidDigit { digit: char, radix: u32 },
+}
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigIntError::InvalidDigit { digit, radix } => {
+                write!(f, "invalid digit {:?} for radix {}", digit, radix)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::from(1u32)
+    }
+
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> BigInt {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        BigInt { negative, magnitude }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// 符号：-1、0 或 1
+    pub fn signum(&self) -> i32 {
+        match (self.negative, self.is_zero()) {
+            (_, true) => 0,
+            (true, _) => -1,
+            (false, _) => 1,
+        }
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.magnitude.clone())
+    }
+
+    /// 二进制位数（不含符号），零为 0
+    pub fn bit_len(&self) -> u64 {
+        match self.magnitude.last() {
+            Some(top) => self.magnitude.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// 乘方，使用平方求幂
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// 同时求商和余数，除数为 0 时返回 None
+    pub fn checked_div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (q, r) = div_rem_magnitude(&self.magnitude, &other.magnitude);
+        Some((
+            BigInt::from_parts(self.negative != other.negative, q),
+            BigInt::from_parts(self.negative, r),
+        ))
+    }
+
+    /// 与 `i128::div_rem` 类似，除数为 0 时 panic
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        self.checked_div_rem(other).expect("attempt to divide by zero")
+    }
+
+    pub fn checked_div(&self, other: &BigInt) -> Option<BigInt> {
+        self.checked_div_rem(other).map(|(q, _)| q)
+    }
+
+    pub fn checked_rem(&self, other: &BigInt) -> Option<BigInt> {
+        self.checked_div_rem(other).map(|(_, r)| r)
+    }
+
+    /// 按指定进制解析，进制范围 2 ~ 36，可以带正负号，数字之间可以有 `_`
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36, got {}", radix);
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() || digits.starts_with('_') {
+            return Err(ParseBigIntError::Empty);
+        }
+
+        let mut magnitude = Vec::new();
+        for c in digits.chars().filter(|c| *c != '_') {
+            let digit = c.to_digit(radix).ok_or(ParseBigIntError::InvalidDigit { digit: c, radix })?;
+            mul_add_small(&mut magnitude, radix, digit);
+        }
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+
+    /// 按指定进制输出，字母用小写，负数带 `-`
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36, got {}", radix);
+        let digits = magnitude_to_str_radix(&self.magnitude, radix);
+        if self.negative {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    /// 能放进 i128 时返回对应的值
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.to_u128_magnitude()?;
+        if self.negative {
+            // i128::MIN 的绝对值比 i128::MAX 大 1
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// 能放进 u128 时返回对应的值
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative {
+            return None;
+        }
+        self.to_u128_magnitude()
+    }
+
+    fn to_u128_magnitude(&self) -> Option<u128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+        Some(self.magnitude.iter().rev().fold(0u128, |acc, limb| (acc << 32) | *limb as u128))
+    }
+}
+
+// ==== 绝对值的运算 ====
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, limb) in long.iter().enumerate() {
+        let sum = *limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+// 要求 a >= b
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, limb) in a.iter().enumerate() {
+        let mut diff = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    debug_assert_eq!(0, borrow, "sub_magnitude requires a >= b");
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let t = *x as u64 * *y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+// magnitude = magnitude * factor + addend
+fn mul_add_small(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in magnitude.iter_mut() {
+        let t = *limb as u64 * factor as u64 + carry;
+        *limb = t as u32;
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+// 除以一个 u32，返回余数
+fn div_rem_small(magnitude: &mut [u32], divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in magnitude.iter_mut().rev() {
+        let cur = (rem << 32) | *limb as u64;
+        *limb = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    rem as u32
+}
+
+// 逐位的长除法：余数左移一位、带入被除数的下一位，够减就减并在商上记 1
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitude(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if b.len() == 1 {
+        let mut q = a.to_vec();
+        let r = div_rem_small(&mut q, b[0]);
+        return (q, vec![r]);
+    }
+
+    let mut q = vec![0u32; a.len()];
+    let mut r: Vec<u32> = Vec::with_capacity(b.len() + 1);
+    for bit in (0..a.len() * 32).rev() {
+        // r = r << 1 | 当前位
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in r.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            r.push(carry);
+        }
+        if cmp_magnitude(&r, b) != Ordering::Less {
+            r = sub_magnitude(&r, b);
+            while r.last() == Some(&0) {
+                r.pop();
+            }
+            q[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (q, r)
+}
+
+fn magnitude_to_str_radix(magnitude: &[u32], radix: u32) -> String {
+    if magnitude.is_empty() {
+        return "0".to_string();
+    }
+    let mut rest = magnitude.to_vec();
+    let mut digits = Vec::new();
+    while !rest.is_empty() {
+        let d = div_rem_small(&mut rest, radix);
+        digits.push(std::char::from_digit(d, radix).unwrap());
+        while rest.last() == Some(&0) {
+            rest.pop();
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+// ==== 带符号的运算 ====
+
+fn add_signed(a: &BigInt, b_negative: bool, b: &[u32]) -> BigInt {
+    if a.negative == b_negative {
+        return BigInt::from_parts(a.negative, add_magnitude(&a.magnitude, b));
+    }
+    // 符号不同，用绝对值大的减去小的，结果的符号跟随绝对值大的一方
+    match cmp_magnitude(&a.magnitude, b) {
+        Ordering::Less => BigInt::from_parts(b_negative, sub_magnitude(b, &a.magnitude)),
+        _ => BigInt::from_parts(a.negative, sub_magnitude(&a.magnitude, b)),
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        add_signed(self, other.negative, &other.magnitude)
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        add_signed(self, !other.negative, &other.magnitude)
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mul_magnitude(&self.magnitude, &other.magnitude))
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+// 按值传参的版本都转发到引用的版本
+macro_rules! forward_binop {
+    ($($imp:ident $method:ident),*) => {$(
+        impl $imp<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                self.$method(&other)
+            }
+        }
+    )*};
+}
+
+forward_binop!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            // 都是负数时，绝对值大的反而小
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// ==== 与原生整数的转换 ====
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> BigInt {
+                let mut value = value as u128;
+                let mut magnitude = Vec::new();
+                while value > 0 {
+                    magnitude.push(value as u32);
+                    value >>= 32;
+                }
+                BigInt::from_parts(false, magnitude)
+            }
+        }
+    )*};
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> BigInt {
+                let abs = BigInt::from((value as i128).unsigned_abs());
+                BigInt::from_parts(value < 0, abs.magnitude)
+            }
+        }
+    )*};
+}
+
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+from_signed!(i8, i16, i32, i64, i128, isize);
+
+// ==== 解析与输出 ====
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// 十进制，或者带 `0x` 前缀的十六进制，如 `-0xff`
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        let (sign, body) = match s.as_bytes().first() {
+            Some(b'-') | Some(b'+') => s.split_at(1),
+            _ => ("", s),
+        };
+        match body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")) {
+            // 符号只能写在 0x 前面
+            Some(hex) => match hex.chars().next() {
+                Some(digit @ ('-' | '+')) => Err(ParseBigIntError::InvalidDigit { digit, radix: 16 }),
+                _ => BigInt::from_str_radix(&format!("{}{}", sign, hex), 16),
+            },
+            None => BigInt::from_str_radix(s, 10),
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &magnitude_to_str_radix(&self.magnitude, 10))
+    }
+}
+
+impl fmt::LowerHex for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "0x", &magnitude_to_str_radix(&self.magnitude, 16))
+    }
+}
+
+impl fmt::UpperHex for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let digits = magnitude_to_str_radix(&self.magnitude, 16).to_uppercase();
+        f.pad_integral(!self.negative, "0x", &digits)
+    }
+}
@@ -0,0 +1,349 @@
+/*
+位运算
+
+    0011 AND 0101 is 0001
+    0011 OR  0101 is 0111
+    0011 XOR 0101 is 0110
+
+位运算的结果与类型的位数和有无符号有关：
+- `!a` 会翻转类型的所有位，u8 的 !0 是 255，i8 的 !0 是 -1
+- 有符号数的 `>>` 是算术右移，会用符号位填充高位
+- 移位的位数必须小于类型的位数，否则调试模式下 panic，
+  发布模式下实际移动的位数是 `n % BITS`（与 wrapping_shl 相同）
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+use crate::int_type::IntType;
+use crate::table::{Align, Table};
+
+/// 某个整数类型的一个值，用 u128 保存它的位模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operand {
+    pub ty: IntType,
+    pub raw: u128,
+}
+
+/// 解析操作数失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperandError {
+    /// 不是合法的数字
+    Invalid(String),
+    /// 数值超出了类型的范围
+    OutOfRange { input: String, ty: IntType },
+}
+
+impl Display for OperandError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            OperandError::Invalid(s) => write!(f, "`{}` is not a valid integer", s),
+            OperandError::OutOfRange { input, ty } => {
+                write!(f, "`{}` does not fit in {} ({}..={})", input, ty, ty.min(), ty.max())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OperandError {}
+
+impl Operand {
+    /// 用位模式创建，超出类型位数的高位会被丢弃
+    pub fn from_raw(ty: IntType, raw: u128) -> Operand {
+        Operand { ty, raw: raw & ty.mask() }
+    }
+
+    /// 用有符号数创建，负数会转为补码
+    pub fn from_i128(ty: IntType, value: i128) -> Operand {
+        Operand::from_raw(ty, value as u128)
+    }
+
+    /// 解析操作数，写法与 Rust 字面量相同，可以带下划线，也可以写 `MIN`、`MAX`
+    ///
+    /// 十进制必须在类型的范围内；`0x`、`0o`、`0b` 前缀的写法表示位模式，
+    /// 只要不超过类型的位数即可，所以 `0xff` 作为 i8 就是 -1
+    pub fn parse(ty: IntType, input: &str) -> Result<Operand, OperandError> {
+        let invalid = || OperandError::Invalid(input.to_string());
+        let out_of_range = || OperandError::OutOfRange { input: input.to_string(), ty };
+
+        match input {
+            "MIN" => return Ok(Operand::from_i128(ty, ty.min())),
+            "MAX" => return Ok(Operand::from_raw(ty, ty.max())),
+            _ => {}
+        }
+
+        let cleaned: String = input.chars().filter(|c| *c != '_').collect();
+        let (negative, digits) = match cleaned.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, cleaned.as_str()),
+        };
+        let (radix, digits) = match digits.get(..2) {
+            Some("0x") | Some("0X") => (16, &digits[2..]),
+            Some("0o") | Some("0O") => (8, &digits[2..]),
+            Some("0b") | Some("0B") => (2, &digits[2..]),
+            _ => (10, digits),
+        };
+        if digits.is_empty() || digits.starts_with('+') {
+            return Err(invalid());
+        }
+        let magnitude = u128::from_str_radix(digits, radix).map_err(|_| invalid())?;
+
+        if negative {
+            // 负数的绝对值最大是 2^(BITS-1)
+            if !ty.is_signed() || magnitude > ty.min().unsigned_abs() {
+                return Err(out_of_range());
+            }
+            return Ok(Operand::from_raw(ty, magnitude.wrapping_neg()));
+        }
+        let limit = if radix == 10 { ty.max() } else { ty.mask() };
+        if magnitude > limit {
+            return Err(out_of_range());
+        }
+        Ok(Operand::from_raw(ty, magnitude))
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.ty.bits()
+    }
+
+    /// 按类型解释的十进制值
+    pub fn decimal(&self) -> String {
+        self.ty.format_decimal(self.raw)
+    }
+
+    /// 补齐到类型位数的二进制，每 4 位用 `_` 分隔
+    pub fn binary(&self) -> String {
+        let digits = format!("{:0width$b}", self.raw, width = self.bits() as usize);
+        group(&digits, 4)
+    }
+
+    /// 补齐到类型位数的十六进制
+    pub fn hex(&self) -> String {
+        format!("0x{:0width$x}", self.raw, width = self.bits() as usize / 4)
+    }
+
+    /// 作为移位位数使用时的值，负数返回 None。`a << b` 按 b 完整的值检查溢出，不会先截断
+    pub fn shift_amount(&self) -> Option<u128> {
+        (!self.is_negative()).then_some(self.raw)
+    }
+
+    /// 作为 bits 位整数的移位位数时是否溢出：负数，或者不小于 bits
+    pub fn shift_overflows(&self, bits: u32) -> bool {
+        self.shift_amount().is_none_or(|n| n >= bits as u128)
+    }
+
+    /// 是否为负数
+    pub fn is_negative(&self) -> bool {
+        self.ty.is_signed() && self.raw >> (self.bits() - 1) == 1
+    }
+
+    /// 与 `checked_shl` 相同，位数不小于类型位数时返回 None
+    pub fn checked_shl(self, n: u32) -> Option<Operand> {
+        if n >= self.bits() {
+            return None;
+        }
+        Some(Operand::from_raw(self.ty, self.raw << n))
+    }
+
+    /// 与 `checked_shr` 相同，有符号数做算术右移
+    pub fn checked_shr(self, n: u32) -> Option<Operand> {
+        if n >= self.bits() {
+            return None;
+        }
+        if self.is_negative() {
+            // 先符号扩展到 128 位再移，高位会被填成 1
+            let extended = (self.raw | !self.ty.mask()) as i128;
+            return Some(Operand::from_raw(self.ty, (extended >> n) as u128));
+        }
+        Some(Operand::from_raw(self.ty, self.raw >> n))
+    }
+
+    /// 与 `wrapping_shl` 相同，实际移动 `n % BITS` 位
+    pub fn wrapping_shl(self, n: u32) -> Operand {
+        self.checked_shl(n % self.bits()).unwrap()
+    }
+
+    /// 与 `wrapping_shr` 相同，实际移动 `n % BITS` 位
+    pub fn wrapping_shr(self, n: u32) -> Operand {
+        self.checked_shr(n % self.bits()).unwrap()
+    }
+
+    pub fn rotate_left(self, n: u32) -> Operand {
+        let n = n % self.bits();
+        if n == 0 {
+            return self;
+        }
+        Operand::from_raw(self.ty, (self.raw << n) | (self.raw >> (self.bits() - n)))
+    }
+
+    pub fn rotate_right(self, n: u32) -> Operand {
+        self.rotate_left(self.bits() - n % self.bits())
+    }
+
+    pub fn count_ones(self) -> u32 {
+        self.raw.count_ones()
+    }
+
+    pub fn leading_zeros(self) -> u32 {
+        self.raw.leading_zeros() - (128 - self.bits())
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(&self.decimal())
+    }
+}
+
+impl BitAnd for Operand {
+    type Output = Operand;
+
+    fn bitand(self, other: Operand) -> Operand {
+        Operand::from_raw(self.ty, self.raw & other.raw)
+    }
+}
+
+impl BitOr for Operand {
+    type Output = Operand;
+
+    fn bitor(self, other: Operand) -> Operand {
+        Operand::from_raw(self.ty, self.raw | other.raw)
+    }
+}
+
+impl BitXor for Operand {
+    type Output = Operand;
+
+    fn bitxor(self, other: Operand) -> Operand {
+        Operand::from_raw(self.ty, self.raw ^ other.raw)
+    }
+}
+
+impl Not for Operand {
+    type Output = Operand;
+
+    fn not(self) -> Operand {
+        Operand::from_raw(self.ty, !self.raw)
+    }
+}
+
+fn group(digits: &str, size: usize) -> String {
+    let chars: Vec<char> = digits.chars().collect();
+    let groups: Vec<String> = chars.chunks(size).map(|c| c.iter().collect()).collect();
+    groups.join("_")
+}
+
+/// 表格中的一行运算结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// 运算结果是同类型的值
+    Value(Operand),
+    /// 运算结果是一个计数，如 count_ones
+    Count(u32),
+    /// 移位位数过大：调试模式下 panic，发布模式下得到 wrapping 的结果
+    Overflow { wrapped: Operand },
+}
+
+/// 对 a 和 b 做所有的位运算，移位和循环移位的位数取 b
+pub fn operations(a: Operand, b: Operand) -> Vec<(String, Outcome)> {
+    // 溢出按 b 完整的值判断；发布模式下实际移动 b 的低位表示的位数，即补码的 b % BITS
+    let overflow = b.shift_overflows(a.bits());
+    let n = (b.raw % a.bits() as u128) as u32;
+    let shift = |value: Operand| match overflow {
+        true => Outcome::Overflow { wrapped: value },
+        false => Outcome::Value(value),
+    };
+
+    vec![
+        ("a".to_string(), Outcome::Value(a)),
+        ("b".to_string(), Outcome::Value(b)),
+        ("a & b".to_string(), Outcome::Value(a & b)),
+        ("a | b".to_string(), Outcome::Value(a | b)),
+        ("a ^ b".to_string(), Outcome::Value(a ^ b)),
+        ("!a".to_string(), Outcome::Value(!a)),
+        ("!b".to_string(), Outcome::Value(!b)),
+        ("a << b".to_string(), shift(a.wrapping_shl(n))),
+        ("a >> b".to_string(), shift(a.wrapping_shr(n))),
+        ("a.rotate_left(b)".to_string(), Outcome::Value(a.rotate_left(n))),
+        ("a.rotate_right(b)".to_string(), Outcome::Value(a.rotate_right(n))),
+        ("a.count_ones()".to_string(), Outcome::Count(a.count_ones())),
+        ("b.count_ones()".to_string(), Outcome::Count(b.count_ones())),
+        ("a.leading_zeros()".to_string(), Outcome::Count(a.leading_zeros())),
+        ("b.leading_zeros()".to_string(), Outcome::Count(b.leading_zeros())),
+    ]
+}
+
+/// 移位位数不小于类型位数时各个移位方法的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShiftCase {
+    pub amount: u128,
+    pub checked_shl: Option<Operand>,
+    pub wrapping_shl: Operand,
+    pub checked_shr: Option<Operand>,
+    pub wrapping_shr: Operand,
+}
+
+/// 用位数 BITS - 1、BITS、BITS + 1 以及 extra（如果它也溢出）移动 a
+pub fn shift_cases(a: Operand, extra: Option<u128>) -> Vec<ShiftCase> {
+    let bits = a.bits() as u128;
+    let mut amounts = vec![bits - 1, bits, bits + 1];
+    if let Some(extra) = extra.filter(|n| *n >= bits && !amounts.contains(n)) {
+        amounts.push(extra);
+    }
+    amounts
+        .into_iter()
+        .map(|amount| {
+            // 比 u32::MAX 还大的位数同样是溢出
+            let checked = u32::try_from(amount).ok();
+            let wrapped = (amount % bits) as u32;
+            ShiftCase {
+                amount,
+                checked_shl: checked.and_then(|n| a.checked_shl(n)),
+                wrapping_shl: a.wrapping_shl(wrapped),
+                checked_shr: checked.and_then(|n| a.checked_shr(n)),
+                wrapping_shr: a.wrapping_shr(wrapped),
+            }
+        })
+        .collect()
+}
+
+/// 生成 `learn_rust bitwise` 命令的输出
+pub fn render(a: Operand, b: Operand) -> String {
+    let bits = a.bits();
+    let mut out = format!("type {} ({} bits), a = {}, b = {}\n\n", a.ty, bits, a, b);
+
+    let mut table = Table::new(["expr", "binary", "hex", "decimal"]).align(3, Align::Right);
+    for (expr, outcome) in operations(a, b) {
+        match outcome {
+            Outcome::Value(v) => table.row([expr, v.binary(), v.hex(), v.decimal()]),
+            Outcome::Count(c) => table.row([expr, String::new(), String::new(), c.to_string()]),
+            Outcome::Overflow { wrapped } => table.row([
+                expr,
+                format!("overflow: panics in debug, release gives {}", wrapped.binary()),
+                wrapped.hex(),
+                wrapped.decimal(),
+            ]),
+        }
+    }
+    out.push_str(&table.to_string());
+
+    out.push_str(&format!(
+        "\nshift amount >= {} bits: `a << n` panics in debug builds (attempt to shift left with overflow),\n\
+         release builds and wrapping_shl/wrapping_shr shift by n % {} instead\n\n",
+        bits, bits
+    ));
+    let show = |v: Option<Operand>| v.map_or("None".to_string(), |v| v.hex());
+    let mut table = Table::new(["n", "checked_shl", "wrapping_shl", "checked_shr", "wrapping_shr"])
+        .align(0, Align::Right);
+    for case in shift_cases(a, b.shift_amount()) {
+        table.row([
+            case.amount.to_string(),
+            show(case.checked_shl),
+            case.wrapping_shl.hex(),
+            show(case.checked_shr),
+            case.wrapping_shr.hex(),
+        ]);
+    }
+    out.push_str(&table.to_string());
+    out
+}
@@ -0,0 +1,141 @@
+/*
+函数调用跟踪
+
+在函数上标注 `#[trace]`，打开 cargo 的 `trace` feature 后，每次调用都会记录：
+
+    > add(x: 2, y: 3)
+    < add = 5 (1.2µs)
+
+进入时是用 Debug 格式化的参数，离开时是返回值和耗时，函数中再调用被跟踪的函数时按嵌套深度缩进。
+所以参数和返回值的类型都要实现 Debug，不需要显示的参数可以跳过：`#[trace(skip(data))]`，
+方法的 self 总是跳过。
+
+    cargo test --features trace --test test_function -- --nocapture
+
+`#[trace]` 由 learn_rust_macros 这个过程宏（proc-macro）crate 提供。它把函数体包装成一个闭包，
+在闭包前后调用这里的 `enter` 与 `Frame::exit`，所以函数体中的 return 和 `?` 仍然按原来的方式工作。
+没有打开 feature 时它原样返回函数，不会产生任何代码，也没有运行时的开销。
+生成的代码通过 `::learn_rust::call_trace` 调用这里的函数，所以只能在依赖 learn_rust 的 crate 中使用，比如 tests 下的测试。
+
+记录默认打印到标准错误；`capture` 可以在当前线程中把记录收集起来，而不是打印，方便测试。
+深度和收集的记录都是每个线程各自的（thread_local）。
+ */
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::time::{Duration, Instant};
+
+pub use learn_rust_macros::trace;
+
+/// 一条调用记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// 进入函数，args 是参数名和 Debug 格式化的值
+    Enter { function: &'static str, depth: usize, args: Vec<(&'static str, String)> },
+    /// 函数返回，value 是 Debug 格式化的返回值
+    Exit { function: &'static str, depth: usize, value: String, elapsed: Duration },
+    /// 函数因为 panic 而离开
+    Unwind { function: &'static str, depth: usize, elapsed: Duration },
+}
+
+impl Event {
+    pub fn function(&self) -> &'static str {
+        match self {
+            Event::Enter { function, .. } | Event::Exit { function, .. } | Event::Unwind { function, .. } => function,
+        }
+    }
+
+    /// 嵌套深度，最外层的调用为 0
+    pub fn depth(&self) -> usize {
+        match self {
+            Event::Enter { depth, .. } | Event::Exit { depth, .. } | Event::Unwind { depth, .. } => *depth,
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let indent = "  ".repeat(self.depth());
+        match self {
+            Event::Enter { function, args, .. } => {
+                let args: Vec<String> = args.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+                write!(f, "{}> {}({})", indent, function, args.join(", "))
+            }
+            Event::Exit { function, value, elapsed, .. } => write!(f, "{}< {} = {} ({:?})", indent, function, value, elapsed),
+            Event::Unwind { function, elapsed, .. } => write!(f, "{}< {} panicked ({:?})", indent, function, elapsed),
+        }
+    }
+}
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    // capture 正在收集时为 Some
+    static CAPTURED: RefCell<Option<Vec<Event>>> = const { RefCell::new(None) };
+}
+
+fn record(event: Event) {
+    let event = CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(events) => {
+            events.push(event);
+            None
+        }
+        None => Some(event),
+    });
+    if let Some(event) = event {
+        eprintln!("{}", event);
+    }
+}
+
+/// 执行 f，返回它的结果和其间当前线程中的所有调用记录，记录不再打印
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Event>) {
+    let outer = CAPTURED.replace(Some(Vec::new()));
+    // f panic 时恢复外层的状态
+    let restore = crate::guard::guard(|| {
+        CAPTURED.set(outer);
+    });
+    let result = f();
+    let events = CAPTURED.take().unwrap_or_default();
+    drop(restore);
+    (result, events)
+}
+
+/// 一次正在进行的调用，由 `#[trace]` 生成的代码使用
+#[derive(Debug)]
+pub struct Frame {
+    function: &'static str,
+    depth: usize,
+    start: Instant,
+    exited: bool,
+}
+
+/// 记录进入函数，深度加 1
+pub fn enter(function: &'static str, args: Vec<(&'static str, String)>) -> Frame {
+    let depth = DEPTH.get();
+    DEPTH.set(depth + 1);
+    record(Event::Enter { function, depth, args });
+    Frame { function, depth, start: Instant::now(), exited: false }
+}
+
+/// 执行 `#[trace]` 包装的函数体
+pub fn call<R>(body: impl FnOnce() -> R) -> R {
+    body()
+}
+
+impl Frame {
+    /// 记录返回值
+    pub fn exit(mut self, value: &dyn Debug) {
+        self.exited = true;
+        let elapsed = self.start.elapsed();
+        record(Event::Exit { function: self.function, depth: self.depth, value: format!("{:?}", value), elapsed });
+    }
+}
+
+impl Drop for Frame {
+    // 正常返回和 panic 都要恢复深度；没有经过 exit 的就是 panic 了
+    fn drop(&mut self) {
+        DEPTH.set(self.depth);
+        if !self.exited {
+            record(Event::Unwind { function: self.function, depth: self.depth, elapsed: self.start.elapsed() });
+        }
+    }
+}
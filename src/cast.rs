@@ -0,0 +1,468 @@
+/*
+`as` 类型转换
+
+    assert_eq!(5, add(2, a as i32));
+    let num = (64 as f64) / (5 as f64);
+
+值能放进目标类型时 `as` 不会改变它，放不下时 `as` 也不会报错，而是：
+- 整数转更窄的整数：截断，只保留低位，300 as u8 == 44
+- 相同位数的有符号与无符号互转：按位重新解释，-1i8 as u8 == 255
+- 负数转更宽的无符号数：先符号扩展再重新解释，-1i8 as u32 == 4294967295
+- 浮点数转整数：丢弃小数部分，超出范围时取最大或最小值（饱和），NaN 转为 0
+- 整数转浮点数、f64 转 f32：取最接近的值，可能丢失精度，超出 f32 范围时得到无穷大
+
+不想丢失信息时应该用 From（一定成功）或 TryFrom（放不下时返回错误）：
+
+    let x: i32 = i32::from(3i8);
+    let y: Result<u8, _> = u8::try_from(300i32);
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::bitwise::Operand;
+use crate::decimal::exact_decimal;
+use crate::int_type::{IntType, UnknownType};
+use crate::table::Table;
+
+/// 数值类型：所有整数类型与两种浮点类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumType {
+    Int(IntType),
+    F32,
+    F64,
+}
+
+impl NumType {
+    pub const ALL: [NumType; 14] = [
+        NumType::Int(IntType::I8),
+        NumType::Int(IntType::I16),
+        NumType::Int(IntType::I32),
+        NumType::Int(IntType::I64),
+        NumType::Int(IntType::I128),
+        NumType::Int(IntType::Isize),
+        NumType::Int(IntType::U8),
+        NumType::Int(IntType::U16),
+        NumType::Int(IntType::U32),
+        NumType::Int(IntType::U64),
+        NumType::Int(IntType::U128),
+        NumType::Int(IntType::Usize),
+        NumType::F32,
+        NumType::F64,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NumType::Int(t) => t.name(),
+            NumType::F32 => "f32",
+            NumType::F64 => "f64",
+        }
+    }
+
+    pub fn is_float(self) -> bool {
+        matches!(self, NumType::F32 | NumType::F64)
+    }
+}
+
+impl Display for NumType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for NumType {
+    type Err = UnknownType;
+
+    fn from_str(s: &str) -> Result<NumType, UnknownType> {
+        match s {
+            "f32" => Ok(NumType::F32),
+            "f64" => Ok(NumType::F64),
+            _ => s.parse().map(NumType::Int),
+        }
+    }
+}
+
+/// 某个数值类型的一个值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Isize(isize),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+    F32(f32),
+    F64(f64),
+}
+
+// 对 Value 的每个变体执行同一段代码，$v 绑定到里面的值
+macro_rules! each_value {
+    ($value:expr, $v:ident => $body:expr) => {
+        match $value {
+            Value::I8($v) => $body,
+            Value::I16($v) => $body,
+            Value::I32($v) => $body,
+            Value::I64($v) => $body,
+            Value::I128($v) => $body,
+            Value::Isize($v) => $body,
+            Value::U8($v) => $body,
+            Value::U16($v) => $body,
+            Value::U32($v) => $body,
+            Value::U64($v) => $body,
+            Value::U128($v) => $body,
+            Value::Usize($v) => $body,
+            Value::F32($v) => $body,
+            Value::F64($v) => $body,
+        }
+    };
+}
+
+// 用 `as` 把 $v 转换为目标类型
+macro_rules! cast_as {
+    ($v:expr, $target:expr) => {
+        match $target {
+            NumType::Int(IntType::I8) => Value::I8($v as i8),
+            NumType::Int(IntType::I16) => Value::I16($v as i16),
+            NumType::Int(IntType::I32) => Value::I32($v as i32),
+            NumType::Int(IntType::I64) => Value::I64($v as i64),
+            NumType::Int(IntType::I128) => Value::I128($v as i128),
+            NumType::Int(IntType::Isize) => Value::Isize($v as isize),
+            NumType::Int(IntType::U8) => Value::U8($v as u8),
+            NumType::Int(IntType::U16) => Value::U16($v as u16),
+            NumType::Int(IntType::U32) => Value::U32($v as u32),
+            NumType::Int(IntType::U64) => Value::U64($v as u64),
+            NumType::Int(IntType::U128) => Value::U128($v as u128),
+            NumType::Int(IntType::Usize) => Value::Usize($v as usize),
+            NumType::F32 => Value::F32($v as f32),
+            NumType::F64 => Value::F64($v as f64),
+        }
+    };
+}
+
+/// 解析数值失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseValueError {
+    pub input: String,
+    pub ty: NumType,
+}
+
+impl Display for ParseValueError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "`{}` is not a valid {}", self.input, self.ty)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+impl Value {
+    pub fn ty(&self) -> NumType {
+        match self {
+            Value::I8(_) => NumType::Int(IntType::I8),
+            Value::I16(_) => NumType::Int(IntType::I16),
+            Value::I32(_) => NumType::Int(IntType::I32),
+            Value::I64(_) => NumType::Int(IntType::I64),
+            Value::I128(_) => NumType::Int(IntType::I128),
+            Value::Isize(_) => NumType::Int(IntType::Isize),
+            Value::U8(_) => NumType::Int(IntType::U8),
+            Value::U16(_) => NumType::Int(IntType::U16),
+            Value::U32(_) => NumType::Int(IntType::U32),
+            Value::U64(_) => NumType::Int(IntType::U64),
+            Value::U128(_) => NumType::Int(IntType::U128),
+            Value::Usize(_) => NumType::Int(IntType::Usize),
+            Value::F32(_) => NumType::F32,
+            Value::F64(_) => NumType::F64,
+        }
+    }
+
+    /// 由整数的位模式创建
+    pub fn from_bits(ty: IntType, raw: u128) -> Value {
+        cast_as!(raw, NumType::Int(ty))
+    }
+
+    /// 按类型解析，整数可以写 `MIN`、`MAX`，浮点数可以写 `NaN`、`inf`、`MIN`、`MAX`
+    pub fn parse(ty: NumType, input: &str) -> Result<Value, ParseValueError> {
+        let error = || ParseValueError { input: input.to_string(), ty };
+        match ty {
+            NumType::Int(t) => Operand::parse(t, input).map(|o| Value::from_bits(t, o.raw)).map_err(|_| error()),
+            NumType::F32 => match input {
+                "MIN" => Ok(Value::F32(f32::MIN)),
+                "MAX" => Ok(Value::F32(f32::MAX)),
+                _ => input.parse().map(Value::F32).map_err(|_| error()),
+            },
+            NumType::F64 => match input {
+                "MIN" => Ok(Value::F64(f64::MIN)),
+                "MAX" => Ok(Value::F64(f64::MAX)),
+                _ => input.parse().map(Value::F64).map_err(|_| error()),
+            },
+        }
+    }
+
+    /// 用 `as` 转换为目标类型
+    pub fn cast(self, target: NumType) -> Value {
+        each_value!(self, v => cast_as!(v, target))
+    }
+
+    pub fn is_nan(&self) -> bool {
+        match self {
+            Value::F32(v) => v.is_nan(),
+            Value::F64(v) => v.is_nan(),
+            _ => false,
+        }
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        match self {
+            Value::F32(v) => v.is_infinite(),
+            Value::F64(v) => v.is_infinite(),
+            _ => false,
+        }
+    }
+
+    /// 值的精确十进制表示，用来比较转换前后是否相等
+    pub fn exact(&self) -> String {
+        match *self {
+            Value::F32(v) if !v.is_finite() => v.to_string(),
+            Value::F64(v) if !v.is_finite() => v.to_string(),
+            Value::F32(v) => exact_decimal(v as f64),
+            Value::F64(v) => exact_decimal(v),
+            _ => self.to_string(),
+        }
+    }
+
+    /// 写成 Rust 字面量的形式，如 `-1i8`、`2.7f64`、`f32::NAN`
+    pub fn literal(&self) -> String {
+        let ty = self.ty();
+        match *self {
+            Value::F32(v) if v.is_nan() => format!("{}::NAN", ty),
+            Value::F64(v) if v.is_nan() => format!("{}::NAN", ty),
+            Value::F32(v) if v.is_infinite() => format!("{}::{}INFINITY", ty, if v < 0.0 { "NEG_" } else { "" }),
+            Value::F64(v) if v.is_infinite() => format!("{}::{}INFINITY", ty, if v < 0.0 { "NEG_" } else { "" }),
+            Value::F32(v) => format!("{:?}{}", v, ty),
+            Value::F64(v) => format!("{:?}{}", v, ty),
+            _ => format!("{}{}", self, ty),
+        }
+    }
+
+    /// 课程里用到的典型取值，包括各种边界值
+    pub fn samples(ty: NumType) -> Vec<Value> {
+        let parse = |inputs: &[&str]| -> Vec<Value> {
+            inputs.iter().filter_map(|s| Value::parse(ty, s).ok()).collect()
+        };
+        match ty {
+            NumType::Int(t) if t.is_signed() => parse(&["MIN", "-1", "0", "300", "MAX"]),
+            NumType::Int(t) => {
+                // 最高位为 1 的最小值，转为同宽度的有符号数时会变成负数
+                let high_bit = (1u128 << (t.bits() - 1)).to_string();
+                parse(&["0", "300", &high_bit, "MAX"])
+            }
+            NumType::F32 => parse(&["NaN", "inf", "-inf", "-1.5", "2.7", "300.5", "1e10", "MAX"]),
+            NumType::F64 => parse(&["NaN", "inf", "-inf", "-1.5", "0.1", "2.7", "300.5", "16777217", "1e10", "MAX"]),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        each_value!(self, v => Display::fmt(v, f))
+    }
+}
+
+/// `as` 转换对值做了什么
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastKind {
+    /// 值没有变化
+    Exact,
+    /// 整数转更窄的整数，高位被丢弃
+    Truncated,
+    /// 有符号与无符号之间按位重新解释（必要时先符号扩展）
+    Reinterpreted,
+    /// 浮点数转整数，丢弃了小数部分
+    FractionDropped,
+    /// 浮点数转整数时超出范围，取了最大或最小值
+    Saturated,
+    /// NaN 转整数得到 0
+    NanToZero,
+    /// 转为浮点数时舍入到了最接近的可表示值
+    Rounded,
+    /// 超出 f32 的范围，变成了无穷大
+    Infinity,
+}
+
+impl CastKind {
+    pub fn describe(self) -> &'static str {
+        match self {
+            CastKind::Exact => "exact",
+            CastKind::Truncated => "truncated to the low bits",
+            CastKind::Reinterpreted => "bits reinterpreted as another sign",
+            CastKind::FractionDropped => "fraction dropped (rounds toward zero)",
+            CastKind::Saturated => "saturated to the target's MIN/MAX",
+            CastKind::NanToZero => "NaN becomes 0",
+            CastKind::Rounded => "rounded to the nearest representable value",
+            CastKind::Infinity => "out of range, became infinity",
+        }
+    }
+}
+
+/// 判断 value 用 `as` 转换为 result 时发生了什么
+pub fn classify(value: Value, result: Value) -> CastKind {
+    let (from, to) = (value.ty(), result.ty());
+    if value.is_nan() {
+        return if to.is_float() { CastKind::Exact } else { CastKind::NanToZero };
+    }
+    if value.exact() == result.exact() {
+        return CastKind::Exact;
+    }
+    match (from, to) {
+        (NumType::Int(s), NumType::Int(t)) if t.bits() < s.bits() => CastKind::Truncated,
+        (NumType::Int(_), NumType::Int(_)) => CastKind::Reinterpreted,
+        (_, NumType::Int(_)) => {
+            // 丢弃小数部分后与结果相同，否则就是超出范围发生了饱和
+            let truncated = match value {
+                Value::F32(v) => Value::F32(v.trunc()),
+                Value::F64(v) => Value::F64(v.trunc()),
+                _ => unreachable!(),
+            };
+            if truncated.exact() == result.exact() {
+                CastKind::FractionDropped
+            } else {
+                CastKind::Saturated
+            }
+        }
+        _ if result.is_infinite() => CastKind::Infinity,
+        _ => CastKind::Rounded,
+    }
+}
+
+/// 不丢失信息的转换方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lossless {
+    /// 相同类型
+    Identity,
+    /// 实现了 From，转换一定成功
+    From,
+    /// 只实现了 TryFrom，值放不下时返回错误
+    TryFrom,
+    /// 标准库没有提供，只能用 `as` 或者自己检查
+    None,
+}
+
+impl Lossless {
+    /// 矩阵中使用的简写
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Lossless::Identity => "=",
+            Lossless::From => "From",
+            Lossless::TryFrom => "Try",
+            Lossless::None => "-",
+        }
+    }
+}
+
+/// 标准库为 from -> to 提供的无损转换
+pub fn lossless(from: NumType, to: NumType) -> Lossless {
+    if from == to {
+        return Lossless::Identity;
+    }
+    match (from, to) {
+        (NumType::Int(s), NumType::Int(t)) if int_from_exists(s, t) => Lossless::From,
+        (NumType::Int(_), NumType::Int(_)) => Lossless::TryFrom,
+        // f32 有 24 位有效数字，f64 有 53 位，能放下所有 16 位、32 位整数
+        (NumType::Int(s), NumType::F32) if !is_size(s) && s.bits() <= 16 => Lossless::From,
+        (NumType::Int(s), NumType::F64) if !is_size(s) && s.bits() <= 32 => Lossless::From,
+        (NumType::F32, NumType::F64) => Lossless::From,
+        _ => Lossless::None,
+    }
+}
+
+fn is_size(ty: IntType) -> bool {
+    matches!(ty, IntType::Isize | IntType::Usize)
+}
+
+// 标准库只为 isize、usize 实现了来自 16 位以内类型的 From，
+// 因为它们在 16 位平台上也要成立；isize、usize 自身只有 TryFrom
+fn int_from_exists(from: IntType, to: IntType) -> bool {
+    if is_size(from) {
+        return false;
+    }
+    let to_bits = if is_size(to) { 16 } else { to.bits() };
+    match (from.is_signed(), to.is_signed()) {
+        (true, true) | (false, false) => from.bits() <= to_bits,
+        (false, true) => from.bits() < to_bits,
+        (true, false) => false,
+    }
+}
+
+/// 一次转换的完整结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    pub value: Value,
+    pub result: Value,
+    pub kind: CastKind,
+    pub lossless: Lossless,
+}
+
+impl Cast {
+    pub fn new(value: Value, target: NumType) -> Cast {
+        let result = value.cast(target);
+        Cast { value, result, kind: classify(value, result), lossless: lossless(value.ty(), target) }
+    }
+
+    /// 等价的 From / TryFrom 写法及其结果，没有无损转换时返回 None
+    pub fn lossless_call(&self) -> Option<String> {
+        let to = self.result.ty();
+        let call = |name: &str| format!("{}::{}({})", to, name, self.value.literal());
+        match self.lossless {
+            Lossless::Identity => None,
+            Lossless::From => Some(format!("{} == {}", call("from"), self.result)),
+            Lossless::TryFrom if self.kind == CastKind::Exact => Some(format!("{} == Ok({})", call("try_from"), self.result)),
+            Lossless::TryFrom => Some(format!("{} == Err(TryFromIntError(()))", call("try_from"))),
+            Lossless::None => None,
+        }
+    }
+}
+
+/// 所有类型两两之间的无损转换矩阵
+pub fn render_matrix() -> String {
+    let mut headers = vec!["from \\ to".to_string()];
+    headers.extend(NumType::ALL.iter().map(|t| t.to_string()));
+    let mut table = Table::new(headers);
+    for from in NumType::ALL {
+        let mut row = vec![from.to_string()];
+        row.extend(NumType::ALL.iter().map(|to| lossless(from, *to).symbol().to_string()));
+        table.row(row);
+    }
+    format!(
+        "lossless alternatives to `as` (From: always succeeds, Try: TryFrom, -: only `as`)\n\n{}\n\
+         run `learn_rust cast <from> [to] [value]` to see what `as` does with edge values\n",
+        table
+    )
+}
+
+/// value 转换为每个目标类型（或指定的 target）的结果
+pub fn render_casts(values: &[Value], target: Option<NumType>) -> String {
+    let mut table = Table::new(["value", "as", "result", "what happened", "lossless alternative"]);
+    for value in values {
+        let targets: Vec<NumType> = match target {
+            Some(t) => vec![t],
+            None => NumType::ALL.iter().copied().filter(|t| *t != value.ty()).collect(),
+        };
+        for to in targets {
+            let cast = Cast::new(*value, to);
+            table.row([
+                value.literal(),
+                to.to_string(),
+                // 浮点数用精确的十进制显示，-2147483648f32 的 Display 是 -2147483600
+                cast.result.exact(),
+                cast.kind.describe().to_string(),
+                cast.lossless_call().unwrap_or_else(|| "-".to_string()),
+            ]);
+        }
+    }
+    table.to_string()
+}
@@ -0,0 +1,234 @@
+/*
+char 检查
+
+char 是 Unicode 标量值（Unicode scalar value），占 4 个字节，合法范围：
+    U+0000 ~ U+D7FF
+    U+E000 ~ U+10FFFF
+
+中间的 U+D800 ~ U+DFFF 是 UTF-16 的代理区（surrogate），只能在 UTF-16 里成对出现，
+单独拿出来不是一个字符，所以 Rust 的 char 不能表示它们。
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+use unicode_general_category::get_general_category;
+use unicode_width::UnicodeWidthChar;
+
+/// 代理区的范围
+pub const SURROGATE_RANGE: std::ops::RangeInclusive<u32> = 0xD800..=0xDFFF;
+
+/// char 能表示的最大码点
+pub const MAX_CODE_POINT: u32 = char::MAX as u32;
+
+/// 一个码点属于哪一类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodePoint {
+    /// 合法的 Unicode 标量值，可以转换为 char
+    Scalar(char),
+    /// 落在 U+D800 ~ U+DFFF 的代理码点
+    Surrogate(u32),
+    /// 超过 U+10FFFF
+    OutOfRange(u32),
+}
+
+impl CodePoint {
+    pub fn classify(value: u32) -> CodePoint {
+        match char::from_u32(value) {
+            Some(ch) => CodePoint::Scalar(ch),
+            None if SURROGATE_RANGE.contains(&value) => CodePoint::Surrogate(value),
+            None => CodePoint::OutOfRange(value),
+        }
+    }
+
+    pub fn is_surrogate(&self) -> bool {
+        matches!(self, CodePoint::Surrogate(_))
+    }
+
+    pub fn is_out_of_range(&self) -> bool {
+        matches!(self, CodePoint::OutOfRange(_))
+    }
+}
+
+/// 解析字符输入时可能出现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CharError {
+    /// 输入为空
+    Empty,
+    /// 输入了多于一个字符，又不是 U+XXXX 的形式
+    NotSingleChar(String),
+    /// U+ 后面不是合法的十六进制数
+    InvalidHex(String),
+    /// 代理码点，不是 Unicode 标量值
+    Surrogate(u32),
+    /// 超出 U+10FFFF
+    OutOfRange(u32),
+}
+
+impl Display for CharError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CharError::Empty => write!(f, "empty input, expected a single char or U+XXXX"),
+            CharError::NotSingleChar(s) => {
+                write!(f, "{:?} is {} chars long, expected a single char or U+XXXX", s, s.chars().count())
+            }
+            CharError::InvalidHex(s) => write!(f, "{:?} is not a valid hexadecimal code point", s),
+            CharError::Surrogate(v) => write!(
+                f,
+                "U+{:04X} is a surrogate code point: U+D800..U+DFFF is reserved for UTF-16 surrogate pairs \
+                 and is not a Unicode scalar value, so it cannot be a char",
+                v
+            ),
+            CharError::OutOfRange(v) => write!(
+                f,
+                "U+{:X} is out of range: Unicode code points stop at U+{:X}",
+                v, MAX_CODE_POINT
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CharError {}
+
+/// 把码点转换为 char，代理码点与超出范围的码点会返回对应的错误
+pub fn from_code_point(value: u32) -> Result<char, CharError> {
+    match CodePoint::classify(value) {
+        CodePoint::Scalar(ch) => Ok(ch),
+        CodePoint::Surrogate(v) => Err(CharError::Surrogate(v)),
+        CodePoint::OutOfRange(v) => Err(CharError::OutOfRange(v)),
+    }
+}
+
+/// 解析命令行输入：既可以是单个字符，如 `中`，也可以是 `U+4E2D` 这样的码点写法
+pub fn parse_char(input: &str) -> Result<char, CharError> {
+    if input.is_empty() {
+        return Err(CharError::Empty);
+    }
+
+    let hex = input.strip_prefix("U+").or_else(|| input.strip_prefix("u+"));
+    if let Some(hex) = hex {
+        // U+ 后面最多 8 位十六进制，再多就连 u32 都装不下了
+        if hex.is_empty() || hex.len() > 8 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(CharError::InvalidHex(input.to_string()));
+        }
+        let value = u32::from_str_radix(hex, 16).map_err(|_| CharError::InvalidHex(input.to_string()))?;
+        return from_code_point(value);
+    }
+
+    let mut chars = input.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ => Err(CharError::NotSingleChar(input.to_string())),
+    }
+}
+
+/// 一个字符的检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharInfo {
+    pub ch: char,
+    pub code_point: u32,
+    /// UTF-8 编码后的字节，1 ~ 4 个
+    pub utf8: Vec<u8>,
+    /// UTF-16 编码后的码元，1 ~ 2 个
+    pub utf16: Vec<u16>,
+    /// 写在 Rust 字面量里的转义形式，如 `\n`、`\u{1f60a}`
+    pub escape: String,
+    /// Unicode 一般类别的缩写，如 `Lu`、`Lo`、`Cc`
+    pub category: &'static str,
+    /// 终端里的显示宽度（东亚宽字符占 2 列），控制字符没有宽度
+    pub width: Option<usize>,
+}
+
+impl CharInfo {
+    pub fn new(ch: char) -> CharInfo {
+        let mut utf8 = [0u8; 4];
+        let mut utf16 = [0u16; 2];
+        CharInfo {
+            ch,
+            code_point: ch as u32,
+            utf8: ch.encode_utf8(&mut utf8).as_bytes().to_vec(),
+            utf16: ch.encode_utf16(&mut utf16).to_vec(),
+            escape: ch.escape_default().to_string(),
+            category: get_general_category(ch).abbreviation(),
+            width: if ch.is_control() { None } else { ch.width() },
+        }
+    }
+
+    /// 一般类别的全称
+    pub fn category_name(&self) -> &'static str {
+        category_name(self.category)
+    }
+
+    /// UTF-16 需要两个码元（代理对）时为 true，即码点大于 U+FFFF
+    pub fn needs_surrogate_pair(&self) -> bool {
+        self.utf16.len() == 2
+    }
+}
+
+impl Display for CharInfo {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let utf8: Vec<String> = self.utf8.iter().map(|b| format!("{:02X}", b)).collect();
+        let utf16: Vec<String> = self.utf16.iter().map(|u| format!("{:04X}", u)).collect();
+        // 控制字符直接打印会弄乱终端，用转义形式代替
+        let shown = if self.ch.is_control() { self.escape.clone() } else { self.ch.to_string() };
+
+        writeln!(f, "char        : {}", shown)?;
+        writeln!(f, "code point  : U+{:04X} ({})", self.code_point, self.code_point)?;
+        writeln!(f, "utf-8       : {} ({} bytes)", utf8.join(" "), self.utf8.len())?;
+        writeln!(f, "utf-16      : {} ({} units)", utf16.join(" "), self.utf16.len())?;
+        writeln!(f, "escape      : '{}'", self.escape)?;
+        writeln!(f, "category    : {} ({})", self.category, self.category_name())?;
+        match self.width {
+            Some(w) => writeln!(f, "width       : {}", w)?,
+            None => writeln!(f, "width       : none (control character)")?,
+        }
+        writeln!(f, "surrogate   : no")?;
+        write!(f, "in range    : yes (U+0000..U+D7FF or U+E000..U+10FFFF)")
+    }
+}
+
+/// 检查一个字符
+pub fn inspect(ch: char) -> CharInfo {
+    CharInfo::new(ch)
+}
+
+/// 按命令行输入检查字符，输入不合法时返回的错误里带有说明
+pub fn inspect_input(input: &str) -> Result<CharInfo, CharError> {
+    parse_char(input).map(CharInfo::new)
+}
+
+/// 一般类别缩写对应的全称
+pub fn category_name(abbreviation: &str) -> &'static str {
+    match abbreviation {
+        "Lu" => "Uppercase_Letter",
+        "Ll" => "Lowercase_Letter",
+        "Lt" => "Titlecase_Letter",
+        "Lm" => "Modifier_Letter",
+        "Lo" => "Other_Letter",
+        "Mn" => "Nonspacing_Mark",
+        "Mc" => "Spacing_Mark",
+        "Me" => "Enclosing_Mark",
+        "Nd" => "Decimal_Number",
+        "Nl" => "Letter_Number",
+        "No" => "Other_Number",
+        "Pc" => "Connector_Punctuation",
+        "Pd" => "Dash_Punctuation",
+        "Ps" => "Open_Punctuation",
+        "Pe" => "Close_Punctuation",
+        "Pi" => "Initial_Punctuation",
+        "Pf" => "Final_Punctuation",
+        "Po" => "Other_Punctuation",
+        "Sm" => "Math_Symbol",
+        "Sc" => "Currency_Symbol",
+        "Sk" => "Modifier_Symbol",
+        "So" => "Other_Symbol",
+        "Zs" => "Space_Separator",
+        "Zl" => "Line_Separator",
+        "Zp" => "Paragraph_Separator",
+        "Cc" => "Control",
+        "Cf" => "Format",
+        "Cs" => "Surrogate",
+        "Co" => "Private_Use",
+        "Cn" => "Unassigned",
+        _ => "Unknown",
+    }
+}
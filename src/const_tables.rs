@@ -0,0 +1,125 @@
+/*
+编译期计算的查找表
+
+const 的值必须在编译时确定，`const fn` 就是可以在编译时执行的函数：用在 const、static、数组长度等位置时，
+由编译器求值，结果直接写进程序里，运行时没有任何计算；用在普通表达式中时，和普通函数一样在运行时执行。
+
+const fn 中不能使用 for 循环（for 依赖 Iterator trait 的方法，目前还不是 const 的），所以下面都用 while 循环。
+编译期求值时发生 panic（比如数组越界、溢出、assert 失败）会直接变成编译错误，
+所以每张表后面的 `const _: () = assert!(...)` 在编译时就检查了表的内容。
+
+- CRC32_TABLE：CRC-32（IEEE 802.3，zlib、PNG 使用的那种）逐字节计算用的 256 项表
+- POW10_U8 .. POW10_U128：每种无符号整数能表示的所有 10 的幂
+- POPCOUNT：0..=255 每个字节中 1 的个数
+ */
+
+/// CRC-32 使用的多项式（按位反转的表示）
+pub const CRC32_POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// 按位反转表示的多项式 polynomial 的 CRC 查找表：第 i 项是字节 i 经过 8 次移位后的余数
+pub const fn crc32_table(polynomial: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ polynomial } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+pub const CRC32_TABLE: [u32; 256] = crc32_table(CRC32_POLYNOMIAL);
+
+const _: () = assert!(CRC32_TABLE[1] == 0x7707_3096 && CRC32_TABLE[255] == 0x2D02_EF8D);
+
+/// 在已有的 CRC 上继续计算 data，用于分段计算；第一段的 crc 为 0
+pub const fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    let mut i = 0;
+    while i < data.len() {
+        crc = CRC32_TABLE[((crc ^ data[i] as u32) & 0xFF) as usize] ^ (crc >> 8);
+        i += 1;
+    }
+    !crc
+}
+
+/// data 的 CRC-32
+pub const fn crc32(data: &[u8]) -> u32 {
+    crc32_update(0, data)
+}
+
+// 标准的检验值
+const _: () = assert!(crc32(b"123456789") == 0xCBF4_3926);
+
+// 生成 1, 10, 100, ... 直到 $t 能表示的最大的 10 的幂，表的长度由 ilog10 在编译时算出
+macro_rules! pow10_table {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("`", stringify!($t), "` 能表示的所有 10 的幂，第 n 项是 10^n")]
+        pub const $name: [$t; <$t>::MAX.ilog10() as usize + 1] = {
+            let mut table = [1 as $t; <$t>::MAX.ilog10() as usize + 1];
+            let mut i = 1;
+            while i < table.len() {
+                table[i] = table[i - 1] * 10;
+                i += 1;
+            }
+            table
+        };
+    };
+}
+
+pow10_table!(POW10_U8, u8);
+pow10_table!(POW10_U16, u16);
+pow10_table!(POW10_U32, u32);
+pow10_table!(POW10_U64, u64);
+pow10_table!(POW10_U128, u128);
+
+const _: () = assert!(POW10_U8.len() == 3 && POW10_U32.len() == 10 && POW10_U64.len() == 20 && POW10_U128.len() == 39);
+const _: () = assert!(POW10_U64[19] == 10_000_000_000_000_000_000);
+
+/// 10^exp，超出 u64 时为 None
+pub const fn pow10_u64(exp: u32) -> Option<u64> {
+    if (exp as usize) < POW10_U64.len() {
+        Some(POW10_U64[exp as usize])
+    } else {
+        None
+    }
+}
+
+/// n 的十进制位数，0 为 1 位
+pub const fn decimal_digits(n: u64) -> u32 {
+    let mut digits = 1;
+    while (digits as usize) < POW10_U64.len() && n >= POW10_U64[digits as usize] {
+        digits += 1;
+    }
+    digits
+}
+
+/// 每个字节中 1 的个数：i 中 1 的个数等于 i / 2 中 1 的个数加上最低位
+pub const POPCOUNT: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 1;
+    while i < 256 {
+        table[i] = table[i / 2] + (i & 1) as u8;
+        i += 1;
+    }
+    table
+};
+
+const _: () = assert!(POPCOUNT[0] == 0 && POPCOUNT[0b1011_0110] == 5 && POPCOUNT[255] == 8);
+
+/// 查表计算 n 中 1 的个数，每次处理一个字节
+pub const fn popcount(n: u64) -> u32 {
+    let bytes = n.to_le_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        count += POPCOUNT[bytes[i] as usize] as u32;
+        i += 1;
+    }
+    count
+}
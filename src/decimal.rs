@@ -0,0 +1,393 @@
+/*
+定点小数
+
+浮点数是二进制的，0.1 这样的十进制小数无法精确表示：
+
+    0.1 + 0.2 == 0.30000000000000004
+
+金额、计量这类需要精确到某一位小数的场景，应该用整数保存最小单位，比如用 “分” 保存人民币。
+Decimal 用一个 i128 保存 `值 * 10^scale`，scale 就是小数位数，加减与乘以整数都是精确的，
+只有降低小数位数时才需要选择舍入方式。
+ */
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::bigint::BigInt;
+
+/// 支持的最大小数位数
+pub const MAX_SCALE: u32 = 18;
+
+/// 舍入方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// 四舍五入，正好一半时远离零：2.5 -> 3，-2.5 -> -3
+    HalfUp,
+    /// 银行家舍入，正好一半时取偶数：2.5 -> 2，3.5 -> 4
+    HalfEven,
+    /// 直接截断，向零取整：2.9 -> 2，-2.9 -> -2
+    TowardZero,
+}
+
+impl Rounding {
+    // 已经算出了商的绝对值和余数，判断绝对值是否需要加 1
+    // half 表示余数的两倍与除数的比较结果
+    fn round_up(self, quotient_is_odd: bool, remainder_is_zero: bool, half: Ordering) -> bool {
+        if remainder_is_zero {
+            return false;
+        }
+        match self {
+            Rounding::HalfUp => half != Ordering::Less,
+            Rounding::HalfEven => half == Ordering::Greater || (half == Ordering::Equal && quotient_is_odd),
+            Rounding::TowardZero => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecimalError {
+    /// 结果超出了 i128 能保存的范围
+    Overflow,
+    /// 小数位数超过 MAX_SCALE
+    ScaleTooLarge(u32),
+    /// 字符串不是合法的小数
+    Invalid(String),
+    /// NaN 或无穷大不能转换为小数
+    NotFinite(f64),
+    /// 浮点数无法在给定的小数位数下精确表示
+    Inexact { value: f64, exact: String, scale: u32 },
+}
+
+impl Display for DecimalError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            DecimalError::Overflow => write!(f, "decimal overflow"),
+            DecimalError::ScaleTooLarge(scale) => write!(f, "scale {} is larger than {}", scale, MAX_SCALE),
+            DecimalError::Invalid(s) => write!(f, "`{}` is not a valid decimal", s),
+            DecimalError::NotFinite(v) => write!(f, "{} cannot be converted to a decimal", v),
+            DecimalError::Inexact { value, exact, scale } => write!(
+                f,
+                "{} is stored as {} and cannot be represented with {} decimal places",
+                value, exact, scale
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecimalError {}
+
+fn pow10(scale: u32) -> i128 {
+    10i128.pow(scale)
+}
+
+fn check_scale(scale: u32) -> Result<(), DecimalError> {
+    if scale > MAX_SCALE {
+        return Err(DecimalError::ScaleTooLarge(scale));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    // 值乘以 10^scale 之后的整数
+    units: i128,
+    scale: u32,
+}
+
+/// 浮点数转换为小数的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FloatConversion {
+    /// 舍入到指定小数位数后的值
+    pub value: Decimal,
+    /// 是否没有发生舍入
+    pub exact: bool,
+    /// 浮点数在内存中实际保存的值，写成完整的十进制
+    pub float_value: String,
+}
+
+impl Decimal {
+    /// 用最小单位和小数位数创建，`Decimal::new(200, 2)` 就是 2.00
+    pub fn new(units: i128, scale: u32) -> Decimal {
+        assert!(scale <= MAX_SCALE, "scale {} is larger than {}", scale, MAX_SCALE);
+        Decimal { units, scale }
+    }
+
+    pub fn zero(scale: u32) -> Decimal {
+        Decimal::new(0, scale)
+    }
+
+    /// 整数，指定小数位数
+    pub fn from_int(value: i64, scale: u32) -> Result<Decimal, DecimalError> {
+        check_scale(scale)?;
+        let units = (value as i128).checked_mul(pow10(scale)).ok_or(DecimalError::Overflow)?;
+        Ok(Decimal { units, scale })
+    }
+
+    pub fn units(&self) -> i128 {
+        self.units
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.units == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.units < 0
+    }
+
+    /// 绝对值，units 为 i128::MIN 时没有对应的正数，返回 Overflow
+    pub fn checked_abs(&self) -> Result<Decimal, DecimalError> {
+        let units = self.units.checked_abs().ok_or(DecimalError::Overflow)?;
+        Ok(Decimal { units, scale: self.scale })
+    }
+
+    /// 相反数，与 checked_abs 一样，units 为 i128::MIN 时返回 Overflow
+    pub fn checked_neg(&self) -> Result<Decimal, DecimalError> {
+        let units = self.units.checked_neg().ok_or(DecimalError::Overflow)?;
+        Ok(Decimal { units, scale: self.scale })
+    }
+
+    /// 改变小数位数，增加位数总是精确的，减少位数时按 mode 舍入
+    pub fn rescale(&self, scale: u32, mode: Rounding) -> Result<Decimal, DecimalError> {
+        check_scale(scale)?;
+        if scale >= self.scale {
+            let units = self.units.checked_mul(pow10(scale - self.scale)).ok_or(DecimalError::Overflow)?;
+            return Ok(Decimal { units, scale });
+        }
+
+        // 用 unsigned_abs 计算，i128::MIN 也有绝对值
+        let divisor = pow10(self.scale - scale) as u128;
+        let quotient = self.units.unsigned_abs() / divisor;
+        let remainder = self.units.unsigned_abs() % divisor;
+        let half = (remainder * 2).cmp(&divisor);
+        let mut magnitude = quotient;
+        if mode.round_up(quotient % 2 == 1, remainder == 0, half) {
+            magnitude += 1;
+        }
+        // 至少除以了 10，一定能放进 i128
+        let magnitude = magnitude as i128;
+        let units = if self.units < 0 { -magnitude } else { magnitude };
+        Ok(Decimal { units, scale })
+    }
+
+    /// 舍入到 scale 位小数，与 rescale 相同
+    pub fn round(&self, scale: u32, mode: Rounding) -> Result<Decimal, DecimalError> {
+        self.rescale(scale, mode)
+    }
+
+    // 把两个数调整到相同的小数位数
+    fn align(&self, other: &Decimal) -> Result<(i128, i128, u32), DecimalError> {
+        let scale = self.scale.max(other.scale);
+        let a = self.rescale(scale, Rounding::TowardZero)?;
+        let b = other.rescale(scale, Rounding::TowardZero)?;
+        Ok((a.units, b.units, scale))
+    }
+
+    /// 精确加法，结果的小数位数取两者中较大的
+    pub fn checked_add(&self, other: &Decimal) -> Result<Decimal, DecimalError> {
+        let (a, b, scale) = self.align(other)?;
+        let units = a.checked_add(b).ok_or(DecimalError::Overflow)?;
+        Ok(Decimal { units, scale })
+    }
+
+    /// 精确减法，结果的小数位数取两者中较大的
+    pub fn checked_sub(&self, other: &Decimal) -> Result<Decimal, DecimalError> {
+        let (a, b, scale) = self.align(other)?;
+        let units = a.checked_sub(b).ok_or(DecimalError::Overflow)?;
+        Ok(Decimal { units, scale })
+    }
+
+    /// 乘以整数，小数位数不变
+    pub fn checked_mul_int(&self, factor: i128) -> Result<Decimal, DecimalError> {
+        let units = self.units.checked_mul(factor).ok_or(DecimalError::Overflow)?;
+        Ok(Decimal { units, scale: self.scale })
+    }
+
+    /// 转换为浮点数，可能损失精度
+    pub fn to_f64(&self) -> f64 {
+        // 直接用字符串解析，能得到最接近的 f64
+        self.to_string().parse().unwrap()
+    }
+
+    /// 从浮点数转换，按 mode 舍入到 scale 位小数，并报告是否发生了舍入
+    ///
+    /// 0.1 在内存中实际是 0.1000000000000000055511151231257827...，
+    /// 所以 `from_f64(0.1, 2, ..)` 的结果是 0.10，但 exact 为 false
+    pub fn from_f64(value: f64, scale: u32, mode: Rounding) -> Result<FloatConversion, DecimalError> {
+        check_scale(scale)?;
+        if !value.is_finite() {
+            return Err(DecimalError::NotFinite(value));
+        }
+
+        // f64 的值是 mantissa * 2^exponent
+        let (mantissa, exponent) = decompose(value);
+        let scaled = BigInt::from(mantissa) * BigInt::from(10).pow(scale);
+        let (magnitude, exact) = if exponent >= 0 {
+            (scaled * BigInt::from(2).pow(exponent as u32), true)
+        } else {
+            let divisor = BigInt::from(2).pow(exponent.unsigned_abs());
+            let (quotient, remainder) = scaled.div_rem(&divisor);
+            let half = (&remainder * &BigInt::from(2)).cmp(&divisor);
+            let odd = !(&quotient % &BigInt::from(2)).is_zero();
+            let quotient = if mode.round_up(odd, remainder.is_zero(), half) {
+                quotient + BigInt::one()
+            } else {
+                quotient
+            };
+            (quotient, remainder.is_zero())
+        };
+
+        let magnitude = magnitude.to_i128().ok_or(DecimalError::Overflow)?;
+        let units = if value.is_sign_negative() { -magnitude } else { magnitude };
+        Ok(FloatConversion { value: Decimal { units, scale }, exact, float_value: exact_decimal(value) })
+    }
+
+    /// 从浮点数转换，不能精确表示时返回错误
+    pub fn from_f64_exact(value: f64, scale: u32) -> Result<Decimal, DecimalError> {
+        let conversion = Decimal::from_f64(value, scale, Rounding::HalfEven)?;
+        if !conversion.exact {
+            return Err(DecimalError::Inexact { value, exact: conversion.float_value, scale });
+        }
+        Ok(conversion.value)
+    }
+}
+
+// 拆出 f64 的尾数与指数，value 的绝对值 = mantissa * 2^exponent
+fn decompose(value: f64) -> (u64, i32) {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    if exponent == 0 {
+        // 非规格化数没有隐含的最高位 1
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), exponent - 1075)
+    }
+}
+
+/// 浮点数在内存中实际保存的值，写成完整的十进制，比如 0.1 是
+/// 0.1000000000000000055511151231257827021181583404541015625
+pub fn exact_decimal(value: f64) -> String {
+    let (mantissa, exponent) = decompose(value);
+    let sign = if value.is_sign_negative() && mantissa != 0 { "-" } else { "" };
+    if exponent >= 0 {
+        return format!("{}{}", sign, BigInt::from(mantissa) * BigInt::from(2).pow(exponent as u32));
+    }
+    // m / 2^k == m * 5^k / 10^k
+    let places = exponent.unsigned_abs() as usize;
+    let digits = (BigInt::from(mantissa) * BigInt::from(5).pow(places as u32)).to_string();
+    let digits = format!("{:0>width$}", digits, width = places + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - places);
+    let frac_part = frac_part.trim_end_matches('0');
+    if frac_part.is_empty() {
+        format!("{}{}", sign, int_part)
+    } else {
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+impl PartialEq for Decimal {
+    // 2.0 与 2.00 相等
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        match self.align(other) {
+            Ok((a, b, _)) => a.cmp(&b),
+            // 对齐时溢出，说明两者量级相差很大，用 BigInt 比较
+            Err(_) => {
+                let scale = self.scale.max(other.scale);
+                let a = BigInt::from(self.units) * BigInt::from(10).pow(scale - self.scale);
+                let b = BigInt::from(other.units) * BigInt::from(10).pow(scale - other.scale);
+                a.cmp(&b)
+            }
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::ops::Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        self.checked_add(&other).expect("decimal overflow")
+    }
+}
+
+impl std::ops::Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        self.checked_sub(&other).expect("decimal overflow")
+    }
+}
+
+impl std::ops::Mul<i128> for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, factor: i128) -> Decimal {
+        self.checked_mul_int(factor).expect("decimal overflow")
+    }
+}
+
+impl std::ops::Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        self.checked_neg().expect("decimal overflow")
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let digits = self.units.unsigned_abs().to_string();
+        let text = if self.scale == 0 {
+            digits
+        } else {
+            let scale = self.scale as usize;
+            let digits = format!("{:0>width$}", digits, width = scale + 1);
+            let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+            format!("{}.{}", int_part, frac_part)
+        };
+        f.pad_integral(self.units >= 0, "", &text)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = DecimalError;
+
+    /// 小数位数就是字符串里小数点后的位数，`"2.50"` 的 scale 为 2
+    fn from_str(s: &str) -> Result<Decimal, DecimalError> {
+        let invalid = || DecimalError::Invalid(s.to_string());
+        let (negative, body) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int_part, frac_part) = body.split_once('.').unwrap_or((body, ""));
+        let all_digits = |p: &str| p.bytes().all(|b| b.is_ascii_digit());
+        if int_part.is_empty() || !all_digits(int_part) || !all_digits(frac_part) || body.ends_with('.') {
+            return Err(invalid());
+        }
+
+        let scale = frac_part.len() as u32;
+        check_scale(scale)?;
+        let units: i128 = format!("{}{}", int_part, frac_part).parse().map_err(|_| DecimalError::Overflow)?;
+        Ok(Decimal { units: if negative { -units } else { units }, scale })
+    }
+}
@@ -0,0 +1,144 @@
+/*
+整数除法与取余
+
+    64 / 5 == 12
+    54 % 5 == 4
+
+操作数都是正数时结果很直观，有负数时不同的运算结果就不同了：
+- `/` 向零取整，`%` 的符号与被除数相同：-7 / 2 == -3，-7 % 2 == -1
+- div_euclid 保证余数非负：(-7).div_euclid(2) == -4，(-7).rem_euclid(2) == 1
+- 向下取整（floor）与向上取整（ceil）只在结果不是整数时与 `/` 不同
+- 除数为 0 时 `/` 与 `%` 都会 panic
+- MIN / -1 的结果比 MAX 大 1，无论调试还是发布模式都会 panic，MIN % -1 同样 panic
+- 转为 f64 后再除不会 panic，除以 0 得到无穷大或 NaN，但很大的整数转换时会丢失精度
+ */
+
+use crate::bitwise::{Operand, OperandError};
+use crate::decimal::exact_decimal;
+use crate::int_type::IntType;
+use crate::table::Table;
+
+/// 一种除法的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// 整数结果
+    Value(String),
+    /// 会 panic，附带 panic 的信息
+    Panic(&'static str),
+    /// 转为 f64 后相除的结果，lossy 表示操作数转换为 f64 时丢失了精度
+    Float { value: f64, lossy: bool },
+}
+
+impl Outcome {
+    pub fn is_panic(&self) -> bool {
+        matches!(self, Outcome::Panic(_))
+    }
+}
+
+/// 表中的一行，如 `a / b`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub expr: &'static str,
+    pub outcome: Outcome,
+}
+
+const DIVIDE_BY_ZERO: &str = "attempt to divide by zero";
+const REMAINDER_BY_ZERO: &str = "attempt to calculate the remainder with a divisor of zero";
+const DIVIDE_OVERFLOW: &str = "attempt to divide with overflow";
+const REMAINDER_OVERFLOW: &str = "attempt to calculate the remainder with overflow";
+
+// 对某个具体的整数类型计算所有的除法
+macro_rules! explore_as {
+    ($t:ty, $ty:expr, $a:expr, $b:expr) => {{
+        let (a, b) = ($a.raw as $t, $b.raw as $t);
+        let is_negative = |x: $t| $ty.is_signed() && (x as i128) < 0;
+        // b 为 0 与 MIN / -1 是两种不同的 panic
+        let div_panic = if b == 0 { DIVIDE_BY_ZERO } else { DIVIDE_OVERFLOW };
+        let rem_panic = if b == 0 { REMAINDER_BY_ZERO } else { REMAINDER_OVERFLOW };
+        let value = |x: Option<$t>, panic: &'static str| match x {
+            Some(x) => Outcome::Value(x.to_string()),
+            None => Outcome::Panic(panic),
+        };
+
+        let quotient = a.checked_div(b);
+        let remainder = a.checked_rem(b);
+        // 余数不为 0 且余数与除数异号时，向零取整的结果比向下取整大 1
+        let floor = match (quotient, remainder) {
+            (Some(q), Some(r)) if r != 0 && is_negative(r) != is_negative(b) => Some(q - 1),
+            (q, _) => q,
+        };
+        // 余数不为 0 且余数与除数同号时，向上取整的结果比向零取整大 1
+        let ceil = match (quotient, remainder) {
+            (Some(q), Some(r)) if r != 0 && is_negative(r) == is_negative(b) => q.checked_add(1),
+            (q, _) => q,
+        };
+        let (fa, fb) = (a as f64, b as f64);
+
+        vec![
+            Row { expr: "a / b", outcome: value(quotient, div_panic) },
+            Row { expr: "a % b", outcome: value(remainder, rem_panic) },
+            Row { expr: "a.div_euclid(b)", outcome: value(a.checked_div_euclid(b), div_panic) },
+            Row { expr: "a.rem_euclid(b)", outcome: value(a.checked_rem_euclid(b), rem_panic) },
+            Row { expr: "floor(a / b)", outcome: value(floor, div_panic) },
+            Row { expr: "ceil(a / b)", outcome: value(ceil, div_panic) },
+            Row { expr: "a.wrapping_div(b)", outcome: value((b != 0).then(|| a.wrapping_div(b)), DIVIDE_BY_ZERO) },
+            Row {
+                expr: "a as f64 / b as f64",
+                // f64 实际保存的值与整数不同，说明转换时丢失了精度
+                outcome: Outcome::Float {
+                    value: fa / fb,
+                    lossy: exact_decimal(fa) != a.to_string() || exact_decimal(fb) != b.to_string(),
+                },
+            },
+        ]
+    }};
+}
+
+/// 计算 a 与 b 的所有除法与取余
+pub fn explore(a: Operand, b: Operand) -> Vec<Row> {
+    let ty = a.ty;
+    match ty {
+        IntType::I8 => explore_as!(i8, ty, a, b),
+        IntType::I16 => explore_as!(i16, ty, a, b),
+        IntType::I32 => explore_as!(i32, ty, a, b),
+        IntType::I64 => explore_as!(i64, ty, a, b),
+        IntType::I128 => explore_as!(i128, ty, a, b),
+        IntType::Isize => explore_as!(isize, ty, a, b),
+        IntType::U8 => explore_as!(u8, ty, a, b),
+        IntType::U16 => explore_as!(u16, ty, a, b),
+        IntType::U32 => explore_as!(u32, ty, a, b),
+        IntType::U64 => explore_as!(u64, ty, a, b),
+        IntType::U128 => explore_as!(u128, ty, a, b),
+        IntType::Usize => explore_as!(usize, ty, a, b),
+    }
+}
+
+/// 按字符串解析操作数再计算，操作数可以写 `MIN`、`MAX`
+pub fn explore_str(ty: IntType, a: &str, b: &str) -> Result<Vec<Row>, OperandError> {
+    Ok(explore(Operand::parse(ty, a)?, Operand::parse(ty, b)?))
+}
+
+/// 生成 `learn_rust divide` 命令的输出
+pub fn render(a: Operand, b: Operand) -> String {
+    let mut out = format!("type {}, a = {}, b = {}\n\n", a.ty, a, b);
+    let mut table = Table::new(["expr", "result", "note"]);
+    for row in explore(a, b) {
+        let (result, note) = match row.outcome {
+            Outcome::Value(v) => (v, String::new()),
+            Outcome::Panic(message) => ("panic".to_string(), message.to_string()),
+            Outcome::Float { value, lossy } => {
+                let mut notes = Vec::new();
+                if lossy {
+                    notes.push("operands lose precision when converted to f64");
+                }
+                if !value.is_finite() {
+                    notes.push("no panic, the result is not a finite number");
+                }
+                (format!("{:?}", value), notes.join("; "))
+            }
+        };
+        table.row([row.expr.to_string(), result, note]);
+    }
+    out.push_str(&table.to_string());
+    out
+}
@@ -0,0 +1,326 @@
+/*
+字节序
+
+一个多字节的值在内存或网络中按什么顺序存放字节：
+
+    0x12345678u32.to_be_bytes() == [0x12, 0x34, 0x56, 0x78]   // 大端：高位在前，网络协议常用
+    0x12345678u32.to_le_bytes() == [0x78, 0x56, 0x34, 0x12]   // 小端：低位在前，x86 与 ARM 常用
+    0x12345678u32.to_ne_bytes()                               // 本机字节序，取决于平台
+
+整数和浮点数都有 to_*_bytes / from_*_bytes，这里用 `ByteRepr` 把它们统一起来，
+并补上 bool 和 char：它们不是所有的位模式都合法，从字节转换回来时需要检查。
+`ByteReader` 从一段字节中依次读出多个值，字节不够时返回错误而不是 panic。
+ */
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+use crate::cast::Value;
+
+/// 字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Big,
+    Little,
+    /// 本机字节序，实际是 Big 或 Little 之一
+    Native,
+}
+
+impl Endian {
+    /// 把 Native 换成本机实际的字节序
+    pub fn resolve(self) -> Endian {
+        match self {
+            Endian::Native if cfg!(target_endian = "big") => Endian::Big,
+            Endian::Native => Endian::Little,
+            e => e,
+        }
+    }
+}
+
+impl Display for Endian {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(match self {
+            Endian::Big => "big",
+            Endian::Little => "little",
+            Endian::Native => "native",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BytesError {
+    /// 剩余的字节不够读出一个值
+    UnexpectedEof { offset: usize, needed: usize, available: usize },
+    /// 按类型读完后还剩下不足一个值的字节
+    TrailingBytes { offset: usize, count: usize },
+    /// 字节的长度与类型的大小不同
+    WrongLength { expected: usize, actual: usize },
+    /// bool 只能是 0 或 1
+    InvalidBool(u8),
+    /// 不是合法的 Unicode 标量值
+    InvalidChar(u32),
+}
+
+impl Display for BytesError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            BytesError::UnexpectedEof { offset, needed, available } => write!(
+                f,
+                "unexpected end of buffer at offset {}: needed {} bytes, {} available",
+                offset, needed, available
+            ),
+            BytesError::TrailingBytes { offset, count } => {
+                write!(f, "{} trailing bytes at offset {} do not form a whole value", count, offset)
+            }
+            BytesError::WrongLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            BytesError::InvalidBool(b) => write!(f, "invalid bool byte 0x{:02x}, expected 0 or 1", b),
+            BytesError::InvalidChar(c) => write!(f, "0x{:x} is not a valid char", c),
+        }
+    }
+}
+
+impl std::error::Error for BytesError {}
+
+/// 可以与定长字节数组互相转换的基本类型
+pub trait ByteRepr: Sized + Copy {
+    /// `[u8; SIZE]`
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default + Copy + Debug;
+    const SIZE: usize;
+
+    fn to_bytes(self, endian: Endian) -> Self::Bytes;
+
+    /// bool 与 char 会检查字节是否合法，其他类型总是成功
+    fn from_bytes(bytes: Self::Bytes, endian: Endian) -> Result<Self, BytesError>;
+
+    /// 从切片转换，长度必须正好是 SIZE
+    fn from_slice(bytes: &[u8], endian: Endian) -> Result<Self, BytesError> {
+        if bytes.len() != Self::SIZE {
+            return Err(BytesError::WrongLength { expected: Self::SIZE, actual: bytes.len() });
+        }
+        let mut array = Self::Bytes::default();
+        array.as_mut().copy_from_slice(bytes);
+        Self::from_bytes(array, endian)
+    }
+}
+
+macro_rules! impl_byte_repr {
+    ($($t:ty)*) => {
+        $(
+            impl ByteRepr for $t {
+                type Bytes = [u8; std::mem::size_of::<$t>()];
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn to_bytes(self, endian: Endian) -> Self::Bytes {
+                    match endian {
+                        Endian::Big => self.to_be_bytes(),
+                        Endian::Little => self.to_le_bytes(),
+                        Endian::Native => self.to_ne_bytes(),
+                    }
+                }
+
+                fn from_bytes(bytes: Self::Bytes, endian: Endian) -> Result<$t, BytesError> {
+                    Ok(match endian {
+                        Endian::Big => <$t>::from_be_bytes(bytes),
+                        Endian::Little => <$t>::from_le_bytes(bytes),
+                        Endian::Native => <$t>::from_ne_bytes(bytes),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_byte_repr!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64);
+
+impl ByteRepr for bool {
+    type Bytes = [u8; 1];
+    const SIZE: usize = 1;
+
+    fn to_bytes(self, _endian: Endian) -> [u8; 1] {
+        [self as u8]
+    }
+
+    fn from_bytes(bytes: [u8; 1], _endian: Endian) -> Result<bool, BytesError> {
+        match bytes[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(BytesError::InvalidBool(b)),
+        }
+    }
+}
+
+impl ByteRepr for char {
+    type Bytes = [u8; 4];
+    const SIZE: usize = 4;
+
+    /// 按码点保存为 u32
+    fn to_bytes(self, endian: Endian) -> [u8; 4] {
+        (self as u32).to_bytes(endian)
+    }
+
+    fn from_bytes(bytes: [u8; 4], endian: Endian) -> Result<char, BytesError> {
+        let code = u32::from_bytes(bytes, endian)?;
+        char::from_u32(code).ok_or(BytesError::InvalidChar(code))
+    }
+}
+
+/// 从字节中依次读出值
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    endian: Endian,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(buf: &'a [u8], endian: Endian) -> ByteReader<'a> {
+        ByteReader { buf, pos: 0, endian }
+    }
+
+    /// 已经读过的字节数
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// 读出一个值，字节不够时返回错误，位置不变
+    pub fn read<T: ByteRepr>(&mut self) -> Result<T, BytesError> {
+        let bytes = self.take(T::SIZE)?;
+        let value = T::from_slice(bytes, self.endian);
+        if value.is_ok() {
+            self.pos += T::SIZE;
+        }
+        value
+    }
+
+    /// 读出 n 个值，任何一个失败都会返回错误，位置停在失败的值之前
+    pub fn read_many<T: ByteRepr>(&mut self, n: usize) -> Result<Vec<T>, BytesError> {
+        (0..n).map(|_| self.read()).collect()
+    }
+
+    /// 读出原始的 n 个字节
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], BytesError> {
+        let bytes = self.take(n)?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn take(&self, n: usize) -> Result<&'a [u8], BytesError> {
+        let end = self.pos.checked_add(n);
+        end.and_then(|end| self.buf.get(self.pos..end)).ok_or(BytesError::UnexpectedEof {
+            offset: self.pos,
+            needed: n,
+            available: self.remaining(),
+        })
+    }
+}
+
+/// 依次写入值
+#[derive(Debug, Clone)]
+pub struct ByteWriter {
+    buf: Vec<u8>,
+    endian: Endian,
+}
+
+impl ByteWriter {
+    pub fn new(endian: Endian) -> ByteWriter {
+        ByteWriter { buf: Vec::new(), endian }
+    }
+
+    pub fn write<T: ByteRepr>(&mut self, value: T) -> &mut ByteWriter {
+        self.buf.extend_from_slice(value.to_bytes(self.endian).as_ref());
+        self
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// 把整段字节按同一类型读出，长度必须是类型大小的整数倍
+pub fn read_all<T: ByteRepr>(buf: &[u8], endian: Endian) -> Result<Vec<T>, BytesError> {
+    let whole = buf.len() / T::SIZE * T::SIZE;
+    if whole != buf.len() {
+        return Err(BytesError::TrailingBytes { offset: whole, count: buf.len() - whole });
+    }
+    ByteReader::new(buf, endian).read_many(buf.len() / T::SIZE)
+}
+
+/// 把一组值按同一字节序写成字节
+pub fn write_all<T: ByteRepr>(values: &[T], endian: Endian) -> Vec<u8> {
+    let mut writer = ByteWriter::new(endian);
+    for value in values {
+        writer.write(*value);
+    }
+    writer.into_bytes()
+}
+
+/// 与 `xxd`、`hexdump -C` 类似的格式：偏移、16 个字节的十六进制、可打印的 ASCII
+///
+/// 00000000  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|
+pub fn hexdump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (i, line) in bytes.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for j in 0..16 {
+            if j == 8 {
+                hex.push(' ');
+            }
+            match line.get(j) {
+                Some(b) => hex.push_str(&format!("{:02x} ", b)),
+                None => hex.push_str("   "),
+            }
+        }
+        let ascii: String =
+            line.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+        out.push_str(&format!("{:08x}  {} |{}|\n", i * 16, hex, ascii));
+    }
+    out
+}
+
+/// 以空格分隔的十六进制，如 `12 34 56 78`
+pub fn hex(bytes: &[u8]) -> String {
+    let parts: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    parts.join(" ")
+}
+
+// 对 Value 的每种数值调用 to_bytes
+macro_rules! value_bytes {
+    ($value:expr, $endian:expr, $($variant:ident)*) => {
+        match $value {
+            $(Value::$variant(v) => v.to_bytes($endian).to_vec(),)*
+        }
+    };
+}
+
+/// `cast` 命令解析出的数值转换为字节
+pub fn value_to_bytes(value: Value, endian: Endian) -> Vec<u8> {
+    value_bytes!(value, endian, I8 I16 I32 I64 I128 Isize U8 U16 U32 U64 U128 Usize F32 F64)
+}
+
+/// 生成 `learn_rust bytes` 命令的输出：同一个值在三种字节序下的字节
+pub fn render(label: &str, to_bytes: impl Fn(Endian) -> Vec<u8>) -> String {
+    let mut out = format!("{}\n", label);
+    for endian in [Endian::Big, Endian::Little, Endian::Native] {
+        let name = match endian {
+            Endian::Native => format!("native ({})", endian.resolve()),
+            e => e.to_string(),
+        };
+        out.push_str(&format!("{:<16}{}\n", name, hex(&to_bytes(endian))));
+    }
+    out.push_str("\nhexdump (big endian)\n");
+    out.push_str(&hexdump(&to_bytes(Endian::Big)));
+    out
+}
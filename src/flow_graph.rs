@@ -0,0 +1,910 @@
+/*
+控制流图
+
+把一个函数的源码拆成基本块（basic block）：块内的语句总是从头到尾依次执行，
+块与块之间用边连接，边上标出走这条边的原因：if 的 true / false、match 的分支、
+循环回到开头的回边（back edge），以及 break、continue、return 这样的跳转。
+
+    fn nest_loop() -> i32 {
+        let mut count = 0;
+        'outer: loop {
+            count = 2;
+            'inner: loop {
+                count = 3;
+                break 'outer;   // 直接跳到 'outer 循环之后
+            }
+            count = 4; // 没有任何边指向这个块，所以它不会执行
+        }
+        return count;
+    }
+
+画成图后可以看到 `break 'outer` 的边越过了 'inner 循环之后的块，`count = 4` 所在的块没有入边。
+
+这里只识别语句位置上的 if / else if / else、match、loop / while / for、break、continue、return
+和嵌套的 {} 块，其他语句（包括 `let x = if ...` 这样写在表达式中的分支）作为一条普通语句；
+闭包、`?` 运算符与 panic 不产生边。源码只做词法分析，不需要能通过编译。
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlowError {
+    /// 括号没有闭合或不匹配，line 从 1 开始
+    UnbalancedDelimiter { line: usize },
+    /// 字符串、字符或块注释没有结束
+    UnterminatedLiteral { line: usize },
+    /// 源码中找不到这个函数
+    FunctionNotFound(String),
+    /// break 或 continue 的标签不属于任何外层的循环
+    UnknownLabel { line: usize, label: String },
+    /// break 或 continue 不在循环中
+    OutsideLoop { line: usize, keyword: String },
+    /// 语法不完整，比如 if 后面没有 {}
+    Unexpected { line: usize, expected: &'static str },
+}
+
+impl Display for FlowError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            FlowError::UnbalancedDelimiter { line } => write!(f, "line {}: unbalanced delimiter", line),
+            FlowError::UnterminatedLiteral { line } => write!(f, "line {}: unterminated literal or comment", line),
+            FlowError::FunctionNotFound(name) => write!(f, "function `{}` not found", name),
+            FlowError::UnknownLabel { line, label } => write!(f, "line {}: use of undeclared label `{}`", line, label),
+            FlowError::OutsideLoop { line, keyword } => write!(f, "line {}: `{}` outside of a loop", line, keyword),
+            FlowError::Unexpected { line, expected } => write!(f, "line {}: expected {}", line, expected),
+        }
+    }
+}
+
+impl std::error::Error for FlowError {}
+
+// ---- 词法分析 ----
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Ident,
+    Lifetime,
+    Literal,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Token {
+    pub(crate) kind: TokenKind,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Tree {
+    Leaf(Token),
+    Group { delimiter: char, open: Token, close: Token, children: Vec<Tree> },
+}
+
+impl Tree {
+    pub(crate) fn first(&self) -> Token {
+        match self {
+            Tree::Leaf(token) => *token,
+            Tree::Group { open, .. } => *open,
+        }
+    }
+
+    pub(crate) fn brace(&self) -> Option<&[Tree]> {
+        match self {
+            Tree::Group { delimiter: '{', children, .. } => Some(children),
+            _ => None,
+        }
+    }
+}
+
+// 按最长匹配的顺序排列
+const PUNCTS: [&str; 25] = [
+    "..=", "...", "<<=", ">>=", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "*=", "/=", "%=",
+    "^=", "&=", "|=", "<<", ">>", "..", "#!",
+];
+
+pub(crate) fn tokenize(src: &str) -> Result<Vec<Token>, FlowError> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+    let is_ident = |c: char| c == '_' || c.is_alphanumeric();
+
+    while i < bytes.len() {
+        let c = src[i..].chars().next().unwrap_or_default();
+        let start = i;
+        let start_line = line;
+        let unterminated = FlowError::UnterminatedLiteral { line: start_line };
+        let rest = &src[i..];
+
+        if c == '\n' {
+            line += 1;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += c.len_utf8();
+            continue;
+        }
+        if rest.starts_with("//") {
+            i += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        if rest.starts_with("/*") {
+            // 块注释可以嵌套
+            let mut depth = 0;
+            loop {
+                let rest = &src[i..];
+                if rest.starts_with("/*") {
+                    depth += 1;
+                    i += 2;
+                } else if rest.starts_with("*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else if let Some(c) = rest.chars().next() {
+                    line += (c == '\n') as usize;
+                    i += c.len_utf8();
+                } else {
+                    return Err(unterminated);
+                }
+            }
+            continue;
+        }
+
+        let kind = if let Some(len) = raw_string_len(rest) {
+            line += rest[..len].matches('\n').count();
+            i += len;
+            TokenKind::Literal
+        } else if c == '"' || rest.starts_with("b\"") || rest.starts_with("c\"") {
+            i += if c == '"' { 1 } else { 2 };
+            loop {
+                match src[i..].chars().next() {
+                    None => return Err(unterminated),
+                    Some('\\') => i += 1 + src[i + 1..].chars().next().map_or(0, char::len_utf8),
+                    Some('"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(c) => {
+                        line += (c == '\n') as usize;
+                        i += c.len_utf8();
+                    }
+                }
+            }
+            TokenKind::Literal
+        } else if c == '\'' || rest.starts_with("b'") {
+            // 'a' 与 '\n' 是字符，'a 是生命周期或标签
+            let quote = if c == '\'' { i } else { i + 1 };
+            let mut chars = src[quote + 1..].char_indices();
+            match (chars.next(), chars.next()) {
+                (Some((_, '\\')), _) => {
+                    // 跳过反斜杠后的一个字符，'\'' 中间的引号不是结尾
+                    let escaped = quote + 2 + src[quote + 2..].chars().next().map_or(0, char::len_utf8);
+                    let close = src[escaped..].find('\'').ok_or(unterminated)?;
+                    i = escaped + close + 1;
+                    TokenKind::Literal
+                }
+                (Some(_), Some((offset, '\''))) => {
+                    i = quote + 1 + offset + 1;
+                    TokenKind::Literal
+                }
+                (Some((_, c)), _) if is_ident(c) => {
+                    i = quote + 1 + src[quote + 1..].find(|c: char| !is_ident(c)).unwrap_or(src.len() - quote - 1);
+                    TokenKind::Lifetime
+                }
+                _ => return Err(unterminated),
+            }
+        } else if c.is_ascii_digit() {
+            i += 1;
+            while let Some(c) = src[i..].chars().next() {
+                let fraction = c == '.' && src[i + 1..].starts_with(|c: char| c.is_ascii_digit());
+                if !(is_ident(c) || fraction) {
+                    break;
+                }
+                i += c.len_utf8();
+            }
+            TokenKind::Literal
+        } else if is_ident(c) {
+            i += rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+            // r#type 这样的原始标识符
+            if &src[start..i] == "r" && src[i..].starts_with('#') {
+                i += 1;
+                i += src[i..].find(|c: char| !is_ident(c)).unwrap_or(src.len() - i);
+            }
+            TokenKind::Ident
+        } else {
+            i += PUNCTS.iter().find(|p| rest.starts_with(**p)).map_or(c.len_utf8(), |p| p.len());
+            TokenKind::Punct
+        };
+        tokens.push(Token { kind, start, end: i, line: start_line });
+    }
+    Ok(tokens)
+}
+
+// r"..."、r#"..."#、br"..." 的长度
+fn raw_string_len(rest: &str) -> Option<usize> {
+    let prefix = if rest.starts_with("br") || rest.starts_with("cr") { 2 } else { usize::from(rest.starts_with('r')) };
+    if prefix == 0 {
+        return None;
+    }
+    let hashes = rest[prefix..].bytes().take_while(|&b| b == b'#').count();
+    if !rest[prefix + hashes..].starts_with('"') {
+        return None;
+    }
+    let body = prefix + hashes + 1;
+    let close = format!("\"{}", "#".repeat(hashes));
+    rest[body..].find(&close).map(|end| body + end + close.len())
+}
+
+pub(crate) fn build_trees(src: &str, tokens: &[Token]) -> Result<Vec<Tree>, FlowError> {
+    // 每一层是 (左括号, 已经收集的子节点)
+    let mut stack: Vec<(Option<Token>, Vec<Tree>)> = vec![(None, Vec::new())];
+    for token in tokens {
+        let text = &src[token.start..token.end];
+        match (token.kind, text) {
+            (TokenKind::Punct, "(" | "[" | "{") => stack.push((Some(*token), Vec::new())),
+            (TokenKind::Punct, ")" | "]" | "}") => {
+                let unbalanced = FlowError::UnbalancedDelimiter { line: token.line };
+                let (open, children) = stack.pop().ok_or(unbalanced.clone())?;
+                let open = open.ok_or(unbalanced.clone())?;
+                let delimiter = src[open.start..].chars().next().unwrap_or_default();
+                let expected = match delimiter {
+                    '(' => ")",
+                    '[' => "]",
+                    _ => "}",
+                };
+                if text != expected {
+                    return Err(unbalanced);
+                }
+                let parent = &mut stack.last_mut().ok_or(unbalanced)?.1;
+                parent.push(Tree::Group { delimiter, open, close: *token, children });
+            }
+            _ => stack.last_mut().expect("root level").1.push(Tree::Leaf(*token)),
+        }
+    }
+    match stack.pop() {
+        Some((None, trees)) if stack.is_empty() => Ok(trees),
+        Some((Some(open), _)) => Err(FlowError::UnbalancedDelimiter { line: open.line }),
+        _ => unreachable!("root level is never pushed twice"),
+    }
+}
+
+// ---- 控制流图 ----
+
+/// 基本块的种类
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockKind {
+    Entry,
+    Exit,
+    /// 依次执行的语句
+    Basic,
+    /// if 的条件或 match 的表达式，如 `if count == 100`
+    Branch(String),
+    /// 循环的开头，如 `'outer: loop`、`while n != 0`、`for i in 0..3`
+    Loop(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub kind: BlockKind,
+    pub statements: Vec<String>,
+}
+
+/// 边的种类
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// 顺序执行到下一个块
+    Goto,
+    /// if 或 while 的条件成立
+    True,
+    /// if 或 while 的条件不成立
+    False,
+    /// for 取到了下一个元素
+    Next,
+    /// for 的迭代器耗尽
+    Done,
+    /// match 的一个分支，保存模式（包括守卫）
+    Arm(String),
+    /// 保存标签，如 `Some("'outer")`
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return,
+}
+
+impl Display for EdgeKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            EdgeKind::Goto => Ok(()),
+            EdgeKind::True => write!(f, "true"),
+            EdgeKind::False => write!(f, "false"),
+            EdgeKind::Next => write!(f, "next"),
+            EdgeKind::Done => write!(f, "done"),
+            EdgeKind::Arm(pattern) => write!(f, "{}", pattern),
+            EdgeKind::Break(Some(label)) => write!(f, "break {}", label),
+            EdgeKind::Break(None) => write!(f, "break"),
+            EdgeKind::Continue(Some(label)) => write!(f, "continue {}", label),
+            EdgeKind::Continue(None) => write!(f, "continue"),
+            EdgeKind::Return => write!(f, "return"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: EdgeKind,
+}
+
+impl Edge {
+    /// 回到循环开头的边；循环体中的块总是在循环开头之后创建，所以指向前面的块就是回边
+    pub fn is_back(&self) -> bool {
+        self.to <= self.from
+    }
+}
+
+/// 一个函数的控制流图，块 0 是入口，最后一个块是出口
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowGraph {
+    pub name: String,
+    pub blocks: Vec<BasicBlock>,
+    pub edges: Vec<Edge>,
+}
+
+impl FlowGraph {
+    /// 解析源码，为其中名为 name 的函数（可以是嵌套在其他函数中的函数）生成控制流图
+    pub fn from_function(src: &str, name: &str) -> Result<FlowGraph, FlowError> {
+        let trees = build_trees(src, &tokenize(src)?)?;
+        let body = find_function(src, &trees, name).ok_or_else(|| FlowError::FunctionNotFound(name.to_string()))?;
+        let mut builder = Builder::new(src);
+        builder.lower_stmts(body)?;
+        Ok(builder.finish(name))
+    }
+
+    pub fn entry(&self) -> usize {
+        0
+    }
+
+    pub fn exit(&self) -> usize {
+        self.blocks.len() - 1
+    }
+
+    pub fn successors(&self, block: usize) -> impl Iterator<Item = &Edge> + '_ {
+        self.edges.iter().filter(move |e| e.from == block)
+    }
+
+    pub fn predecessors(&self, block: usize) -> impl Iterator<Item = &Edge> + '_ {
+        self.edges.iter().filter(move |e| e.to == block)
+    }
+
+    /// 包含这条语句的块，语句的写法与源码相同，但多个空白会合并为一个空格
+    pub fn block_of(&self, statement: &str) -> Option<usize> {
+        self.blocks.iter().position(|b| b.statements.iter().any(|s| s == statement))
+    }
+
+    /// 从入口出发到达不了的块
+    pub fn unreachable(&self) -> Vec<usize> {
+        let mut reached = vec![false; self.blocks.len()];
+        let mut stack = vec![self.entry()];
+        while let Some(block) = stack.pop() {
+            if !std::mem::replace(&mut reached[block], true) {
+                stack.extend(self.successors(block).map(|e| e.to));
+            }
+        }
+        (0..self.blocks.len()).filter(|&b| !reached[b]).collect()
+    }
+
+    fn title(&self, block: usize) -> String {
+        match &self.blocks[block].kind {
+            BlockKind::Entry => "entry".to_string(),
+            BlockKind::Exit => "exit".to_string(),
+            BlockKind::Basic => String::new(),
+            BlockKind::Branch(text) | BlockKind::Loop(text) => text.clone(),
+        }
+    }
+
+    /// Graphviz 的 DOT 格式，用 `dot -Tsvg` 生成图片
+    ///
+    /// 条件为菱形、循环开头为圆角框，回边是虚线，break / continue / return 标为红色，不可达的块填充灰色
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph \"{}\" {{\n", escape(&self.name));
+        out.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        out.push_str("    edge [fontname=\"monospace\"];\n");
+        let unreachable = self.unreachable();
+        for (i, block) in self.blocks.iter().enumerate() {
+            let mut attrs = Vec::new();
+            let label = match block.kind {
+                BlockKind::Basic => block.statements.iter().map(|s| format!("{}\\l", escape(s))).collect(),
+                _ => escape(&self.title(i)),
+            };
+            attrs.push(format!("label=\"{}\"", label));
+            match block.kind {
+                BlockKind::Entry | BlockKind::Exit => attrs.push("shape=oval".to_string()),
+                BlockKind::Branch(_) => attrs.push("shape=diamond".to_string()),
+                BlockKind::Loop(_) => attrs.push("style=rounded".to_string()),
+                BlockKind::Basic => {}
+            }
+            if unreachable.contains(&i) {
+                attrs.push("style=\"filled,dashed\", fillcolor=lightgrey".to_string());
+            }
+            out.push_str(&format!("    b{} [{}];\n", i, attrs.join(", ")));
+        }
+        for edge in &self.edges {
+            let mut attrs = Vec::new();
+            let label = edge.kind.to_string();
+            if !label.is_empty() {
+                attrs.push(format!("label=\"{}\"", escape(&label)));
+            }
+            if matches!(edge.kind, EdgeKind::Break(_) | EdgeKind::Continue(_) | EdgeKind::Return) {
+                attrs.push("color=red, fontcolor=red".to_string());
+            }
+            if edge.is_back() {
+                attrs.push("style=dashed".to_string());
+            }
+            match attrs.is_empty() {
+                true => out.push_str(&format!("    b{} -> b{};\n", edge.from, edge.to)),
+                false => out.push_str(&format!("    b{} -> b{} [{}];\n", edge.from, edge.to, attrs.join(", "))),
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// 在终端中显示：按顺序列出每个块的语句和出边，跳到其他位置的边在左侧画出连线
+    pub fn render_ascii(&self) -> String {
+        // 每一行的文字，每个块标题所在的行，以及需要画线的边 (出边所在行, 目标块)
+        let mut rows: Vec<String> = Vec::new();
+        let mut headers = Vec::new();
+        let mut jumps = Vec::new();
+        let unreachable = self.unreachable();
+        for (i, block) in self.blocks.iter().enumerate() {
+            headers.push(rows.len());
+            let mut header = format!("B{}", i);
+            let title = self.title(i);
+            if !title.is_empty() {
+                header.push_str(&format!("  {}", title));
+            }
+            if unreachable.contains(&i) {
+                header.push_str("  (unreachable)");
+            }
+            rows.push(header);
+            for statement in &block.statements {
+                rows.push(format!("      {}", statement));
+            }
+            for edge in self.successors(i) {
+                let mut line = format!("    -> B{}", edge.to);
+                let label = edge.kind.to_string();
+                if !label.is_empty() {
+                    line.push_str(&format!("  {}", label));
+                }
+                if edge.is_back() {
+                    line.push_str("  (back)");
+                }
+                // 到紧接着的块不需要画线
+                if edge.to != i + 1 {
+                    jumps.push((rows.len(), edge.to));
+                }
+                rows.push(line);
+            }
+        }
+        let arcs: Vec<(usize, usize)> = jumps.into_iter().map(|(row, to)| (row, headers[to])).collect();
+        let gutter = draw_arcs(rows.len(), &arcs);
+
+        let mut out = format!("fn {}\n\n", self.name);
+        for (margin, row) in gutter.iter().zip(&rows) {
+            out.push_str(format!("{} {}", margin, row).trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// 在左侧画出从 from 行到 to 行的连线：短的线靠近文字，不重叠的线共用一列
+fn draw_arcs(rows: usize, arcs: &[(usize, usize)]) -> Vec<String> {
+    let mut order: Vec<&(usize, usize)> = arcs.iter().collect();
+    order.sort_by_key(|(from, to)| from.abs_diff(*to));
+    let mut lanes: Vec<Vec<(usize, usize)>> = Vec::new();
+    let mut placed = Vec::new();
+    for &&(from, to) in &order {
+        let span = (from.min(to), from.max(to));
+        let free = lanes.iter().position(|lane| lane.iter().all(|&(lo, hi)| span.1 < lo || hi < span.0));
+        let lane = free.unwrap_or_else(|| {
+            lanes.push(Vec::new());
+            lanes.len() - 1
+        });
+        lanes[lane].push(span);
+        placed.push((lane, from, to, span));
+    }
+
+    let width = if lanes.is_empty() { 0 } else { lanes.len() * 2 + 1 };
+    let mut grid = vec![vec![' '; width]; rows];
+    for &(lane, _, _, (lo, hi)) in &placed {
+        let column = (lanes.len() - 1 - lane) * 2;
+        for row in grid.iter_mut().take(hi).skip(lo + 1) {
+            row[column] = '|';
+        }
+    }
+    for &(lane, from, to, _) in &placed {
+        let column = (lanes.len() - 1 - lane) * 2;
+        for row in [from, to] {
+            grid[row][column] = '+';
+            for cell in grid[row].iter_mut().skip(column + 1) {
+                if *cell == ' ' {
+                    *cell = '-';
+                }
+            }
+        }
+        grid[to][width - 1] = '>';
+    }
+    grid.into_iter().map(|row| row.into_iter().collect()).collect()
+}
+
+// DOT 字符串中的 " 和 \ 需要转义
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// 源码中所有函数的名字，按出现的顺序
+pub fn function_names(src: &str) -> Result<Vec<String>, FlowError> {
+    let trees = build_trees(src, &tokenize(src)?)?;
+    let mut names = Vec::new();
+    collect_functions(src, &trees, &mut names);
+    Ok(names)
+}
+
+fn collect_functions(src: &str, trees: &[Tree], names: &mut Vec<String>) {
+    for (i, tree) in trees.iter().enumerate() {
+        match tree {
+            Tree::Leaf(token) if text_of(src, token) == "fn" => {
+                if let Some(Tree::Leaf(name)) = trees.get(i + 1) {
+                    names.push(text_of(src, name).to_string());
+                }
+            }
+            Tree::Group { children, .. } => collect_functions(src, children, names),
+            _ => {}
+        }
+    }
+}
+
+fn find_function<'t>(src: &str, trees: &'t [Tree], name: &str) -> Option<&'t [Tree]> {
+    for (i, tree) in trees.iter().enumerate() {
+        match tree {
+            Tree::Leaf(token) if text_of(src, token) == "fn" => {
+                let is_match = matches!(trees.get(i + 1), Some(Tree::Leaf(t)) if text_of(src, t) == name);
+                if is_match {
+                    // 参数、返回值和 where 之后的第一个 {} 是函数体，没有函数体的是 trait 中的声明
+                    let rest = &trees[i + 2..];
+                    let end = rest.iter().position(|t| t.brace().is_some() || is_punct(src, t, ";"));
+                    if let Some(body) = end.and_then(|end| rest[end].brace()) {
+                        return Some(body);
+                    }
+                }
+            }
+            Tree::Group { children, .. } => {
+                if let Some(body) = find_function(src, children, name) {
+                    return Some(body);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub(crate) fn text_of<'s>(src: &'s str, token: &Token) -> &'s str {
+    &src[token.start..token.end]
+}
+
+pub(crate) fn is_punct(src: &str, tree: &Tree, punct: &str) -> bool {
+    matches!(tree, Tree::Leaf(t) if t.kind == TokenKind::Punct && text_of(src, t) == punct)
+}
+
+pub(crate) fn is_keyword(src: &str, tree: &Tree, keyword: &str) -> bool {
+    matches!(tree, Tree::Leaf(t) if t.kind == TokenKind::Ident && text_of(src, t) == keyword)
+}
+
+// 还原一段源码，词法单元之间有空白或注释的地方换成一个空格
+fn source_text(src: &str, trees: &[Tree]) -> String {
+    let mut out = String::new();
+    let mut last = None;
+    let mut push = |token: &Token, out: &mut String| {
+        if last.is_some_and(|end| end < token.start) {
+            out.push(' ');
+        }
+        out.push_str(text_of(src, token));
+        last = Some(token.end);
+    };
+    fn walk(trees: &[Tree], out: &mut String, push: &mut impl FnMut(&Token, &mut String)) {
+        for tree in trees {
+            match tree {
+                Tree::Leaf(token) => push(token, out),
+                Tree::Group { open, close, children, .. } => {
+                    push(open, out);
+                    walk(children, out, push);
+                    push(close, out);
+                }
+            }
+        }
+    }
+    walk(trees, &mut out, &mut push);
+    out
+}
+
+struct LoopFrame {
+    label: Option<String>,
+    head: usize,
+    // 以这个循环为目标的 break，循环结束后接到循环之后的块
+    breaks: Vec<(usize, EdgeKind)>,
+}
+
+// 生成控制流图：open 是正在接收语句的块，pending 是还没有确定目标的边，两者不会同时存在；
+// 都没有时说明当前位置不可达，之后的语句会放到一个没有入边的新块中
+struct Builder<'s> {
+    src: &'s str,
+    blocks: Vec<BasicBlock>,
+    edges: Vec<Edge>,
+    open: Option<usize>,
+    pending: Vec<(usize, EdgeKind)>,
+    loops: Vec<LoopFrame>,
+    returns: Vec<(usize, EdgeKind)>,
+}
+
+impl<'s> Builder<'s> {
+    fn new(src: &'s str) -> Builder<'s> {
+        let entry = BasicBlock { kind: BlockKind::Entry, statements: Vec::new() };
+        Builder {
+            src,
+            blocks: vec![entry],
+            edges: Vec::new(),
+            open: None,
+            pending: vec![(0, EdgeKind::Goto)],
+            loops: Vec::new(),
+            returns: Vec::new(),
+        }
+    }
+
+    fn finish(mut self, name: &str) -> FlowGraph {
+        let mut ends = self.take_ends();
+        ends.append(&mut self.returns);
+        self.pending = ends;
+        self.node(BlockKind::Exit);
+        FlowGraph { name: name.to_string(), blocks: self.blocks, edges: self.edges }
+    }
+
+    // 当前位置的所有出口
+    fn take_ends(&mut self) -> Vec<(usize, EdgeKind)> {
+        match self.open.take() {
+            Some(block) => vec![(block, EdgeKind::Goto)],
+            None => std::mem::take(&mut self.pending),
+        }
+    }
+
+    fn connect(&mut self, ends: Vec<(usize, EdgeKind)>, to: usize) {
+        self.edges.extend(ends.into_iter().map(|(from, kind)| Edge { from, to, kind }));
+    }
+
+    // 新建一个块，把当前位置的出口接到它上面
+    fn node(&mut self, kind: BlockKind) -> usize {
+        let id = self.blocks.len();
+        self.blocks.push(BasicBlock { kind, statements: Vec::new() });
+        let ends = self.take_ends();
+        self.connect(ends, id);
+        id
+    }
+
+    fn statement(&mut self, text: String) -> usize {
+        let block = match self.open {
+            Some(block) => block,
+            None => self.node(BlockKind::Basic),
+        };
+        self.blocks[block].statements.push(text);
+        self.open = Some(block);
+        block
+    }
+
+    fn lower_stmts(&mut self, trees: &[Tree]) -> Result<(), FlowError> {
+        let mut i = 0;
+        while i < trees.len() {
+            i = self.lower_stmt(trees, i)?;
+        }
+        Ok(())
+    }
+
+    // 处理从 trees[i] 开始的一条语句，返回下一条语句的位置
+    fn lower_stmt(&mut self, trees: &[Tree], i: usize) -> Result<usize, FlowError> {
+        let src = self.src;
+        let tree = &trees[i];
+        let keyword = |k: &str| is_keyword(src, tree, k);
+
+        if is_punct(src, tree, ";") {
+            return Ok(i + 1);
+        }
+        // #[...] 属性
+        if is_punct(src, tree, "#") || is_punct(src, tree, "#!") {
+            return Ok(i + 2);
+        }
+        if let Tree::Leaf(token @ Token { kind: TokenKind::Lifetime, .. }) = tree {
+            let label = text_of(src, token).to_string();
+            let is_loop = ["loop", "while", "for"].iter().any(|k| trees.get(i + 2).is_some_and(|t| is_keyword(src, t, k)));
+            if is_punct(src, trees.get(i + 1).unwrap_or(tree), ":") && is_loop {
+                return self.lower_loop(trees, i + 2, Some(label));
+            }
+        }
+        if keyword("loop") || keyword("while") || keyword("for") {
+            return self.lower_loop(trees, i, None);
+        }
+        if keyword("if") {
+            return self.lower_if(trees, i);
+        }
+        if keyword("match") {
+            return self.lower_match(trees, i);
+        }
+        if let Some(children) = tree.brace() {
+            self.lower_stmts(children)?;
+            return Ok(i + 1);
+        }
+        if keyword("unsafe") {
+            if let Some(children) = trees.get(i + 1).and_then(Tree::brace) {
+                self.lower_stmts(children)?;
+                return Ok(i + 2);
+            }
+        }
+        // 嵌套的函数、类型等定义不会在这里执行
+        let items = ["fn", "struct", "enum", "union", "impl", "trait", "mod", "use", "const", "static", "type", "extern"];
+        if items.iter().any(|k| keyword(k)) {
+            let end = trees[i..].iter().position(|t| t.brace().is_some() || is_punct(src, t, ";"));
+            return Ok(end.map_or(trees.len(), |end| i + end + 1));
+        }
+
+        let end = statement_end(src, trees, i);
+        if keyword("break") || keyword("continue") || keyword("return") {
+            self.lower_jump(&trees[i..end])?;
+        } else {
+            self.statement(source_text(src, &trees[i..end]));
+        }
+        Ok(end + usize::from(end < trees.len() && is_punct(src, &trees[end], ";")))
+    }
+
+    // 找到 trees[i] 之后的第一个 {}，即 if、while、for、match 的主体
+    fn body_after(&self, trees: &[Tree], i: usize, expected: &'static str) -> Result<usize, FlowError> {
+        trees[i + 1..]
+            .iter()
+            .position(|t| t.brace().is_some())
+            .map(|offset| i + 1 + offset)
+            .ok_or(FlowError::Unexpected { line: trees[i].first().line, expected })
+    }
+
+    fn lower_loop(&mut self, trees: &[Tree], i: usize, label: Option<String>) -> Result<usize, FlowError> {
+        let src = self.src;
+        let body = self.body_after(trees, i, "loop body")?;
+        let keyword = text_of(src, &trees[i].first());
+        let mut head_text = source_text(src, &trees[i..body]);
+        if let Some(label) = &label {
+            head_text = format!("{}: {}", label, head_text);
+        }
+
+        let head = self.node(BlockKind::Loop(head_text));
+        let (enter, leave) = match keyword {
+            "while" => (EdgeKind::True, Some(EdgeKind::False)),
+            "for" => (EdgeKind::Next, Some(EdgeKind::Done)),
+            _ => (EdgeKind::Goto, None),
+        };
+        self.pending = vec![(head, enter)];
+        self.loops.push(LoopFrame { label, head, breaks: Vec::new() });
+        self.lower_stmts(trees[body].brace().unwrap_or_default())?;
+        let ends = self.take_ends();
+        self.connect(ends, head);
+
+        let frame = self.loops.pop().expect("pushed above");
+        self.pending = frame.breaks;
+        if let Some(leave) = leave {
+            self.pending.push((head, leave));
+        }
+        Ok(body + 1)
+    }
+
+    fn lower_if(&mut self, trees: &[Tree], i: usize) -> Result<usize, FlowError> {
+        let src = self.src;
+        let body = self.body_after(trees, i, "`{` after if condition")?;
+        let branch = self.node(BlockKind::Branch(source_text(src, &trees[i..body])));
+        self.pending = vec![(branch, EdgeKind::True)];
+        self.lower_stmts(trees[body].brace().unwrap_or_default())?;
+        let mut ends = self.take_ends();
+
+        self.pending = vec![(branch, EdgeKind::False)];
+        let next = match trees.get(body + 1) {
+            Some(t) if is_keyword(src, t, "else") => match trees.get(body + 2) {
+                Some(t) if is_keyword(src, t, "if") => self.lower_if(trees, body + 2)?,
+                Some(Tree::Group { delimiter: '{', children, .. }) => {
+                    self.lower_stmts(children)?;
+                    body + 3
+                }
+                _ => return Err(FlowError::Unexpected { line: t.first().line, expected: "`{` or `if` after else" }),
+            },
+            _ => body + 1,
+        };
+        ends.append(&mut self.take_ends());
+        self.pending = ends;
+        Ok(next)
+    }
+
+    fn lower_match(&mut self, trees: &[Tree], i: usize) -> Result<usize, FlowError> {
+        let src = self.src;
+        let body = self.body_after(trees, i, "`{` after match expression")?;
+        let branch = self.node(BlockKind::Branch(source_text(src, &trees[i..body])));
+        let arms = trees[body].brace().unwrap_or_default();
+
+        let mut ends = Vec::new();
+        let mut start = 0;
+        while start < arms.len() {
+            let arrow = arms[start..]
+                .iter()
+                .position(|t| is_punct(src, t, "=>"))
+                .map(|offset| start + offset)
+                .ok_or(FlowError::Unexpected { line: arms[start].first().line, expected: "`=>` in match arm" })?;
+            let pattern = source_text(src, &arms[start..arrow]);
+            self.pending = vec![(branch, EdgeKind::Arm(pattern))];
+
+            // 分支是 {} 时到 } 为止，否则到下一个逗号为止
+            let end = match arms.get(arrow + 1) {
+                Some(t) if t.brace().is_some() => arrow + 2,
+                _ => arms[arrow + 1..].iter().position(|t| is_punct(src, t, ",")).map_or(arms.len(), |p| arrow + 1 + p),
+            };
+            self.lower_stmts(&arms[arrow + 1..end])?;
+            ends.append(&mut self.take_ends());
+            start = end + usize::from(arms.get(end).is_some_and(|t| is_punct(src, t, ",")));
+        }
+        self.pending = ends;
+        Ok(body + 1)
+    }
+
+    fn lower_jump(&mut self, trees: &[Tree]) -> Result<(), FlowError> {
+        let src = self.src;
+        let first = trees[0].first();
+        let keyword = text_of(src, &first);
+        let block = self.statement(source_text(src, trees));
+        self.open = None;
+        if keyword == "return" {
+            self.returns.push((block, EdgeKind::Return));
+            return Ok(());
+        }
+
+        let label = match trees.get(1) {
+            Some(Tree::Leaf(t)) if t.kind == TokenKind::Lifetime => Some(text_of(src, t).to_string()),
+            _ => None,
+        };
+        let frame = match &label {
+            None => self.loops.last_mut(),
+            Some(label) => self.loops.iter_mut().rev().find(|f| f.label.as_ref() == Some(label)),
+        };
+        let frame = match (frame, label.clone()) {
+            (Some(frame), _) => frame,
+            (None, Some(label)) => return Err(FlowError::UnknownLabel { line: first.line, label }),
+            (None, None) => return Err(FlowError::OutsideLoop { line: first.line, keyword: keyword.to_string() }),
+        };
+        if keyword == "break" {
+            frame.breaks.push((block, EdgeKind::Break(label)));
+        } else {
+            let head = frame.head;
+            self.edges.push(Edge { from: block, to: head, kind: EdgeKind::Continue(label) });
+        }
+        Ok(())
+    }
+}
+
+// 一条普通语句在分号处结束；用 {} 调用的宏（如 `thread_local! { ... }`）在 } 处结束
+fn statement_end(src: &str, trees: &[Tree], i: usize) -> usize {
+    for (j, tree) in trees.iter().enumerate().skip(i) {
+        if is_punct(src, tree, ";") {
+            return j;
+        }
+        if tree.brace().is_some() && j >= 1 && is_punct(src, &trees[j - 1], "!") {
+            return j + 1;
+        }
+    }
+    trees.len()
+}
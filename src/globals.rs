@@ -0,0 +1,159 @@
+/*
+线程安全的全局变量
+
+课程中用 `static mut` 做计数器：
+
+    static mut COUNT: i32 = 0;
+    unsafe {
+        COUNT += 1;
+    }
+
+`COUNT += 1` 实际上是三步：读出 COUNT、加 1、写回。两个线程同时执行时，可能都读到 0，
+都写回 1，少加了一次（lost update）。这还只是看得见的后果：对 `static mut` 的并发读写是数据竞争，
+属于未定义行为（undefined behaviour），编译器可以假设它不会发生，
+把值缓存在寄存器里、合并或删除读写，程序的任何表现都是 “正确” 的。所以 Rust 要求写在 unsafe 中，
+2024 edition 更是禁止直接引用 `static mut`。
+
+安全的写法不需要 unsafe：
+- 整数计数器用原子类型（`AtomicU64` 等），`fetch_add` 把读、加、写合成一个不可分割的操作，这里包装为 `Counter`
+- 其他类型用 `Mutex` 保护，同一时刻只有一个线程能访问，这里包装为 `Shared`
+- 只需要初始化一次的值用 `OnceLock`，第一次访问时初始化，之后只读
+
+`race` 模块用原子类型的分开读写模拟 `COUNT += 1` 的三步，演示少加的问题，但它本身没有未定义行为。
+ */
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+/// 原子计数器，可以直接放在 static 中：`static HITS: Counter = Counter::new(0);`
+#[derive(Debug, Default)]
+pub struct Counter {
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub const fn new(value: u64) -> Counter {
+        Counter { value: AtomicU64::new(value) }
+    }
+
+    /// 加 1，返回加之后的值；多个线程同时调用时，每个线程得到的值都不同
+    pub fn increment(&self) -> u64 {
+        self.add(1)
+    }
+
+    /// 加 n，返回加之后的值，溢出时回绕
+    pub fn add(&self, n: u64) -> u64 {
+        // 计数器不用来同步其他数据，Relaxed 就足够了
+        self.value.fetch_add(n, Ordering::Relaxed).wrapping_add(n)
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// 清零，返回清零前的值
+    pub fn reset(&self) -> u64 {
+        self.value.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// 用 Mutex 保护的全局状态，可以直接放在 static 中：`static LOG: Shared<Vec<String>> = Shared::new(Vec::new());`
+///
+/// 持有锁的线程 panic 后锁会被标记为 poisoned，这里仍然继续使用其中的数据
+#[derive(Debug, Default)]
+pub struct Shared<T> {
+    inner: Mutex<T>,
+}
+
+impl<T> Shared<T> {
+    pub const fn new(value: T) -> Shared<T> {
+        Shared { inner: Mutex::new(value) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, T> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 持有锁执行 f，读改写在同一次加锁中完成，不会被其他线程打断
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// 替换为新的值，返回旧的值
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut self.lock(), value)
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    pub fn get(&self) -> T {
+        self.lock().clone()
+    }
+}
+
+static NEXT_ID: Counter = Counter::new(0);
+
+/// 进程内唯一的编号，从 1 开始
+pub fn next_id() -> u64 {
+    NEXT_ID.increment()
+}
+
+/// 第一次调用时的时间，之后的调用都返回同一个值
+pub fn start_time() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+/// 在 threads 个线程中各调用 op iterations 次，全部结束后返回
+pub fn hammer(threads: usize, iterations: usize, op: impl Fn() + Sync) {
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..iterations {
+                    op();
+                }
+            });
+        }
+    });
+}
+
+/// 模拟 `static mut` 的 `COUNT += 1`
+pub mod race {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+
+    /// 分开读和写，与 `COUNT += 1` 一样中间可能被其他线程插入；
+    /// 每一步都是原子操作，所以只会少加，不会出现未定义行为
+    pub fn racy_increment(counter: &AtomicU64) {
+        let value = counter.load(Ordering::Relaxed);
+        // 让出 CPU，让其他线程更容易插进来
+        thread::yield_now();
+        counter.store(value + 1, Ordering::Relaxed);
+    }
+
+    /// 确定会少加的例子：两个线程都先读出 0，等对方也读完再写回 1，两次加 1 的结果是 1
+    pub fn lost_update() -> u64 {
+        let counter = AtomicU64::new(0);
+        let barrier = Barrier::new(2);
+        thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    let value = counter.load(Ordering::Relaxed);
+                    barrier.wait();
+                    counter.store(value + 1, Ordering::Relaxed);
+                });
+            }
+        });
+        counter.into_inner()
+    }
+
+    /// 在 threads 个线程中各做 iterations 次 racy_increment，返回最终的值，通常小于 threads * iterations
+    pub fn racy_total(threads: usize, iterations: usize) -> u64 {
+        let counter = AtomicU64::new(0);
+        super::hammer(threads, iterations, || racy_increment(&counter));
+        counter.into_inner()
+    }
+}
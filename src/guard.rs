@@ -0,0 +1,174 @@
+/*
+RAII 守卫（guard）
+
+值离开所在的 `{}` 时会被 drop，实现了 Drop 的类型在这时执行清理代码。这件事发生在：
+- 正常执行到 `}`
+- 提前 return、break、`?` 离开这个 scope
+- panic 展开（unwind）经过这个 scope
+
+所以把 “离开时要做的事” 放进一个值的 Drop 中，无论怎样离开都不会漏掉，这就是 RAII。
+同一个 scope 中的变量按声明的相反顺序 drop，后声明的先执行，内层 scope 的先于外层。
+
+    {
+        let _a = guard(|| println!("a"));
+        defer! { println!("b"); }
+        println!("body");
+    }   // 依次打印 body、b、a
+
+- `ScopeGuard`：离开时执行一个闭包，可以选择总是执行、只在正常离开时执行、或者只在 panic 时执行，也可以撤销
+- `defer!`、`defer_on_unwind!`、`defer_on_success!`：在当前 scope 中创建一个没有名字的 ScopeGuard
+- `ScopeTimer`：离开时报告经过的时间
+
+注意：panic 展开时执行的闭包如果再次 panic，程序会直接中止（abort）。
+ */
+
+use std::fmt::{self, Debug, Formatter};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::metrics::Histogram;
+
+/// 在什么情况下执行守卫的闭包
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum When {
+    Always,
+    /// 正常离开，没有 panic
+    OnSuccess,
+    /// 因为 panic 展开而离开
+    OnUnwind,
+}
+
+/// 离开 scope 时执行闭包
+#[must_use = "the guard runs immediately if it is not bound to a variable"]
+pub struct ScopeGuard<F: FnOnce()> {
+    f: Option<F>,
+    when: When,
+}
+
+impl<F: FnOnce()> ScopeGuard<F> {
+    pub fn new(when: When, f: F) -> ScopeGuard<F> {
+        ScopeGuard { f: Some(f), when }
+    }
+
+    pub fn when(&self) -> When {
+        self.when
+    }
+
+    /// 撤销，闭包不会再执行；比如操作全部成功后，不再需要回滚
+    pub fn dismiss(mut self) {
+        self.f = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for ScopeGuard<F> {
+    fn drop(&mut self) {
+        let run = match self.when {
+            When::Always => true,
+            When::OnSuccess => !thread::panicking(),
+            When::OnUnwind => thread::panicking(),
+        };
+        if let Some(f) = self.f.take().filter(|_| run) {
+            f();
+        }
+    }
+}
+
+impl<F: FnOnce()> Debug for ScopeGuard<F> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ScopeGuard").field("when", &self.when).field("armed", &self.f.is_some()).finish()
+    }
+}
+
+/// 离开 scope 时总是执行 f
+pub fn guard<F: FnOnce()>(f: F) -> ScopeGuard<F> {
+    ScopeGuard::new(When::Always, f)
+}
+
+/// 只在正常离开时执行 f
+pub fn on_success<F: FnOnce()>(f: F) -> ScopeGuard<F> {
+    ScopeGuard::new(When::OnSuccess, f)
+}
+
+/// 只在 panic 展开时执行 f
+pub fn on_unwind<F: FnOnce()>(f: F) -> ScopeGuard<F> {
+    ScopeGuard::new(When::OnUnwind, f)
+}
+
+/// 离开当前 scope 时执行其中的语句：`defer! { cleanup(); }`
+#[macro_export]
+macro_rules! defer {
+    ($($body:tt)*) => {
+        let _guard = $crate::guard::guard(|| { $($body)* });
+    };
+}
+
+/// 只在 panic 展开经过当前 scope 时执行其中的语句
+#[macro_export]
+macro_rules! defer_on_unwind {
+    ($($body:tt)*) => {
+        let _guard = $crate::guard::on_unwind(|| { $($body)* });
+    };
+}
+
+/// 只在正常离开当前 scope 时执行其中的语句
+#[macro_export]
+macro_rules! defer_on_success {
+    ($($body:tt)*) => {
+        let _guard = $crate::guard::on_success(|| { $($body)* });
+    };
+}
+
+// 计时器的报告方式，参数是标签和经过的时间
+type Report<'a> = Box<dyn FnOnce(&str, Duration) + 'a>;
+
+/// 离开 scope 时报告从创建到离开经过的时间
+#[must_use = "the timer reports immediately if it is not bound to a variable"]
+pub struct ScopeTimer<'a> {
+    label: String,
+    start: Instant,
+    report: Option<Report<'a>>,
+}
+
+impl<'a> ScopeTimer<'a> {
+    /// 打印到标准错误：`label: 1.234ms`
+    pub fn new(label: &str) -> ScopeTimer<'a> {
+        ScopeTimer::with(label, |label, elapsed| eprintln!("{}: {:?}", label, elapsed))
+    }
+
+    /// 离开时调用 report(label, 经过的时间)
+    pub fn with(label: &str, report: impl FnOnce(&str, Duration) + 'a) -> ScopeTimer<'a> {
+        ScopeTimer { label: label.to_string(), start: Instant::now(), report: Some(Box::new(report)) }
+    }
+
+    /// 离开时把经过的秒数记录到直方图中
+    pub fn histogram(label: &str, histogram: &'a Histogram) -> ScopeTimer<'a> {
+        ScopeTimer::with(label, |_, elapsed| histogram.observe_duration(elapsed))
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// 撤销，离开时不再报告
+    pub fn dismiss(mut self) {
+        self.report = None;
+    }
+}
+
+impl Drop for ScopeTimer<'_> {
+    fn drop(&mut self) {
+        if let Some(report) = self.report.take() {
+            report(&self.label, self.start.elapsed());
+        }
+    }
+}
+
+impl Debug for ScopeTimer<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ScopeTimer").field("label", &self.label).field("elapsed", &self.elapsed()).finish()
+    }
+}
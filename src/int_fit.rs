@@ -0,0 +1,205 @@
+/*
+选择整数类型
+
+    struct Person<'a> {
+        name: &'a str,
+        age: u8,
+    }
+
+age 为什么用 u8？年龄不会是负数，也不会超过 255，u8 只占 1 个字节，还留有 100 多的余量。
+这里根据一组样本或者取值范围，找出能装下所有值的最小有符号和无符号类型，并给出余量（headroom）：
+也就是离类型的上下限还差多少。isize 与 usize 的大小取决于平台，不参与推荐。
+
+样本可以直接给出，也可以从 CSV 文件的某一列读取。
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::bigint::BigInt;
+use crate::int_type::IntType;
+use crate::table::{Align, Table};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FitError {
+    /// 没有任何样本
+    Empty,
+    /// 范围的下限比上限大
+    InvalidRange { min: BigInt, max: BigInt },
+    /// CSV 中找不到这一列
+    ColumnNotFound(String),
+    /// CSV 中的值不是整数，line 从 1 开始
+    InvalidValue { line: usize, value: String },
+}
+
+impl Display for FitError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            FitError::Empty => write!(f, "no values to fit"),
+            FitError::InvalidRange { min, max } => write!(f, "min {} is greater than max {}", min, max),
+            FitError::ColumnNotFound(column) => write!(f, "column `{}` not found", column),
+            FitError::InvalidValue { line, value } => write!(f, "line {}: `{}` is not an integer", line, value),
+        }
+    }
+}
+
+impl std::error::Error for FitError {}
+
+/// 一个能装下所有值的类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fit {
+    pub ty: IntType,
+    /// 最小值离类型下限还差多少
+    pub headroom_below: BigInt,
+    /// 最大值离类型上限还差多少
+    pub headroom_above: BigInt,
+}
+
+impl Fit {
+    fn new(ty: IntType, min: &BigInt, max: &BigInt) -> Option<Fit> {
+        let (low, high) = (BigInt::from(ty.min()), BigInt::from(ty.max()));
+        if *min < low || *max > high {
+            return None;
+        }
+        Some(Fit { ty, headroom_below: min - &low, headroom_above: &high - max })
+    }
+
+    /// 最大值占类型上限的百分比，最大值为负数时是负数
+    pub fn percent_of_max(&self) -> f64 {
+        // headroom_above 不会超过类型的取值个数，一定能放进 u128
+        let headroom = self.headroom_above.to_u128().unwrap_or(u128::MAX) as f64;
+        let max = self.ty.max() as f64;
+        (max - headroom) / max * 100.0
+    }
+}
+
+/// 推荐结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Advice {
+    pub min: BigInt,
+    pub max: BigInt,
+    /// 能装下的最小有符号类型
+    pub signed: Option<Fit>,
+    /// 能装下的最小无符号类型，有负数时为 None
+    pub unsigned: Option<Fit>,
+}
+
+// 按位数从小到大，不包含 isize 与 usize
+const SIGNED: [IntType; 5] = [IntType::I8, IntType::I16, IntType::I32, IntType::I64, IntType::I128];
+const UNSIGNED: [IntType; 5] = [IntType::U8, IntType::U16, IntType::U32, IntType::U64, IntType::U128];
+
+impl Advice {
+    /// 最终推荐的类型：位数更少的那个，位数相同时优先无符号，因为它表达了 “不会是负数”
+    pub fn recommended(&self) -> Option<&Fit> {
+        match (&self.signed, &self.unsigned) {
+            (Some(s), Some(u)) if s.ty.bits() < u.ty.bits() => Some(s),
+            (_, Some(u)) => Some(u),
+            (s, None) => s.as_ref(),
+        }
+    }
+}
+
+/// 根据取值范围推荐
+pub fn advise_range(min: BigInt, max: BigInt) -> Result<Advice, FitError> {
+    if min > max {
+        return Err(FitError::InvalidRange { min, max });
+    }
+    let signed = SIGNED.iter().find_map(|&ty| Fit::new(ty, &min, &max));
+    let unsigned = UNSIGNED.iter().find_map(|&ty| Fit::new(ty, &min, &max));
+    Ok(Advice { min, max, signed, unsigned })
+}
+
+/// 根据一组样本推荐
+pub fn advise<I>(values: I) -> Result<Advice, FitError>
+where
+    I: IntoIterator,
+    I::Item: Into<BigInt>,
+{
+    let mut range: Option<(BigInt, BigInt)> = None;
+    for value in values {
+        let value = value.into();
+        range = Some(match range {
+            None => (value.clone(), value),
+            Some((min, max)) => (min.min(value.clone()), max.max(value)),
+        });
+    }
+    let (min, max) = range.ok_or(FitError::Empty)?;
+    advise_range(min, max)
+}
+
+/// 读取 CSV 中的一列再推荐，第一行是表头，column 可以是列名或从 0 开始的列号
+///
+/// 空的单元格会被跳过，支持用双引号包住的字段
+pub fn advise_csv(text: &str, column: &str) -> Result<Advice, FitError> {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines.next().ok_or(FitError::Empty)?;
+    let header = split_csv_line(header);
+    let index = header
+        .iter()
+        .position(|name| name == column)
+        .or_else(|| column.parse::<usize>().ok().filter(|&i| i < header.len()))
+        .ok_or_else(|| FitError::ColumnNotFound(column.to_string()))?;
+
+    let mut values = Vec::new();
+    for (i, line) in lines {
+        let cells = split_csv_line(line);
+        let cell = cells.get(index).map(String::as_str).unwrap_or("");
+        if cell.is_empty() {
+            continue;
+        }
+        let value = cell
+            .replace('_', "")
+            .parse::<BigInt>()
+            .map_err(|_| FitError::InvalidValue { line: i + 1, value: cell.to_string() })?;
+        values.push(value);
+    }
+    advise(values)
+}
+
+// 按逗号拆分一行，双引号中的逗号不拆分，"" 表示一个双引号
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// 生成 `learn_rust fit` 命令的输出
+pub fn render(advice: &Advice) -> String {
+    let mut out = format!("min = {}, max = {}\n\n", advice.min, advice.max);
+    let mut table = Table::new(["type", "fits", "headroom below", "headroom above", "max used"])
+        .align(2, Align::Right)
+        .align(3, Align::Right)
+        .align(4, Align::Right);
+    for ty in SIGNED.iter().chain(UNSIGNED.iter()) {
+        match Fit::new(*ty, &advice.min, &advice.max) {
+            Some(fit) => table.row([
+                ty.to_string(),
+                "yes".to_string(),
+                fit.headroom_below.to_string(),
+                fit.headroom_above.to_string(),
+                format!("{:.1}%", fit.percent_of_max()),
+            ]),
+            None => table.row([ty.to_string(), "no".to_string()]),
+        }
+    }
+    out.push_str(&table.to_string());
+    out.push('\n');
+    match advice.recommended() {
+        Some(fit) => out.push_str(&format!("recommended: {}\n", fit.ty)),
+        None => out.push_str("recommended: none, the values do not fit in 128 bits, use BigInt\n"),
+    }
+    out
+}
@@ -0,0 +1,175 @@
+/*
+整数类型的描述
+
+| Length  | Signed | Unsigned |
+| ------- | ------ | -------- |
+| 8-bit   | i8     | u8       |
+| 16-bit  | i16    | u16      |
+| 32-bit  | i32    | u32      |
+| 64-bit  | i64    | u64      |
+| 128-bit | i128   | u128     |
+| arch    | isize  | usize    |
+
+各个工具模块需要在运行时根据名字选择类型（比如命令行里输入 `u8`），
+所以用一个枚举来描述这些类型，值统一用 u128 的位模式保存。
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntType {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+}
+
+impl IntType {
+    /// 所有整数类型，有符号在前
+    pub const ALL: [IntType; 12] = [
+        IntType::I8,
+        IntType::I16,
+        IntType::I32,
+        IntType::I64,
+        IntType::I128,
+        IntType::Isize,
+        IntType::U8,
+        IntType::U16,
+        IntType::U32,
+        IntType::U64,
+        IntType::U128,
+        IntType::Usize,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IntType::I8 => "i8",
+            IntType::I16 => "i16",
+            IntType::I32 => "i32",
+            IntType::I64 => "i64",
+            IntType::I128 => "i128",
+            IntType::Isize => "isize",
+            IntType::U8 => "u8",
+            IntType::U16 => "u16",
+            IntType::U32 => "u32",
+            IntType::U64 => "u64",
+            IntType::U128 => "u128",
+            IntType::Usize => "usize",
+        }
+    }
+
+    /// 位数，isize 和 usize 由计算机架构决定
+    pub fn bits(self) -> u32 {
+        match self {
+            IntType::I8 | IntType::U8 => 8,
+            IntType::I16 | IntType::U16 => 16,
+            IntType::I32 | IntType::U32 => 32,
+            IntType::I64 | IntType::U64 => 64,
+            IntType::I128 | IntType::U128 => 128,
+            IntType::Isize | IntType::Usize => usize::BITS,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(
+            self,
+            IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64 | IntType::I128 | IntType::Isize
+        )
+    }
+
+    /// 该类型位数对应的掩码，如 u8 为 0xff
+    pub fn mask(self) -> u128 {
+        match self.bits() {
+            128 => u128::MAX,
+            bits => (1u128 << bits) - 1,
+        }
+    }
+
+    /// 最小值，无符号类型为 0
+    pub fn min(self) -> i128 {
+        if self.is_signed() {
+            i128::MIN >> (128 - self.bits())
+        } else {
+            0
+        }
+    }
+
+    /// 最大值
+    pub fn max(self) -> u128 {
+        if self.is_signed() {
+            (1u128 << (self.bits() - 1)) - 1
+        } else {
+            self.mask()
+        }
+    }
+
+    /// 把位模式按本类型解释为有符号数，只对能放进 i128 的值有意义
+    pub fn to_i128(self, bits: u128) -> i128 {
+        let bits = bits & self.mask();
+        if self.is_signed() && self.bits() < 128 && bits >> (self.bits() - 1) == 1 {
+            // 最高位是符号位，做符号扩展
+            (bits | !self.mask()) as i128
+        } else {
+            bits as i128
+        }
+    }
+
+    /// 把位模式按本类型转成十进制字符串
+    pub fn format_decimal(self, bits: u128) -> String {
+        let bits = bits & self.mask();
+        if self.is_signed() {
+            self.to_i128(bits).to_string()
+        } else {
+            bits.to_string()
+        }
+    }
+
+    /// 有符号数 value 是否在本类型的范围内
+    pub fn contains_i128(self, value: i128) -> bool {
+        value >= self.min() && (value < 0 || value as u128 <= self.max())
+    }
+
+    /// 无符号数 value 是否在本类型的范围内
+    pub fn contains_u128(self, value: u128) -> bool {
+        value <= self.max()
+    }
+}
+
+impl Display for IntType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// 类型名不认识
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownType(pub String);
+
+impl Display for UnknownType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "unknown integer type `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownType {}
+
+impl FromStr for IntType {
+    type Err = UnknownType;
+
+    fn from_str(s: &str) -> Result<IntType, UnknownType> {
+        IntType::ALL
+            .iter()
+            .copied()
+            .find(|t| t.name() == s)
+            .ok_or_else(|| UnknownType(s.to_string()))
+    }
+}
@@ -0,0 +1,463 @@
+/*
+内存布局
+
+结构体的大小不一定等于各字段大小之和：每个字段要放在自身对齐（alignment）的整数倍上，
+整个结构体的大小也要是对齐的整数倍，中间和末尾空出来的字节就是填充（padding）。
+Rust 默认的布局（repr(Rust)）还会重新排列字段，减少填充。
+
+枚举要额外记录当前是哪个取值（tag），但如果某个字段有不可能出现的取值（niche），
+比如引用不可能为空、char 不可能超过 0x10FFFF，tag 可以借用这些取值，不占额外的空间：
+
+    size_of::<&str>() == 16
+    size_of::<Option<&str>>() == 16   // None 用空指针表示
+
+这里的数字都是在运行时用 size_of、align_of、offset_of 和字段引用的地址计算出来的，
+没有假设具体的布局。
+ */
+
+use std::fmt::Write;
+use std::mem::{align_of, offset_of, size_of, size_of_val};
+use std::ops::Range;
+
+use crate::table::{Align, Table};
+
+/// 与 tests/test_custom_struct.rs 中定义相同的类型，用来查看它们的布局
+pub mod lesson {
+    #[derive(Debug)]
+    pub struct Person<'a> {
+        pub name: &'a str,
+        pub age: u8,
+    }
+
+    // 单元结构体
+    pub struct Nil;
+
+    // 元组结构体
+    pub struct Pair(pub i32, pub f32);
+
+    pub struct Point {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    pub struct Rectangle {
+        pub p1: Point,
+        pub p2: Point,
+    }
+
+    pub enum WebEvent {
+        PageLoad,
+        PageUnload,
+        KeyPress(char),
+        Paste(String),
+        Click { x: i64, y: i64 },
+    }
+
+    // 隐式辨别值，从 0 开始
+    pub enum Number {
+        Zero,
+        One,
+        Two,
+    }
+
+    // 显式辨别值
+    pub enum Color {
+        Red = 0xff0000,
+        Green = 0x00ff00,
+        Blue = 0x0000ff,
+    }
+}
+
+use lesson::{Color, Nil, Number, Pair, Person, Point, Rectangle, WebEvent};
+
+/// 一个字段的位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl Field {
+    pub fn bytes(&self) -> Range<usize> {
+        self.offset..self.offset + self.size
+    }
+}
+
+/// 枚举的一个取值和它的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: &'static str,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    /// 结构体或基本类型，基本类型没有字段
+    Struct(Vec<Field>),
+    Enum(Vec<Variant>),
+}
+
+/// 枚举的 tag 放在哪里
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tag {
+    /// 某个取值的字段占满了整个枚举，tag 只能借用这个字段的 niche
+    Niche { variant: &'static str, field: String },
+    /// 所有取值的字段都没有用到的字节，tag 就在其中（可能还有填充）
+    Bytes(Vec<Range<usize>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeLayout {
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    /// `Option<T>` 的大小，与 size 相同说明 T 有 niche
+    pub option_size: usize,
+    /// 类型本身是 `Option<T>` 时，T 的大小
+    pub inner_size: Option<usize>,
+    pub shape: Shape,
+}
+
+impl TypeLayout {
+    fn new<T>(name: &'static str, shape: Shape) -> TypeLayout {
+        TypeLayout {
+            name,
+            size: size_of::<T>(),
+            align: align_of::<T>(),
+            option_size: size_of::<Option<T>>(),
+            inner_size: None,
+            shape,
+        }
+    }
+
+    /// 包在 Option 里不会变大；类型本身是 `Option<T>` 时，比较的是它与 T
+    pub fn has_niche(&self) -> bool {
+        self.size == self.inner_size.unwrap_or(self.option_size)
+    }
+
+    /// 结构体中没有被字段占用的字节
+    pub fn padding(&self) -> Vec<Range<usize>> {
+        match &self.shape {
+            Shape::Struct(fields) if !fields.is_empty() => unused_bytes(self.size, fields),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 枚举的 tag 所在的位置，结构体返回 None
+    pub fn tag(&self) -> Option<Tag> {
+        let Shape::Enum(variants) = &self.shape else {
+            return None;
+        };
+        for variant in variants {
+            if self.size > 0 && unused_bytes(self.size, &variant.fields).is_empty() {
+                let field = variant.fields.iter().max_by_key(|f| f.size).map(|f| f.name.clone()).unwrap_or_default();
+                return Some(Tag::Niche { variant: variant.name, field });
+            }
+        }
+        let used: Vec<Field> = variants.iter().flat_map(|v| v.fields.iter().cloned()).collect();
+        Some(Tag::Bytes(unused_bytes(self.size, &used)))
+    }
+}
+
+// [0, size) 中没有被任何字段覆盖的区间
+fn unused_bytes(size: usize, fields: &[Field]) -> Vec<Range<usize>> {
+    let mut used = vec![false; size];
+    for field in fields {
+        used[field.bytes()].iter_mut().for_each(|b| *b = true);
+    }
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (i, _) in used.iter().enumerate().filter(|(_, used)| !**used) {
+        match ranges.last_mut() {
+            Some(range) if range.end == i => range.end += 1,
+            _ => ranges.push(i..i + 1),
+        }
+    }
+    ranges
+}
+
+/// 去掉类型名中的模块路径：`alloc::string::String` 变成 `String`
+pub fn short_type_name<T: ?Sized>() -> String {
+    let full = std::any::type_name::<T>();
+    let mut out = String::new();
+    for piece in full.split_inclusive(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')) {
+        let (path, delimiter) = match piece.char_indices().last() {
+            Some((i, c)) if !(c.is_alphanumeric() || c == '_' || c == ':') => (&piece[..i], &piece[i..]),
+            _ => (piece, ""),
+        };
+        out.push_str(path.rsplit("::").next().unwrap_or(path));
+        out.push_str(delimiter);
+    }
+    out
+}
+
+// 通过字段引用的地址计算它在值中的偏移，枚举的字段只能这样取得
+fn field_of<T, F>(base: &T, name: &str, field: &F) -> Field {
+    Field {
+        name: name.to_string(),
+        ty: short_type_name::<F>(),
+        offset: field as *const F as usize - base as *const T as usize,
+        size: size_of_val(field),
+    }
+}
+
+// 结构体的字段可以直接用 offset_of! 计算
+macro_rules! struct_fields {
+    ($t:ty { $($field:tt: $fty:ty),* $(,)? }) => {
+        Shape::Struct(vec![$(Field {
+            name: stringify!($field).to_string(),
+            ty: short_type_name::<$fty>(),
+            offset: offset_of!($t, $field),
+            size: size_of::<$fty>(),
+        }),*])
+    };
+}
+
+fn primitive<T>(name: &'static str) -> TypeLayout {
+    TypeLayout::new::<T>(name, Shape::Struct(Vec::new()))
+}
+
+fn option<T>(name: &'static str, sample: T) -> TypeLayout {
+    let values = [None, Some(sample)];
+    let variants = values
+        .iter()
+        .map(|value| match value {
+            None => Variant { name: "None", fields: Vec::new() },
+            Some(inner) => Variant { name: "Some", fields: vec![field_of(value, "0", inner)] },
+        })
+        .collect();
+    TypeLayout { inner_size: Some(size_of::<T>()), ..TypeLayout::new::<Option<T>>(name, Shape::Enum(variants)) }
+}
+
+// 没有字段的 C 风格枚举
+fn c_style<T>(name: &'static str, variants: &[&'static str]) -> TypeLayout {
+    let variants = variants.iter().map(|name| Variant { name, fields: Vec::new() }).collect();
+    TypeLayout::new::<T>(name, Shape::Enum(variants))
+}
+
+fn web_event() -> TypeLayout {
+    let events = [
+        WebEvent::PageLoad,
+        WebEvent::PageUnload,
+        WebEvent::KeyPress('x'),
+        WebEvent::Paste(String::new()),
+        WebEvent::Click { x: 0, y: 0 },
+    ];
+    let variants = events
+        .iter()
+        .map(|event| match event {
+            WebEvent::PageLoad => Variant { name: "PageLoad", fields: Vec::new() },
+            WebEvent::PageUnload => Variant { name: "PageUnload", fields: Vec::new() },
+            WebEvent::KeyPress(c) => Variant { name: "KeyPress", fields: vec![field_of(event, "0", c)] },
+            WebEvent::Paste(s) => Variant { name: "Paste", fields: vec![field_of(event, "0", s)] },
+            WebEvent::Click { x, y } => {
+                Variant { name: "Click", fields: vec![field_of(event, "x", x), field_of(event, "y", y)] }
+            }
+        })
+        .collect();
+    TypeLayout::new::<WebEvent>("WebEvent", Shape::Enum(variants))
+}
+
+/// `learn_rust layout` 支持的所有类型：课程中的自定义类型，以及几个用来对比 niche 的标准库类型
+pub fn all() -> Vec<TypeLayout> {
+    vec![
+        TypeLayout::new::<Person>("Person<'a>", struct_fields!(Person { name: &str, age: u8 })),
+        TypeLayout::new::<Nil>("Nil", struct_fields!(Nil {})),
+        TypeLayout::new::<Pair>("Pair", struct_fields!(Pair { 0: i32, 1: f32 })),
+        TypeLayout::new::<Point>("Point", struct_fields!(Point { x: f32, y: f32 })),
+        TypeLayout::new::<Rectangle>("Rectangle", struct_fields!(Rectangle { p1: Point, p2: Point })),
+        web_event(),
+        c_style::<Number>("Number", &["Zero", "One", "Two"]),
+        c_style::<Color>("Color", &["Red", "Green", "Blue"]),
+        primitive::<&str>("&str"),
+        option("Option<&str>", ""),
+        primitive::<String>("String"),
+        option("Option<String>", String::new()),
+        primitive::<Box<i32>>("Box<i32>"),
+        option("Option<Box<i32>>", Box::new(0)),
+        primitive::<char>("char"),
+        option("Option<char>", 'x'),
+        primitive::<bool>("bool"),
+        option("Option<bool>", false),
+        primitive::<u32>("u32"),
+        option("Option<u32>", 0u32),
+        primitive::<f64>("f64"),
+        option("Option<f64>", 0.0f64),
+    ]
+}
+
+/// 按名字查找，忽略空白，`Person` 与 `Person<'a>` 都可以
+pub fn lookup(name: &str) -> Option<TypeLayout> {
+    let normalize = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    let name = normalize(name);
+    all().into_iter().find(|layout| {
+        let candidate = normalize(layout.name);
+        candidate == name || candidate.strip_suffix("<'a>") == Some(name.as_str())
+    })
+}
+
+// 每个字节一个字符，8 个一组：字段用 A、B、C…，没用到的字节用 .
+fn byte_row(size: usize, fields: &[Field]) -> String {
+    let mut cells = vec!['.'; size];
+    for (field, letter) in fields.iter().zip('A'..='Z') {
+        cells[field.bytes()].iter_mut().for_each(|c| *c = letter);
+    }
+    let groups: Vec<String> = cells.chunks(8).map(|chunk| chunk.iter().collect()).collect();
+    groups.join(" ")
+}
+
+// 字节图的刻度行：每组的起始偏移
+fn byte_ruler(size: usize) -> String {
+    let marks: Vec<String> = (0..size).step_by(8).map(|offset| format!("{:<8}", offset)).collect();
+    marks.join(" ").trim_end().to_string()
+}
+
+fn legend(fields: &[Field]) -> String {
+    let names: Vec<String> = fields.iter().zip('A'..='Z').map(|(f, letter)| format!("{} = {}", letter, f.name)).collect();
+    names.join(", ")
+}
+
+fn byte_count(n: usize) -> String {
+    if n == 1 {
+        "1 byte".to_string()
+    } else {
+        format!("{} bytes", n)
+    }
+}
+
+fn ranges(ranges: &[Range<usize>]) -> String {
+    let parts: Vec<String> = ranges.iter().map(|r| format!("{}..{}", r.start, r.end)).collect();
+    parts.join(", ")
+}
+
+/// 生成 `learn_rust layout <type>` 的输出
+pub fn render(layout: &TypeLayout) -> String {
+    let mut out = format!("{}\nsize {}, align {}\n", layout.name, layout.size, layout.align);
+    if let Some(inner_size) = layout.inner_size {
+        let inner = layout.name.strip_prefix("Option<").and_then(|s| s.strip_suffix('>')).unwrap_or(layout.name);
+        if layout.has_niche() {
+            let _ = writeln!(out, "same size as {}: None uses a niche", inner);
+        } else {
+            let _ = writeln!(
+                out,
+                "{} is {}: no niche, the tag needs {} more",
+                inner,
+                byte_count(inner_size),
+                byte_count(layout.size - inner_size)
+            );
+        }
+    } else if layout.has_niche() {
+        let _ = writeln!(out, "Option<{}> is also {}: None uses a niche", layout.name, byte_count(layout.option_size));
+    } else {
+        let _ = writeln!(
+            out,
+            "Option<{}> is {}: no niche, the tag needs {} more",
+            layout.name,
+            byte_count(layout.option_size),
+            byte_count(layout.option_size - layout.size)
+        );
+    }
+
+    match &layout.shape {
+        Shape::Struct(fields) if fields.is_empty() => {}
+        Shape::Struct(fields) => {
+            let mut table = Table::new(["field", "type", "offset", "size"]).align(2, Align::Right).align(3, Align::Right);
+            for field in fields {
+                table.row([field.name.clone(), field.ty.clone(), field.offset.to_string(), field.size.to_string()]);
+            }
+            let _ = write!(out, "\n{}", table);
+            let padding = layout.padding();
+            let total: usize = padding.iter().map(|r| r.len()).sum();
+            match total {
+                0 => out.push_str("padding: none\n"),
+                _ => {
+                    let _ = writeln!(out, "padding: {} at {}", byte_count(total), ranges(&padding));
+                }
+            }
+        }
+        Shape::Enum(variants) => {
+            let mut table = Table::new(["variant", "field", "type", "offset", "size"])
+                .align(3, Align::Right)
+                .align(4, Align::Right);
+            for variant in variants {
+                if variant.fields.is_empty() {
+                    table.row([variant.name]);
+                }
+                for (i, field) in variant.fields.iter().enumerate() {
+                    let name = if i == 0 { variant.name } else { "" };
+                    table.row([
+                        name.to_string(),
+                        field.name.clone(),
+                        field.ty.clone(),
+                        field.offset.to_string(),
+                        field.size.to_string(),
+                    ]);
+                }
+            }
+            let _ = write!(out, "\n{}", table);
+            match layout.tag() {
+                Some(Tag::Niche { variant, field }) => {
+                    let _ = writeln!(
+                        out,
+                        "tag: {}.{} fills all {} bytes, the other variants are stored as its invalid values (niche)",
+                        variant, field, layout.size
+                    );
+                }
+                Some(Tag::Bytes(bytes)) => {
+                    let _ = writeln!(out, "tag: in bytes {} (with any padding), unused by every variant", ranges(&bytes));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if layout.size == 0 {
+        out.push_str("\nzero-sized: takes no bytes at all\n");
+        return out;
+    }
+    match &layout.shape {
+        // 基本类型没有字段，不需要字节图
+        Shape::Struct(fields) if fields.is_empty() => {}
+        Shape::Struct(fields) => {
+            let _ = writeln!(out, "\nbyte  {}", byte_ruler(layout.size));
+            let _ = writeln!(out, "      {}", byte_row(layout.size, fields));
+            let _ = writeln!(out, "{}, . = padding", legend(fields));
+        }
+        Shape::Enum(variants) => {
+            let width = variants.iter().map(|v| v.name.len()).max().unwrap_or(0).max(4);
+            let _ = writeln!(out, "\n{:width$}  {}", "byte", byte_ruler(layout.size));
+            for variant in variants {
+                let mut line = format!("{:width$}  {}", variant.name, byte_row(layout.size, &variant.fields));
+                if !variant.fields.is_empty() {
+                    let _ = write!(line, "  {}", legend(&variant.fields));
+                }
+                let _ = writeln!(out, "{}", line.trim_end());
+            }
+            out.push_str(". = not used by the variant's fields\n");
+        }
+    }
+    out
+}
+
+/// 生成 `learn_rust layout` 的输出：所有类型的大小对比
+pub fn render_summary() -> String {
+    let mut table = Table::new(["type", "size", "align", "Option size", "niche"])
+        .align(1, Align::Right)
+        .align(2, Align::Right)
+        .align(3, Align::Right);
+    for layout in all() {
+        table.row([
+            layout.name.to_string(),
+            layout.size.to_string(),
+            layout.align.to_string(),
+            // Option<T> 这一行的 niche 说的是它与 T 相比，再包一层 Option 的大小没有意义
+            match layout.inner_size {
+                Some(_) => "-".to_string(),
+                None => layout.option_size.to_string(),
+            },
+            if layout.has_niche() { "yes" } else { "no" }.to_string(),
+        ]);
+    }
+    table.to_string()
+}
@@ -0,0 +1,88 @@
+/*
+learn_rust 的库部分
+
+tests/ 目录下的课程只是演示语法，这里放的是课程中延伸出来、可以被复用的工具模块，
+命令行入口 src/main.rs 会调用这些模块。
+ */
+
+// Unicode 字符检查：码点、编码、分类与显示宽度
+pub mod chars;
+
+// 整数类型的描述，供各个工具模块在运行时选择类型
+pub mod int_type;
+
+// 按列对齐的文本表格
+pub mod table;
+
+// 位运算的二进制对照表
+pub mod bitwise;
+
+// 任意精度整数，用来计算超出 i128 范围的结果
+pub mod bigint;
+
+// 定点小数，以及建立在它之上的金额类型
+pub mod decimal;
+pub mod money;
+
+// 整数除法与取余在负数、除数为 0 和 MIN / -1 时的行为
+pub mod division;
+
+// `as` 数值转换的行为，以及对应的 From / TryFrom 写法
+pub mod cast;
+
+// 为超过 12 个元素的元组提供 Debug 打印、比较与遍历
+pub mod tuple;
+
+// 基于嵌套数组、用常量泛型确定行列数的矩阵
+pub mod matrix;
+
+// Python 风格的切片：负数下标、步长与越界截断
+pub mod slicing;
+
+// 课程中自定义类型的内存布局：大小、对齐、字段偏移、填充与 niche
+pub mod layout;
+
+// 根据样本或取值范围推荐能装下的最小整数类型
+pub mod int_fit;
+
+// 基本类型与大端、小端、本机字节序字节数组的互相转换
+pub mod endian;
+
+// 记录循环的每次迭代、绑定的值以及带标签的 break / continue
+pub mod loop_trace;
+
+// 从课程函数的源码生成控制流图，输出 DOT 与终端字符画
+pub mod flow_graph;
+
+// 任意正负步长的整数范围、可倒序的迭代与不累积误差的浮点数范围
+pub mod ranges;
+
+// 按顺序或组合的规则把数字映射为标签，规则可以从 TOML 文本读取
+pub mod rules;
+
+// 带退避的失败重试：固定、指数与随机抖动的等待时间，可替换的时钟
+pub mod retry;
+
+// 替代 static mut 的线程安全全局变量：原子计数器、Mutex 与 OnceLock
+pub mod globals;
+
+// 全局的指标注册表：counter、gauge 与 histogram，导出为 Prometheus 文本格式或 JSON
+pub mod metrics;
+
+// 用 const fn 在编译时生成的查找表：CRC32、10 的幂与字节中 1 的个数
+pub mod const_tables;
+
+// 分析源码中变量的绑定：声明的位置、所在的 scope、遮蔽关系、最后一次使用与未使用的变量
+pub mod scopes;
+
+// RAII 守卫：离开 scope 时执行的闭包、defer!、只在 panic 时执行的守卫与计时器
+pub mod guard;
+
+// 函数调用跟踪：#[trace] 记录参数、返回值、耗时与嵌套深度，需要打开 trace feature
+pub mod call_trace;
+
+// 数值类型的 trait：0 与 1、溢出检查、无损的放宽与必须选择方式的收窄，以及对任何数值类型都能用的 add、sum、mean
+pub mod numeric;
+
+// 记录所有权变化的字符串：分配、深拷贝、转移与释放按发生的顺序写入当前线程的日志
+pub mod traced;
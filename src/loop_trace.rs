@@ -0,0 +1,581 @@
+/*
+循环跟踪
+
+嵌套循环配合标签时，很难只看代码想清楚哪一行执行了、哪一行没有执行：
+
+    'outer: loop {
+        count = 2;
+        'inner: loop {
+            count = 3;
+            break 'outer;   // 退出 'outer 的循环
+        }
+        count = 4; // 这行不会执行
+    }
+
+`trace_loop!` 包住一个 loop、while 或 for，展开时在每次迭代、每条语句、每个 break / continue
+处插入记录，同时记下指定变量当时的值，最后按步骤打印出来：
+
+    let trace = LoopTrace::new();
+    trace_loop!(trace, [count], 'outer: loop { ... });
+    print!("{}", trace);
+
+宏只改写循环体中的这几种语句：嵌套的 loop / while / for、if / else if / else、break、continue，
+其他语句（包括 match）整体作为一条语句记录，match 分支中的 break 不会被记录。
+while 和 for 会被改写成等价的 loop，这样才能区分 “条件不满足而结束” 与 “被 break 或 return 带出”。
+ */
+
+use std::cell::RefCell;
+use std::fmt::{self, Display, Formatter};
+
+use crate::table::{Align, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopKind {
+    Loop,
+    While,
+    For,
+}
+
+impl LoopKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            LoopKind::Loop => "loop",
+            LoopKind::While => "while",
+            LoopKind::For => "for",
+        }
+    }
+}
+
+/// 一个循环是怎样结束的
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exit {
+    /// while 的条件不满足，或者 for 的迭代器耗尽
+    Finished,
+    /// 被以它为目标的 break 结束，记录 break 写的标签
+    Break(Option<&'static str>),
+    /// 内层的 break 'label 跳出了这个循环
+    OuterBreak(&'static str),
+    /// 内层的 continue 'label 跳出了这个循环
+    OuterContinue(&'static str),
+    /// return、? 或 panic
+    Early,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Enter { label: Option<&'static str>, kind: LoopKind },
+    /// number 从 1 开始
+    Iteration { label: Option<&'static str>, number: usize },
+    Statement(&'static str),
+    Break { label: Option<&'static str> },
+    Continue { label: Option<&'static str> },
+    Exit { label: Option<&'static str>, iterations: usize, exit: Exit },
+}
+
+/// 跟踪中的一步
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// 嵌套的层数，最外层的循环为 0
+    pub depth: usize,
+    pub event: Event,
+    /// 记录时被跟踪变量的 Debug 输出，循环结束时没有
+    pub bindings: Vec<(&'static str, String)>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    label: Option<&'static str>,
+    iterations: usize,
+    finished: bool,
+}
+
+// 已经执行、但还没有到达目标循环的 break 或 continue
+#[derive(Debug, Clone, Copy)]
+struct Jump {
+    is_break: bool,
+    label: Option<&'static str>,
+    // 目标循环在 frames 中的下标，标签不属于被跟踪的循环时为 None
+    target: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    steps: Vec<Step>,
+    frames: Vec<Frame>,
+    jump: Option<Jump>,
+    declared: Vec<&'static str>,
+}
+
+/// 记录 `trace_loop!` 产生的所有步骤，方法都只需要 &self，循环体中也可以使用
+#[derive(Debug, Default)]
+pub struct LoopTrace {
+    state: RefCell<State>,
+}
+
+type Bindings = Vec<(&'static str, String)>;
+
+impl LoopTrace {
+    pub fn new() -> LoopTrace {
+        LoopTrace::default()
+    }
+
+    fn push(&self, depth: usize, event: Event, bindings: Bindings) {
+        self.state.borrow_mut().steps.push(Step { depth, event, bindings });
+    }
+
+    fn depth(&self) -> usize {
+        self.state.borrow().frames.len()
+    }
+
+    // 以下方法由 trace_loop! 展开后调用
+
+    /// 记录循环体中所有的语句，用来找出没有执行过的语句
+    pub fn declare(&self, statements: &[&'static str]) {
+        let declared = &mut self.state.borrow_mut().declared;
+        for statement in statements {
+            if !declared.contains(statement) {
+                declared.push(statement);
+            }
+        }
+    }
+
+    /// 进入一个循环，返回的 guard 被 drop 时记录循环结束
+    pub fn enter(&self, label: Option<&'static str>, kind: LoopKind, bindings: Bindings) -> LoopGuard<'_> {
+        self.push(self.depth(), Event::Enter { label, kind }, bindings);
+        self.state.borrow_mut().frames.push(Frame { label, iterations: 0, finished: false });
+        LoopGuard { trace: self }
+    }
+
+    /// 开始新的一次迭代
+    pub fn iteration(&self, bindings: Bindings) {
+        let (label, number) = {
+            let mut state = self.state.borrow_mut();
+            let top = state.frames.len() - 1;
+            // 以当前循环为目标的 continue 到这里就完成了
+            if matches!(state.jump, Some(Jump { is_break: false, target: Some(t), .. }) if t == top) {
+                state.jump = None;
+            }
+            let frame = &mut state.frames[top];
+            frame.iterations += 1;
+            (frame.label, frame.iterations)
+        };
+        self.push(self.depth() - 1, Event::Iteration { label, number }, bindings);
+    }
+
+    pub fn statement(&self, text: &'static str, bindings: Bindings) {
+        self.push(self.depth(), Event::Statement(text), bindings);
+    }
+
+    pub fn record_break(&self, label: Option<&'static str>, bindings: Bindings) {
+        self.jump(true, label);
+        self.push(self.depth(), Event::Break { label }, bindings);
+    }
+
+    pub fn record_continue(&self, label: Option<&'static str>, bindings: Bindings) {
+        self.jump(false, label);
+        self.push(self.depth(), Event::Continue { label }, bindings);
+    }
+
+    /// while 的条件不满足或 for 的迭代器耗尽
+    pub fn finished(&self) {
+        if let Some(frame) = self.state.borrow_mut().frames.last_mut() {
+            frame.finished = true;
+        }
+    }
+
+    fn jump(&self, is_break: bool, label: Option<&'static str>) {
+        let mut state = self.state.borrow_mut();
+        // 没有标签时跳到最内层的循环
+        let target = match label {
+            None => state.frames.len().checked_sub(1),
+            Some(_) => state.frames.iter().rposition(|f| f.label == label),
+        };
+        state.jump = Some(Jump { is_break, label, target });
+    }
+
+    fn exit(&self) {
+        let (frame, exit) = {
+            let mut state = self.state.borrow_mut();
+            let frame = state.frames.pop().expect("exit without enter");
+            let index = state.frames.len();
+            let exit = match state.jump {
+                _ if frame.finished => Exit::Finished,
+                Some(Jump { is_break: true, label, target }) if target == Some(index) => {
+                    state.jump = None;
+                    Exit::Break(label)
+                }
+                Some(Jump { is_break, label: Some(label), .. }) => {
+                    if is_break {
+                        Exit::OuterBreak(label)
+                    } else {
+                        Exit::OuterContinue(label)
+                    }
+                }
+                _ => Exit::Early,
+            };
+            if frame.finished && matches!(state.jump, Some(Jump { target: Some(t), .. }) if t == index) {
+                state.jump = None;
+            }
+            (frame, exit)
+        };
+        let event = Event::Exit { label: frame.label, iterations: frame.iterations, exit };
+        self.push(self.depth(), event, Vec::new());
+    }
+
+    // 以下方法用来查看结果
+
+    pub fn steps(&self) -> Vec<Step> {
+        self.state.borrow().steps.clone()
+    }
+
+    /// 某条语句执行的次数，语句的写法与 `stringify!` 的输出相同，如 `count = 4`
+    pub fn executed(&self, statement: &str) -> usize {
+        let state = self.state.borrow();
+        state.steps.iter().filter(|s| s.event == Event::Statement(statement_ref(statement, &state.declared))).count()
+    }
+
+    /// 循环体中一次也没有执行过的语句，按源码中的顺序
+    pub fn never_executed(&self) -> Vec<&'static str> {
+        let state = self.state.borrow();
+        state
+            .declared
+            .iter()
+            .copied()
+            .filter(|d| !state.steps.iter().any(|s| s.event == Event::Statement(d)))
+            .collect()
+    }
+
+    /// 带标签 label 的循环每次进入后迭代的次数，如 `iterations("'outer")`
+    pub fn iterations(&self, label: &str) -> Vec<usize> {
+        let state = self.state.borrow();
+        state
+            .steps
+            .iter()
+            .filter_map(|s| match s.event {
+                Event::Exit { label: Some(l), iterations, .. } if l == label => Some(iterations),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        *self.state.borrow_mut() = State::default();
+    }
+}
+
+// Event::Statement 中保存的是 &'static str，查找时换成已声明的同一个字符串
+fn statement_ref(statement: &str, declared: &[&'static str]) -> &'static str {
+    declared.iter().copied().find(|d| *d == statement).unwrap_or("")
+}
+
+/// `LoopTrace::enter` 返回，离开循环时（包括 break 到外层、return 和 panic）记录结束
+pub struct LoopGuard<'a> {
+    trace: &'a LoopTrace,
+}
+
+impl Drop for LoopGuard<'_> {
+    fn drop(&mut self) {
+        self.trace.exit();
+    }
+}
+
+fn loop_name(label: Option<&'static str>) -> String {
+    label.map(str::to_string).unwrap_or_else(|| "loop".to_string())
+}
+
+fn describe(event: &Event) -> String {
+    match event {
+        Event::Enter { label: Some(label), kind } => format!("enter {}: {}", label, kind.keyword()),
+        Event::Enter { label: None, kind } => format!("enter {}", kind.keyword()),
+        Event::Iteration { label, number } => format!("{} iteration {}", loop_name(*label), number),
+        Event::Statement(text) => text.to_string(),
+        Event::Break { label: Some(label) } => format!("break {}", label),
+        Event::Break { label: None } => "break".to_string(),
+        Event::Continue { label: Some(label) } => format!("continue {}", label),
+        Event::Continue { label: None } => "continue".to_string(),
+        Event::Exit { label, iterations, exit } => {
+            let plural = if *iterations == 1 { "" } else { "s" };
+            let reason = match exit {
+                Exit::Finished => "finished".to_string(),
+                Exit::Break(Some(label)) => format!("break {}", label),
+                Exit::Break(None) => "break".to_string(),
+                Exit::OuterBreak(label) => format!("left by break {}", label),
+                Exit::OuterContinue(label) => format!("left by continue {}", label),
+                Exit::Early => "left early by return, ? or panic".to_string(),
+            };
+            format!("exit {} after {} iteration{} ({})", loop_name(*label), iterations, plural, reason)
+        }
+    }
+}
+
+impl Display for LoopTrace {
+    /// 每步一行，按嵌套层数缩进，最后列出没有执行过的语句
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let state = self.state.borrow();
+        let mut table = Table::new(["step", "event", "bindings"]).align(0, Align::Right);
+        for (i, step) in state.steps.iter().enumerate() {
+            let bindings: Vec<String> = step.bindings.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+            table.row([(i + 1).to_string(), format!("{}{}", "  ".repeat(step.depth), describe(&step.event)), bindings.join(", ")]);
+        }
+        write!(f, "{}", table)?;
+        drop(state);
+
+        let never = self.never_executed();
+        if !never.is_empty() {
+            writeln!(f, "\nnever executed:")?;
+            for statement in never {
+                writeln!(f, "    {}", statement)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 跟踪一个 loop、while 或 for 的执行过程
+///
+/// 第一个参数是 `LoopTrace` 变量，第二个参数是要记录的变量列表，它们要在循环之前声明，
+/// 最后是循环本身，可以带标签。整个宏是一个表达式，值就是循环的值。
+#[macro_export]
+macro_rules! trace_loop {
+    ($t:ident, [$($w:ident),* $(,)?], $($body:tt)+) => {{
+        $t.declare(&$crate::trace_loop!(@collect [] $($body)+));
+        $crate::trace_loop!(@header $t [$($w),*] expr [] $($body)+)
+    }};
+
+    // ---- 辅助：标签与变量 ----
+    (@label) => { ::core::option::Option::None };
+    (@label $l:lifetime) => { ::core::option::Option::Some(stringify!($l)) };
+    (@bindings [$($w:ident),*]) => {
+        vec![$((stringify!($w), format!("{:?}", $w))),*]
+    };
+
+    // ---- 循环头：找到循环体所在的 {}，expr 模式只展开循环，stmt 模式还要继续处理后面的语句 ----
+    (@header $t:ident $w:tt expr [$($head:tt)*] { $($b:tt)* }) => {
+        $crate::trace_loop!(@loop $t $w [$($head)*] { $($b)* })
+    };
+    (@header $t:ident $w:tt stmt [$($head:tt)*] { $($b:tt)* } $($rest:tt)*) => {
+        $crate::trace_loop!(@loop $t $w [$($head)*] { $($b)* });
+        $crate::trace_loop!{@block $t $w $($rest)*}
+    };
+    // for 的模式中可能有 {}，整体作为一个模式取出
+    (@header $t:ident $w:tt $mode:ident [$($head:tt)*] for $p:pat in $($rest:tt)*) => {
+        $crate::trace_loop!(@header $t $w $mode [$($head)* for $p in] $($rest)*)
+    };
+    (@header $t:ident $w:tt $mode:ident [$($head:tt)*] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!(@header $t $w $mode [$($head)* $x] $($rest)*)
+    };
+
+    // ---- 三种循环 ----
+    (@loop $t:ident $w:tt [$($l:lifetime :)? loop] { $($b:tt)* }) => {{
+        let _guard = $t.enter(
+            $crate::trace_loop!(@label $($l)?),
+            $crate::loop_trace::LoopKind::Loop,
+            $crate::trace_loop!(@bindings $w),
+        );
+        $($l:)? loop {
+            $t.iteration($crate::trace_loop!(@bindings $w));
+            $crate::trace_loop!{@block $t $w $($b)*}
+        }
+    }};
+    (@loop $t:ident $w:tt [$($l:lifetime :)? while let $p:pat = $($e:tt)*] { $($b:tt)* }) => {{
+        let _guard = $t.enter(
+            $crate::trace_loop!(@label $($l)?),
+            $crate::loop_trace::LoopKind::While,
+            $crate::trace_loop!(@bindings $w),
+        );
+        $($l:)? loop {
+            let $p = $($e)* else {
+                $t.finished();
+                break;
+            };
+            $t.iteration($crate::trace_loop!(@bindings $w));
+            $crate::trace_loop!{@block $t $w $($b)*}
+        }
+    }};
+    (@loop $t:ident $w:tt [$($l:lifetime :)? while $($c:tt)*] { $($b:tt)* }) => {{
+        let _guard = $t.enter(
+            $crate::trace_loop!(@label $($l)?),
+            $crate::loop_trace::LoopKind::While,
+            $crate::trace_loop!(@bindings $w),
+        );
+        $($l:)? loop {
+            if !($($c)*) {
+                $t.finished();
+                break;
+            }
+            $t.iteration($crate::trace_loop!(@bindings $w));
+            $crate::trace_loop!{@block $t $w $($b)*}
+        }
+    }};
+    (@loop $t:ident $w:tt [$($l:lifetime :)? for $p:pat in $($e:tt)*] { $($b:tt)* }) => {{
+        let _guard = $t.enter(
+            $crate::trace_loop!(@label $($l)?),
+            $crate::loop_trace::LoopKind::For,
+            $crate::trace_loop!(@bindings $w),
+        );
+        let mut iter = ::core::iter::IntoIterator::into_iter($($e)*);
+        $($l:)? loop {
+            let $p = match ::core::iter::Iterator::next(&mut iter) {
+                ::core::option::Option::Some(item) => item,
+                ::core::option::Option::None => {
+                    $t.finished();
+                    break;
+                }
+            };
+            $t.iteration($crate::trace_loop!(@bindings $w));
+            $crate::trace_loop!{@block $t $w $($b)*}
+        }
+    }};
+
+    // ---- 循环体中的语句 ----
+    (@block $t:ident $w:tt) => {};
+    (@block $t:ident $w:tt ; $($rest:tt)*) => {
+        $crate::trace_loop!{@block $t $w $($rest)*}
+    };
+    (@block $t:ident $w:tt break $(;)?) => {
+        $t.record_break(None, $crate::trace_loop!(@bindings $w));
+        break;
+    };
+    (@block $t:ident $w:tt break ; $($rest:tt)+) => {
+        $t.record_break(None, $crate::trace_loop!(@bindings $w));
+        break;
+        $crate::trace_loop!{@block $t $w $($rest)+}
+    };
+    (@block $t:ident $w:tt break $l:lifetime $(;)?) => {
+        $t.record_break(Some(stringify!($l)), $crate::trace_loop!(@bindings $w));
+        break $l;
+    };
+    (@block $t:ident $w:tt break $l:lifetime ; $($rest:tt)+) => {
+        $t.record_break(Some(stringify!($l)), $crate::trace_loop!(@bindings $w));
+        break $l;
+        $crate::trace_loop!{@block $t $w $($rest)+}
+    };
+    (@block $t:ident $w:tt break $l:lifetime $e:expr $(; $($rest:tt)*)?) => {
+        $t.record_break(Some(stringify!($l)), $crate::trace_loop!(@bindings $w));
+        break $l $e;
+        $($crate::trace_loop!{@block $t $w $($rest)*})?
+    };
+    (@block $t:ident $w:tt break $e:expr $(; $($rest:tt)*)?) => {
+        $t.record_break(None, $crate::trace_loop!(@bindings $w));
+        break $e;
+        $($crate::trace_loop!{@block $t $w $($rest)*})?
+    };
+    (@block $t:ident $w:tt continue $($l:lifetime)? $(; $($rest:tt)*)?) => {
+        $t.record_continue($crate::trace_loop!(@label $($l)?), $crate::trace_loop!(@bindings $w));
+        continue $($l)?;
+        $($crate::trace_loop!{@block $t $w $($rest)*})?
+    };
+    (@block $t:ident $w:tt $l:lifetime : $($rest:tt)*) => {
+        $crate::trace_loop!(@header $t $w stmt [$l :] $($rest)*)
+    };
+    (@block $t:ident $w:tt loop $($rest:tt)*) => {
+        $crate::trace_loop!(@header $t $w stmt [] loop $($rest)*)
+    };
+    (@block $t:ident $w:tt while $($rest:tt)*) => {
+        $crate::trace_loop!(@header $t $w stmt [] while $($rest)*)
+    };
+    (@block $t:ident $w:tt for $($rest:tt)*) => {
+        $crate::trace_loop!(@header $t $w stmt [] for $($rest)*)
+    };
+    (@block $t:ident $w:tt if $($rest:tt)*) => {
+        $crate::trace_loop!{@if $t $w [] [] $($rest)*}
+    };
+    (@block $t:ident $w:tt return $($rest:tt)*) => {
+        $crate::trace_loop!{@return $t $w [return] $($rest)*}
+    };
+    (@block $t:ident $w:tt match $($rest:tt)*) => {
+        $crate::trace_loop!{@match $t $w [match] $($rest)*}
+    };
+    (@block $t:ident $w:tt $($rest:tt)*) => {
+        $crate::trace_loop!{@stmt $t $w [] $($rest)*}
+    };
+
+    // ---- if / else if / else：[已经处理的部分] [当前的条件] ----
+    (@if $t:ident $w:tt [$($out:tt)*] [$($c:tt)*] { $($b:tt)* } else if $($rest:tt)*) => {
+        $crate::trace_loop!{@if $t $w [$($out)* if $($c)* { $crate::trace_loop!{@block $t $w $($b)*} } else] [] $($rest)*}
+    };
+    (@if $t:ident $w:tt [$($out:tt)*] [$($c:tt)*] { $($b:tt)* } else { $($e:tt)* } $($rest:tt)*) => {
+        $($out)* if $($c)* { $crate::trace_loop!{@block $t $w $($b)*} } else { $crate::trace_loop!{@block $t $w $($e)*} };
+        $crate::trace_loop!{@block $t $w $($rest)*}
+    };
+    (@if $t:ident $w:tt [$($out:tt)*] [$($c:tt)*] { $($b:tt)* } $($rest:tt)*) => {
+        $($out)* if $($c)* { $crate::trace_loop!{@block $t $w $($b)*} };
+        $crate::trace_loop!{@block $t $w $($rest)*}
+    };
+    (@if $t:ident $w:tt [$($out:tt)*] [$($c:tt)*] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!{@if $t $w [$($out)*] [$($c)* $x] $($rest)*}
+    };
+
+    // ---- match 整体作为一条语句 ----
+    (@match $t:ident $w:tt [$($s:tt)*] { $($arms:tt)* } $($rest:tt)*) => {
+        $($s)* { $($arms)* };
+        $t.statement(stringify!($($s)* { $($arms)* }), $crate::trace_loop!(@bindings $w));
+        $crate::trace_loop!{@block $t $w $($rest)*}
+    };
+    (@match $t:ident $w:tt [$($s:tt)*] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!{@match $t $w [$($s)* $x] $($rest)*}
+    };
+
+    // ---- return 之后不会再回来，所以先记录再执行 ----
+    (@return $t:ident $w:tt [$($s:tt)+] $(; $($rest:tt)*)?) => {
+        $t.statement(stringify!($($s)+), $crate::trace_loop!(@bindings $w));
+        $($s)+;
+        $($crate::trace_loop!{@block $t $w $($rest)*})?
+    };
+    (@return $t:ident $w:tt [$($s:tt)+] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!{@return $t $w [$($s)+ $x] $($rest)*}
+    };
+
+    // ---- 普通语句：一直取到分号，执行后再记录 ----
+    (@stmt $t:ident $w:tt [$($s:tt)+] $(;)?) => {
+        $($s)+;
+        $t.statement(stringify!($($s)+), $crate::trace_loop!(@bindings $w));
+    };
+    (@stmt $t:ident $w:tt [$($s:tt)+] ; $($rest:tt)+) => {
+        $($s)+;
+        $t.statement(stringify!($($s)+), $crate::trace_loop!(@bindings $w));
+        $crate::trace_loop!{@block $t $w $($rest)+}
+    };
+    (@stmt $t:ident $w:tt [$($s:tt)*] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!{@stmt $t $w [$($s)* $x] $($rest)*}
+    };
+
+    // ---- 收集所有语句的文本，与 @stmt、@match 记录的一致 ----
+    (@collect [$($a:expr),*]) => { [$($a),*] };
+    (@collect [$($a:expr),*] ; $($rest:tt)*) => { $crate::trace_loop!(@collect [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] break $($rest:tt)*) => { $crate::trace_loop!(@collect_skip [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] continue $($rest:tt)*) => { $crate::trace_loop!(@collect_skip [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] $l:lifetime : $($rest:tt)*) => { $crate::trace_loop!(@collect [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] for $p:pat in $($rest:tt)*) => { $crate::trace_loop!(@collect_head [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] loop $($rest:tt)*) => { $crate::trace_loop!(@collect_head [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] while $($rest:tt)*) => { $crate::trace_loop!(@collect_head [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] if $($rest:tt)*) => { $crate::trace_loop!(@collect_head [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] else $($rest:tt)*) => { $crate::trace_loop!(@collect_head [$($a),*] $($rest)*) };
+    (@collect [$($a:expr),*] match $($rest:tt)*) => { $crate::trace_loop!(@collect_match [$($a),*] [match] $($rest)*) };
+    (@collect [$($a:expr),*] $($rest:tt)+) => { $crate::trace_loop!(@collect_stmt [$($a),*] [] $($rest)+) };
+
+    (@collect_skip [$($a:expr),*]) => { $crate::trace_loop!(@collect [$($a),*]) };
+    (@collect_skip [$($a:expr),*] ; $($rest:tt)*) => { $crate::trace_loop!(@collect [$($a),*] $($rest)*) };
+    (@collect_skip [$($a:expr),*] $x:tt $($rest:tt)*) => { $crate::trace_loop!(@collect_skip [$($a),*] $($rest)*) };
+
+    // 循环体展开后接在后面的语句之前，用分号隔开
+    (@collect_head [$($a:expr),*] { $($b:tt)* } $($rest:tt)*) => {
+        $crate::trace_loop!(@collect [$($a),*] $($b)* ; $($rest)*)
+    };
+    (@collect_head [$($a:expr),*] $x:tt $($rest:tt)*) => { $crate::trace_loop!(@collect_head [$($a),*] $($rest)*) };
+
+    (@collect_match [$($a:expr),*] [$($s:tt)*] { $($arms:tt)* } $($rest:tt)*) => {
+        $crate::trace_loop!(@collect [$($a,)* stringify!($($s)* { $($arms)* })] $($rest)*)
+    };
+    (@collect_match [$($a:expr),*] [$($s:tt)*] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!(@collect_match [$($a),*] [$($s)* $x] $($rest)*)
+    };
+
+    (@collect_stmt [$($a:expr),*] [$($s:tt)+]) => { $crate::trace_loop!(@collect [$($a,)* stringify!($($s)+)]) };
+    (@collect_stmt [$($a:expr),*] [$($s:tt)+] ; $($rest:tt)*) => {
+        $crate::trace_loop!(@collect [$($a,)* stringify!($($s)+)] $($rest)*)
+    };
+    (@collect_stmt [$($a:expr),*] [$($s:tt)*] $x:tt $($rest:tt)*) => {
+        $crate::trace_loop!(@collect_stmt [$($a),*] [$($s)* $x] $($rest)*)
+    };
+}
@@ -5,11 +5,254 @@
 多行注释
 */
 
+use std::env;
+use std::process;
+
+use learn_rust::bigint::BigInt;
+use learn_rust::bitwise::{self, Operand};
+use learn_rust::cast::{self, NumType, Value};
+use learn_rust::chars;
+use learn_rust::division;
+use learn_rust::endian::{self, ByteRepr};
+use learn_rust::flow_graph::{self, FlowGraph};
+use learn_rust::int_fit;
+use learn_rust::int_type::IntType;
+use learn_rust::layout;
+use learn_rust::metrics::{self, DEFAULT_BUCKETS};
+use learn_rust::rules::RuleSet;
+use learn_rust::scopes::Analysis;
+
 // 这是主函数
 fn main() {
     // 调用编译生成的可执行文件时，这里的语句将被运行。
 
-    // 将文本打印到控制台, println! 是一个宏（macros），可以将文本输出到控制台（console）
-    println!("Hello World!");
-    println!("I'm a Rustacean!");
+    // 命令行参数的第一个是程序本身，跳过
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        // 将文本打印到控制台, println! 是一个宏（macros），可以将文本输出到控制台（console）
+        println!("Hello World!");
+        println!("I'm a Rustacean!");
+        return;
+    }
+
+    // `--metrics <prometheus | json>` 放在子命令之前，运行结束后把指标打印到标准错误
+    let (format, args) = match args.split_first() {
+        Some((flag, rest)) if flag == "--metrics" => match rest.split_first() {
+            Some((format, rest)) if (format == "prometheus" || format == "json") && !rest.is_empty() => {
+                (Some(format.as_str()), rest)
+            }
+            _ => {
+                eprintln!("error: usage: learn_rust --metrics <prometheus | json> <command> [args]");
+                process::exit(1);
+            }
+        },
+        _ => (None, &args[..]),
+    };
+
+    let result = record(&args[0], || run(&args[0], &args[1..]));
+    match format {
+        Some("json") => eprintln!("{}", metrics::global().snapshot().to_json()),
+        Some(_) => eprint!("{}", metrics::global().snapshot().to_prometheus()),
+        None => {}
+    }
+    if let Err(message) = result {
+        eprintln!("error: {}", message);
+        process::exit(1);
+    }
 }
+
+// 记录命令的次数、失败的次数和耗时
+fn record(command: &str, run: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    let registry = metrics::global();
+    let name = command.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let runs = registry.counter(&format!("learn_rust_{}_runs_total", name), "times the command was run");
+    let errors = registry.counter(&format!("learn_rust_{}_errors_total", name), "times the command failed");
+    let seconds = registry.histogram(&format!("learn_rust_{}_seconds", name), "time spent in the command", &DEFAULT_BUCKETS);
+    let (runs, errors, seconds) = (runs.unwrap(), errors.unwrap(), seconds.unwrap());
+
+    runs.increment();
+    let result = seconds.time(run);
+    if result.is_err() {
+        errors.increment();
+    }
+    result
+}
+
+// 按子命令分发，出错时返回要打印给用户的信息
+fn run(command: &str, args: &[String]) -> Result<(), String> {
+    match command {
+        "char" => {
+            let input = args.first().ok_or("usage: learn_rust char <char | U+XXXX>")?;
+            let info = chars::inspect_input(input).map_err(|e| e.to_string())?;
+            println!("{}", info);
+        }
+        "bitwise" => {
+            let usage = "usage: learn_rust bitwise <a> <b> [type, default u32]";
+            if args.len() < 2 {
+                return Err(usage.to_string());
+            }
+            let ty = match args.get(2) {
+                Some(name) => name.parse::<IntType>().map_err(|e| e.to_string())?,
+                None => IntType::U32,
+            };
+            let a = Operand::parse(ty, &args[0]).map_err(|e| e.to_string())?;
+            let b = Operand::parse(ty, &args[1]).map_err(|e| e.to_string())?;
+            print!("{}", bitwise::render(a, b));
+        }
+        "divide" => {
+            let usage = "usage: learn_rust divide <a> <b> [type, default i32]";
+            if args.len() < 2 {
+                return Err(usage.to_string());
+            }
+            let ty = match args.get(2) {
+                Some(name) => name.parse::<IntType>().map_err(|e| e.to_string())?,
+                None => IntType::I32,
+            };
+            let a = Operand::parse(ty, &args[0]).map_err(|e| e.to_string())?;
+            let b = Operand::parse(ty, &args[1]).map_err(|e| e.to_string())?;
+            print!("{}", division::render(a, b));
+        }
+        "cast" => {
+            if args.is_empty() {
+                print!("{}", cast::render_matrix());
+                return Ok(());
+            }
+            let from = args[0].parse::<NumType>().map_err(|e| e.to_string())?;
+            let to = match args.get(1) {
+                Some(name) => Some(name.parse::<NumType>().map_err(|e| e.to_string())?),
+                None => None,
+            };
+            let values = match args.get(2) {
+                Some(input) => vec![Value::parse(from, input).map_err(|e| e.to_string())?],
+                None => Value::samples(from),
+            };
+            print!("{}", cast::render_casts(&values, to));
+        }
+        "layout" => match args.first() {
+            None => print!("{}", layout::render_summary()),
+            Some(name) => {
+                let found = layout::lookup(name).ok_or_else(|| {
+                    let names: Vec<&str> = layout::all().iter().map(|l| l.name).collect();
+                    format!("unknown type `{}`, expected one of: {}", name, names.join(", "))
+                })?;
+                print!("{}", layout::render(&found));
+            }
+        },
+        "fit" => {
+            let usage = "usage: learn_rust fit <value>... | learn_rust fit --csv <file> <column>";
+            let advice = match args.first().map(String::as_str) {
+                None => return Err(usage.to_string()),
+                Some("--csv") => {
+                    let (path, column) = match args {
+                        [_, path, column] => (path, column),
+                        _ => return Err(usage.to_string()),
+                    };
+                    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+                    int_fit::advise_csv(&text, column)
+                }
+                Some(_) => {
+                    let values: Result<Vec<BigInt>, _> = args.iter().map(|arg| arg.parse::<BigInt>()).collect();
+                    int_fit::advise(values.map_err(|e| e.to_string())?)
+                }
+            };
+            print!("{}", int_fit::render(&advice.map_err(|e| e.to_string())?));
+        }
+        "bytes" => {
+            let usage = "usage: learn_rust bytes <type> <value>";
+            let (ty, input) = match args {
+                [ty, input, ..] => (ty.as_str(), input.as_str()),
+                _ => return Err(usage.to_string()),
+            };
+            let label = format!("{} {}", ty, input);
+            let out = match ty {
+                "bool" => {
+                    let value = input.parse::<bool>().map_err(|e| e.to_string())?;
+                    endian::render(&label, |e| value.to_bytes(e).to_vec())
+                }
+                "char" => {
+                    let value = chars::parse_char(input).map_err(|e| e.to_string())?;
+                    endian::render(&label, |e| value.to_bytes(e).to_vec())
+                }
+                _ => {
+                    let ty = ty.parse::<NumType>().map_err(|e| e.to_string())?;
+                    let value = Value::parse(ty, input).map_err(|e| e.to_string())?;
+                    endian::render(&label, |e| endian::value_to_bytes(value, e))
+                }
+            };
+            print!("{}", out);
+        }
+        "flow" => {
+            let usage = "usage: learn_rust flow <file> <fn> [--dot]";
+            let (path, name) = match args {
+                [path, name, ..] => (path, name),
+                _ => return Err(usage.to_string()),
+            };
+            let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            let graph = FlowGraph::from_function(&src, name).map_err(|e| match e {
+                flow_graph::FlowError::FunctionNotFound(_) => {
+                    let names = flow_graph::function_names(&src).unwrap_or_default();
+                    format!("{}, expected one of: {}", e, names.join(", "))
+                }
+                e => e.to_string(),
+            })?;
+            match args.get(2).map(String::as_str) {
+                Some("--dot") => print!("{}", graph.to_dot()),
+                Some(other) => return Err(format!("unknown option `{}`\n{}", other, usage)),
+                None => print!("{}", graph.render_ascii()),
+            }
+        }
+        "rules" => {
+            let usage = "usage: learn_rust rules <spec file> <from> <to>";
+            let (path, from, to) = match args {
+                [path, from, to] => (path, from, to),
+                _ => return Err(usage.to_string()),
+            };
+            let spec = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            let rules = RuleSet::parse(&spec).map_err(|e| format!("{}: {}", path, e))?;
+            let from = from.parse::<i128>().map_err(|e| format!("invalid start `{}`: {}", from, e))?;
+            let to = to.parse::<i128>().map_err(|e| format!("invalid end `{}`: {}", to, e))?;
+            for (n, label) in rules.stream(from..=to) {
+                println!("{:>6}  {}", n, label);
+            }
+        }
+        "scopes" => {
+            let usage = "usage: learn_rust scopes <file> [fn] [--json]";
+            let json = args.iter().any(|arg| arg == "--json");
+            let (path, name) = match &args.iter().filter(|arg| *arg != "--json").collect::<Vec<_>>()[..] {
+                [path] => (*path, None),
+                [path, name] => (*path, Some(name.as_str())),
+                _ => return Err(usage.to_string()),
+            };
+            let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            let analysis = match name {
+                Some(name) => Analysis::from_function(&src, name),
+                None => Analysis::from_source(&src),
+            };
+            let analysis = analysis.map_err(|e| format!("{}: {}", path, e))?;
+            if json {
+                println!("{}", analysis.to_json());
+            } else {
+                print!("{}", analysis.render());
+            }
+        }
+        _ => return Err(format!("unknown command `{}`\n{}", command, USAGE)),
+    }
+    Ok(())
+}
+
+const USAGE: &str = "usage:
+    learn_rust                          print hello world
+    learn_rust char <char | U+XXXX>     inspect a unicode char
+    learn_rust bitwise <a> <b> [type]   show bitwise operations in binary, hex and decimal
+    learn_rust divide <a> <b> [type]    compare `/`, `%`, euclid, floor and ceil division
+    learn_rust cast [from] [to] [value] show what `as` does between numeric types
+    learn_rust layout [type]            show size, alignment, padding and niche of a type
+    learn_rust fit <value>...           find the smallest integer type that holds the values
+    learn_rust fit --csv <file> <col>   same, for a column of a CSV file
+    learn_rust bytes <type> <value>     show the big-, little- and native-endian bytes of a value
+    learn_rust flow <file> <fn> [--dot] draw the control-flow graph of a function
+    learn_rust rules <spec> <from> <to> label each number in a range by the rules in a TOML spec
+    learn_rust scopes <file> [fn]       list bindings, shadowing and unused variables, `--json` for JSON
+
+    learn_rust --metrics <prometheus | json> <command> [args]
+                                        also print the run count and timing of the command";
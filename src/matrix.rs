@@ -0,0 +1,245 @@
+/*
+定长矩阵
+
+数组的长度是类型的一部分，`[i32; 5]` 与 `[i32; 6]` 是不同的类型。
+常量泛型（const generics）让类型可以带上这样的长度参数：
+
+    struct Matrix<T, const R: usize, const C: usize> { rows: [[T; C]; R] }
+
+矩阵的行数和列数都在编译期确定，数据全部放在栈上，不需要 Vec。
+相乘时要求左边的列数等于右边的行数，这个检查也发生在编译期：
+
+    let a: Matrix<i32, 2, 3> = ...;
+    let b: Matrix<i32, 2, 3> = ...;
+    // a * b;          // 编译错误：Matrix<i32, 2, 3> 没有实现 Mul<Matrix<i32, 2, 3>>
+    a * b.transpose(); // Matrix<i32, 2, 2>
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// 可以参与矩阵运算的元素，需要有 0 和 1
+pub trait Scalar: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_scalar {
+    ($zero:literal, $one:literal: $($t:ty)*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+            }
+        )*
+    };
+}
+
+impl_scalar!(0, 1: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+impl_scalar!(0.0, 1.0: f32 f64);
+
+/// R 行 C 列的矩阵，按行保存在嵌套数组中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Matrix<T, const R: usize, const C: usize> {
+    rows: [[T; C]; R],
+}
+
+impl<T, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub const ROWS: usize = R;
+    pub const COLS: usize = C;
+
+    /// 按行创建：`Matrix::new([[1, 2, 3], [4, 5, 6]])` 是 2 行 3 列
+    pub const fn new(rows: [[T; C]; R]) -> Matrix<T, R, C> {
+        Matrix { rows }
+    }
+
+    /// 用下标 (行, 列) 计算每个元素
+    pub fn from_fn(mut f: impl FnMut(usize, usize) -> T) -> Matrix<T, R, C> {
+        Matrix { rows: std::array::from_fn(|i| std::array::from_fn(|j| f(i, j))) }
+    }
+
+    pub const fn rows(&self) -> usize {
+        R
+    }
+
+    pub const fn cols(&self) -> usize {
+        C
+    }
+
+    /// 第 i 行，是连续的内存，可以直接借用为数组
+    pub fn row(&self, i: usize) -> &[T; C] {
+        &self.rows[i]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [T; C] {
+        &mut self.rows[i]
+    }
+
+    /// 第 j 列的所有元素，列在内存中不连续，只能逐个借用
+    pub fn column_iter(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(j < C, "column index {} out of range for matrix with {} columns", j, C);
+        self.rows.iter().map(move |row| &row[j])
+    }
+
+    /// 下标越界时返回 None，不会 panic
+    pub fn get(&self, i: usize, j: usize) -> Option<&T> {
+        self.rows.get(i)?.get(j)
+    }
+
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut T> {
+        self.rows.get_mut(i)?.get_mut(j)
+    }
+
+    /// 按行遍历
+    pub fn iter_rows(&self) -> std::slice::Iter<'_, [T; C]> {
+        self.rows.iter()
+    }
+
+    pub fn as_array(&self) -> &[[T; C]; R] {
+        &self.rows
+    }
+
+    pub fn into_array(self) -> [[T; C]; R] {
+        self.rows
+    }
+
+    /// 对每个元素做变换，矩阵的形状不变
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Matrix<U, R, C> {
+        Matrix { rows: self.rows.map(|row| row.map(&mut f)) }
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// 第 j 列，复制为一个长度为 R 的数组
+    pub fn column(&self, j: usize) -> [T; R] {
+        assert!(j < C, "column index {} out of range for matrix with {} columns", j, C);
+        self.rows.map(|row| row[j])
+    }
+
+    /// 转置：R 行 C 列变成 C 行 R 列，结果的类型也随之改变
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        Matrix::from_fn(|i, j| self.rows[j][i])
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn zero() -> Matrix<T, R, C> {
+        Matrix { rows: [[T::ZERO; C]; R] }
+    }
+
+    /// 每个元素乘以同一个数
+    pub fn scale(self, factor: T) -> Matrix<T, R, C> {
+        self.map(|x| x * factor)
+    }
+}
+
+impl<T: Scalar, const N: usize> Matrix<T, N, N> {
+    /// 单位矩阵，只有方阵才有这个方法
+    pub fn identity() -> Matrix<T, N, N> {
+        Matrix::from_fn(|i, j| if i == j { T::ONE } else { T::ZERO })
+    }
+
+    /// 主对角线元素之和
+    pub fn trace(&self) -> T {
+        (0..N).fold(T::ZERO, |sum, i| sum + self.rows[i][i])
+    }
+}
+
+impl<T: Default + Copy, const R: usize, const C: usize> Default for Matrix<T, R, C> {
+    fn default() -> Matrix<T, R, C> {
+        Matrix { rows: [[T::default(); C]; R] }
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    fn from(rows: [[T; C]; R]) -> Matrix<T, R, C> {
+        Matrix { rows }
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    /// `m[(i, j)]`，与数组一样，越界时 panic
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self.rows[i][j]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self.rows[i][j]
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<usize> for Matrix<T, R, C> {
+    type Output = [T; C];
+
+    /// `m[i]` 取第 i 行，因此也可以写成 `m[i][j]`
+    fn index(&self, i: usize) -> &[T; C] {
+        &self.rows[i]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<usize> for Matrix<T, R, C> {
+    fn index_mut(&mut self, i: usize) -> &mut [T; C] {
+        &mut self.rows[i]
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Add for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    /// 只有形状相同的矩阵才能相加
+    fn add(self, other: Matrix<T, R, C>) -> Matrix<T, R, C> {
+        Matrix::from_fn(|i, j| self.rows[i][j] + other.rows[i][j])
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> Sub for Matrix<T, R, C> {
+    type Output = Matrix<T, R, C>;
+
+    fn sub(self, other: Matrix<T, R, C>) -> Matrix<T, R, C> {
+        Matrix::from_fn(|i, j| self.rows[i][j] - other.rows[i][j])
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize, const K: usize> Mul<Matrix<T, C, K>> for Matrix<T, R, C> {
+    type Output = Matrix<T, R, K>;
+
+    /// (R × C) * (C × K) = (R × K)，中间的 C 必须相同，否则找不到这个 impl，无法编译
+    fn mul(self, other: Matrix<T, C, K>) -> Matrix<T, R, K> {
+        Matrix::from_fn(|i, j| (0..C).fold(T::ZERO, |sum, k| sum + self.rows[i][k] * other.rows[k][j]))
+    }
+}
+
+impl<T: Display, const R: usize, const C: usize> Display for Matrix<T, R, C> {
+    // 每行一对中括号，每列右对齐，精度会作用到每个元素上：
+    // [ 1  -2]
+    // [30   4]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| match f.precision() {
+                        Some(precision) => format!("{:.*}", precision, x),
+                        None => x.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> =
+            (0..C).map(|j| cells.iter().map(|row| row[j].chars().count()).max().unwrap_or(0)).collect();
+
+        for (i, row) in cells.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let line: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:>1$}", cell, width)).collect();
+            write!(f, "[{}]", line.join("  "))?;
+        }
+        Ok(())
+    }
+}
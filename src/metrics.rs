@@ -0,0 +1,440 @@
+/*
+全局的指标（metrics）注册表
+
+进程中的各个部分按名字登记指标，之后随时更新，最后统一导出：
+- counter：只增不减的计数，比如执行了多少次命令
+- gauge：可增可减的当前值，比如正在运行的任务数
+- histogram：把观测值按固定的区间（bucket）计数，同时记录总和与个数，比如每次命令的耗时
+
+按名字查找指标需要加锁，拿到的句柄（Arc）之后的更新都是原子操作，不需要锁，多个线程同时更新也不会相互等待。
+注册表本身用 `const fn` 创建，可以直接放在 static 中，`global()` 就是整个进程共用的那一个。
+
+导出时先取快照（snapshot），再转换为 Prometheus 的文本格式或 JSON。
+快照中每个指标各自是一致的；取快照的同时有其他线程在更新时，不同指标之间不保证是同一时刻的值。
+ */
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::globals::Counter;
+
+/// 以秒为单位的耗时默认使用的区间上限
+pub const DEFAULT_BUCKETS: [f64; 11] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricsError {
+    /// 名字只能由字母、数字、`_`、`:` 组成，并且不能以数字开头
+    InvalidName(String),
+    /// 同一个名字已经登记为另一种指标
+    KindMismatch { name: String, existing: Kind, requested: Kind },
+    /// 区间上限必须是有限的数，并且严格递增
+    InvalidBuckets(Vec<f64>),
+    /// 同一个名字的 histogram 已经用另一组区间登记过
+    BucketsMismatch { name: String, existing: Vec<f64> },
+}
+
+impl Display for MetricsError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MetricsError::InvalidName(name) => write!(f, "invalid metric name `{}`", name),
+            MetricsError::KindMismatch { name, existing, requested } => {
+                write!(f, "metric `{}` is a {}, not a {}", name, existing, requested)
+            }
+            MetricsError::InvalidBuckets(buckets) => {
+                write!(f, "histogram buckets {:?} must be finite and strictly increasing", buckets)
+            }
+            MetricsError::BucketsMismatch { name, existing } => {
+                write!(f, "histogram `{}` is already registered with buckets {:?}", name, existing)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MetricsError {}
+
+/// 指标的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+            Kind::Histogram => "histogram",
+        })
+    }
+}
+
+/// 可增可减的浮点数，按位存放在 AtomicU64 中
+#[derive(Debug, Default)]
+pub struct Gauge {
+    bits: AtomicU64,
+}
+
+impl Gauge {
+    pub const fn new() -> Gauge {
+        // 0.0 的位模式就是 0
+        Gauge { bits: AtomicU64::new(0) }
+    }
+
+    pub fn set(&self, value: f64) {
+        self.bits.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 加上 delta（可以为负数），返回加之后的值
+    pub fn add(&self, delta: f64) -> f64 {
+        let update = |bits| Some((f64::from_bits(bits) + delta).to_bits());
+        // 闭包总是返回 Some，fetch_update 不会失败
+        let old = self.bits.fetch_update(Ordering::Relaxed, Ordering::Relaxed, update).unwrap_or_else(|bits| bits);
+        f64::from_bits(old) + delta
+    }
+
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+}
+
+/// 固定区间的直方图
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    // 每个区间各自的个数（不累加），最后一个是超过所有上限的部分
+    counts: Vec<AtomicU64>,
+    sum: Gauge,
+}
+
+impl Histogram {
+    /// bounds 是各个区间的上限（包含），必须是有限的数并且严格递增
+    pub fn new(bounds: &[f64]) -> Result<Histogram, MetricsError> {
+        let valid = bounds.iter().all(|b| b.is_finite()) && bounds.windows(2).all(|w| w[0] < w[1]);
+        if !valid {
+            return Err(MetricsError::InvalidBuckets(bounds.to_vec()));
+        }
+        let counts = (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect();
+        Ok(Histogram { bounds: bounds.to_vec(), counts, sum: Gauge::new() })
+    }
+
+    pub fn bounds(&self) -> &[f64] {
+        &self.bounds
+    }
+
+    /// 记录一个观测值；NaN 只计入总个数，不计入总和
+    pub fn observe(&self, value: f64) {
+        if value.is_nan() {
+            self.counts[self.bounds.len()].fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let index = self.bounds.partition_point(|&b| b < value);
+        self.counts[index].fetch_add(1, Ordering::Relaxed);
+        self.sum.add(value);
+    }
+
+    /// 以秒为单位记录一段时间
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    /// 执行 f，并记录它花费的时间
+    pub fn time<R>(&self, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = f();
+        self.observe_duration(start.elapsed());
+        result
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().map(|c| c.load(Ordering::Relaxed)).sum()
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum.get()
+    }
+
+    fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+        self.sum.set(0.0);
+    }
+
+    fn snapshot(&self) -> Value {
+        let mut cumulative = 0;
+        let buckets = self
+            .bounds
+            .iter()
+            .zip(&self.counts)
+            .map(|(&bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (bound, cumulative)
+            })
+            .collect();
+        let count = cumulative + self.counts[self.bounds.len()].load(Ordering::Relaxed);
+        Value::Histogram { buckets, sum: self.sum(), count }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Metric {
+    Counter(Arc<Counter>),
+    Gauge(Arc<Gauge>),
+    Histogram(Arc<Histogram>),
+}
+
+impl Metric {
+    fn kind(&self) -> Kind {
+        match self {
+            Metric::Counter(_) => Kind::Counter,
+            Metric::Gauge(_) => Kind::Gauge,
+            Metric::Histogram(_) => Kind::Histogram,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    help: String,
+    metric: Metric,
+}
+
+/// 指标的注册表
+#[derive(Debug, Default)]
+pub struct Registry {
+    entries: Mutex<BTreeMap<String, Entry>>,
+}
+
+static GLOBAL: Registry = Registry::new();
+
+/// 整个进程共用的注册表
+pub fn global() -> &'static Registry {
+    &GLOBAL
+}
+
+impl Registry {
+    pub const fn new() -> Registry {
+        Registry { entries: Mutex::new(BTreeMap::new()) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 按名字取出已有的指标，没有时用 create 创建并登记；help 以第一次登记时的为准
+    fn get_or_register(
+        &self,
+        name: &str,
+        help: &str,
+        requested: Kind,
+        create: impl FnOnce() -> Metric,
+    ) -> Result<Metric, MetricsError> {
+        if !is_valid_name(name) {
+            return Err(MetricsError::InvalidName(name.to_string()));
+        }
+        let mut entries = self.lock();
+        let entry = entries
+            .entry(name.to_string())
+            .or_insert_with(|| Entry { help: help.to_string(), metric: create() });
+        if entry.metric.kind() != requested {
+            let existing = entry.metric.kind();
+            return Err(MetricsError::KindMismatch { name: name.to_string(), existing, requested });
+        }
+        Ok(entry.metric.clone())
+    }
+
+    /// 取出或登记一个 counter
+    pub fn counter(&self, name: &str, help: &str) -> Result<Arc<Counter>, MetricsError> {
+        match self.get_or_register(name, help, Kind::Counter, || Metric::Counter(Arc::default()))? {
+            Metric::Counter(counter) => Ok(counter),
+            _ => unreachable!(),
+        }
+    }
+
+    /// 取出或登记一个 gauge
+    pub fn gauge(&self, name: &str, help: &str) -> Result<Arc<Gauge>, MetricsError> {
+        match self.get_or_register(name, help, Kind::Gauge, || Metric::Gauge(Arc::default()))? {
+            Metric::Gauge(gauge) => Ok(gauge),
+            _ => unreachable!(),
+        }
+    }
+
+    /// 取出或登记一个 histogram，已经登记过时 bounds 必须相同
+    pub fn histogram(&self, name: &str, help: &str, bounds: &[f64]) -> Result<Arc<Histogram>, MetricsError> {
+        let histogram = Arc::new(Histogram::new(bounds)?);
+        match self.get_or_register(name, help, Kind::Histogram, || Metric::Histogram(histogram))? {
+            Metric::Histogram(existing) if existing.bounds() != bounds => {
+                Err(MetricsError::BucketsMismatch { name: name.to_string(), existing: existing.bounds().to_vec() })
+            }
+            Metric::Histogram(histogram) => Ok(histogram),
+            _ => unreachable!(),
+        }
+    }
+
+    /// 所有指标当前的值，按名字排序
+    pub fn snapshot(&self) -> Snapshot {
+        let entries = self.lock();
+        let metrics = entries
+            .iter()
+            .map(|(name, entry)| {
+                let value = match &entry.metric {
+                    Metric::Counter(counter) => Value::Counter(counter.get()),
+                    Metric::Gauge(gauge) => Value::Gauge(gauge.get()),
+                    Metric::Histogram(histogram) => histogram.snapshot(),
+                };
+                Sample { name: name.clone(), help: entry.help.clone(), value }
+            })
+            .collect();
+        Snapshot { metrics }
+    }
+
+    /// 把所有指标清零，已经登记的指标和取出的句柄仍然有效
+    pub fn reset(&self) {
+        for entry in self.lock().values() {
+            match &entry.metric {
+                Metric::Counter(counter) => {
+                    counter.reset();
+                }
+                Metric::Gauge(gauge) => gauge.set(0.0),
+                Metric::Histogram(histogram) => histogram.reset(),
+            }
+        }
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == ':';
+    matches!(chars.next(), Some(c) if valid(c) && !c.is_ascii_digit()) && chars.all(valid)
+}
+
+/// 快照中一个指标的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Counter(u64),
+    Gauge(f64),
+    /// buckets 是每个上限和不超过它的个数（累加），count 包括超过所有上限的部分
+    Histogram { buckets: Vec<(f64, u64)>, sum: f64, count: u64 },
+}
+
+impl Value {
+    pub fn kind(&self) -> Kind {
+        match self {
+            Value::Counter(_) => Kind::Counter,
+            Value::Gauge(_) => Kind::Gauge,
+            Value::Histogram { .. } => Kind::Histogram,
+        }
+    }
+}
+
+/// 快照中的一个指标
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub help: String,
+    pub value: Value,
+}
+
+/// 某一时刻所有指标的值
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snapshot {
+    pub metrics: Vec<Sample>,
+}
+
+impl Snapshot {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.metrics.iter().find(|m| m.name == name).map(|m| &m.value)
+    }
+
+    /// Prometheus 的文本格式，见 https://prometheus.io/docs/instrumenting/exposition_formats/
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        for Sample { name, help, value } in &self.metrics {
+            if !help.is_empty() {
+                let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+                writeln!(out, "# HELP {} {}", name, help).unwrap();
+            }
+            writeln!(out, "# TYPE {} {}", name, value.kind()).unwrap();
+            match value {
+                Value::Counter(n) => writeln!(out, "{} {}", name, n).unwrap(),
+                Value::Gauge(x) => writeln!(out, "{} {}", name, prometheus_float(*x)).unwrap(),
+                Value::Histogram { buckets, sum, count } => {
+                    for (bound, n) in buckets {
+                        writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, prometheus_float(*bound), n).unwrap();
+                    }
+                    writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count).unwrap();
+                    writeln!(out, "{}_sum {}", name, prometheus_float(*sum)).unwrap();
+                    writeln!(out, "{}_count {}", name, count).unwrap();
+                }
+            }
+        }
+        out
+    }
+
+    /// JSON 对象，以指标的名字为键；NaN 和无穷大没有对应的 JSON 数字，输出为 null
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        for (i, Sample { name, help, value }) in self.metrics.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(out, "\n  {}: {{\"type\": \"{}\", \"help\": {}, ", json_string(name), value.kind(), json_string(help))
+                .unwrap();
+            match value {
+                Value::Counter(n) => write!(out, "\"value\": {}", n).unwrap(),
+                Value::Gauge(x) => write!(out, "\"value\": {}", json_float(*x)).unwrap(),
+                Value::Histogram { buckets, sum, count } => {
+                    let buckets: Vec<String> =
+                        buckets.iter().map(|(bound, n)| format!("[{}, {}]", json_float(*bound), n)).collect();
+                    write!(out, "\"buckets\": [{}], \"sum\": {}, \"count\": {}", buckets.join(", "), json_float(*sum), count)
+                        .unwrap();
+                }
+            }
+            out.push('}');
+        }
+        if !self.metrics.is_empty() {
+            out.push('\n');
+        }
+        out.push('}');
+        out
+    }
+}
+
+fn prometheus_float(x: f64) -> String {
+    match x {
+        x if x.is_nan() => "NaN".to_string(),
+        f64::INFINITY => "+Inf".to_string(),
+        f64::NEG_INFINITY => "-Inf".to_string(),
+        x => x.to_string(),
+    }
+}
+
+fn json_float(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
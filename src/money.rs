@@ -0,0 +1,243 @@
+/*
+金额
+
+    let money = 2.0;    // 不要这样保存金额
+
+金额用定点小数保存，小数位数由币种决定（人民币精确到分，日元没有小数），
+加减只能在同一币种之间进行，乘以整数是精确的，只有需要时才显式地舍入。
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::decimal::{Decimal, DecimalError, Rounding};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Currency {
+    Cny,
+    Usd,
+    Eur,
+    Gbp,
+    Jpy,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 5] = [Currency::Cny, Currency::Usd, Currency::Eur, Currency::Gbp, Currency::Jpy];
+
+    /// ISO 4217 代码
+    pub fn code(self) -> &'static str {
+        match self {
+            Currency::Cny => "CNY",
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Jpy => "JPY",
+        }
+    }
+
+    /// 货币符号；人民币和日元都用 ¥，为了能区分，日元写作 JP¥
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Currency::Cny => "¥",
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+            Currency::Jpy => "JP¥",
+        }
+    }
+
+    /// 最小单位的小数位数
+    pub fn scale(self) -> u32 {
+        match self {
+            Currency::Jpy => 0,
+            _ => 2,
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Currency> {
+        Currency::ALL.iter().copied().find(|c| c.code().eq_ignore_ascii_case(code))
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(self.code())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoneyError {
+    /// 不同币种不能直接相加减
+    CurrencyMismatch(Currency, Currency),
+    /// 金额的小数位数比币种允许的多，需要显式舍入
+    TooPrecise { amount: Decimal, currency: Currency },
+    UnknownCurrency(String),
+    Decimal(DecimalError),
+}
+
+impl Display for MoneyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch(a, b) => write!(f, "cannot combine {} with {}", a, b),
+            MoneyError::TooPrecise { amount, currency } => write!(
+                f,
+                "{} has more than {} decimal places allowed for {}",
+                amount,
+                currency.scale(),
+                currency
+            ),
+            MoneyError::UnknownCurrency(s) => write!(f, "unknown currency `{}`", s),
+            MoneyError::Decimal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+impl From<DecimalError> for MoneyError {
+    fn from(e: DecimalError) -> MoneyError {
+        MoneyError::Decimal(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Money {
+    // 总是使用币种的小数位数
+    amount: Decimal,
+    currency: Currency,
+}
+
+/// 浮点数转换为金额的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FloatMoney {
+    pub money: Money,
+    /// 是否没有发生舍入
+    pub exact: bool,
+    /// 浮点数在内存中实际保存的值
+    pub float_value: String,
+}
+
+impl Money {
+    /// 用最小单位创建，`Money::from_minor(200, Currency::Cny)` 就是 ¥2.00
+    pub fn from_minor(minor: i128, currency: Currency) -> Money {
+        Money { amount: Decimal::new(minor, currency.scale()), currency }
+    }
+
+    pub fn zero(currency: Currency) -> Money {
+        Money::from_minor(0, currency)
+    }
+
+    /// 用小数创建，小数位数不能比币种的多，否则需要先调用 `from_decimal_rounded`
+    pub fn from_decimal(amount: Decimal, currency: Currency) -> Result<Money, MoneyError> {
+        let scaled = amount.rescale(currency.scale(), Rounding::TowardZero)?;
+        if scaled != amount {
+            return Err(MoneyError::TooPrecise { amount, currency });
+        }
+        Ok(Money { amount: scaled, currency })
+    }
+
+    /// 用小数创建，按 mode 舍入到币种的小数位数
+    pub fn from_decimal_rounded(amount: Decimal, currency: Currency, mode: Rounding) -> Result<Money, MoneyError> {
+        Ok(Money { amount: amount.rescale(currency.scale(), mode)?, currency })
+    }
+
+    /// 从浮点数转换，结果里会说明是否发生了舍入
+    pub fn from_f64(value: f64, currency: Currency, mode: Rounding) -> Result<FloatMoney, MoneyError> {
+        let conversion = Decimal::from_f64(value, currency.scale(), mode)?;
+        Ok(FloatMoney {
+            money: Money { amount: conversion.value, currency },
+            exact: conversion.exact,
+            float_value: conversion.float_value,
+        })
+    }
+
+    /// 从浮点数转换，不能精确表示时返回错误
+    pub fn from_f64_exact(value: f64, currency: Currency) -> Result<Money, MoneyError> {
+        let amount = Decimal::from_f64_exact(value, currency.scale())?;
+        Ok(Money { amount, currency })
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// 以最小单位表示的金额，比如人民币的 “分”
+    pub fn minor_units(&self) -> i128 {
+        self.amount.units()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_negative()
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+        Ok(())
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        Ok(Money { amount: self.amount.checked_add(&other.amount)?, currency: self.currency })
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        Ok(Money { amount: self.amount.checked_sub(&other.amount)?, currency: self.currency })
+    }
+
+    pub fn checked_mul_int(&self, factor: i128) -> Result<Money, MoneyError> {
+        Ok(Money { amount: self.amount.checked_mul_int(factor)?, currency: self.currency })
+    }
+}
+
+impl Display for Money {
+    /// 币种符号加上固定位数的小数，如 ¥2.00、-$0.50
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let sign = if self.is_negative() { "-" } else { "" };
+        // 不用 abs，i128::MIN 没有对应的正数
+        let amount = self.amount.to_string();
+        let text = format!("{}{}{}", sign, self.currency.symbol(), amount.trim_start_matches('-'));
+        f.pad(&text)
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    /// 支持 `¥2.00`、`$-1.5`、`-€3`、`JP¥300`、`2.00 CNY`、`JPY 300` 这些写法
+    ///
+    /// 符号只能有一个，`-$-1` 是错误的；小数位数不能比币种的多，`¥0.001` 会返回 TooPrecise
+    fn from_str(s: &str) -> Result<Money, MoneyError> {
+        let s = s.trim();
+        let (negative, body) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, s),
+        };
+
+        let (currency, number) = if let Some((currency, rest)) =
+            Currency::ALL.iter().find_map(|c| body.strip_prefix(c.symbol()).map(|rest| (*c, rest)))
+        {
+            (currency, rest.trim())
+        } else if let Some((left, right)) = body.split_once(' ') {
+            match (Currency::from_code(left.trim()), Currency::from_code(right.trim())) {
+                (Some(currency), _) => (currency, right.trim()),
+                (None, Some(currency)) => (currency, left.trim()),
+                (None, None) => return Err(MoneyError::UnknownCurrency(body.to_string())),
+            }
+        } else {
+            return Err(MoneyError::UnknownCurrency(body.to_string()));
+        };
+
+        if negative && number.starts_with(['-', '+']) {
+            return Err(MoneyError::Decimal(DecimalError::Invalid(s.to_string())));
+        }
+        let amount: Decimal = number.parse()?;
+        Money::from_decimal(if negative { amount.checked_neg()? } else { amount }, currency)
+    }
+}
@@ -0,0 +1,393 @@
+/*
+数值类型的 trait
+
+Rust 的算术运算要求两边是同一个类型，`2i32 + 3i8` 不能编译，只能先用 `as` 或 From 转换。
+这里为所有整数与浮点类型实现一组 trait，在它们之上写出对任何数值类型都能用的泛型函数：
+
+- `Zero`、`One`：0 和 1
+- `CheckedOps`：溢出时返回 None 的加减乘除；`WrappingOps`：溢出时回绕的整数运算
+- `Num`：所有数值类型共同的部分，`Int` 与 `Float` 在它上面加上整数和浮点数各自的部分
+- `Widen<T>`：一定不会丢失信息的转换，比如 i8 到 i32、u32 到 f64
+- `Promote<B>`：两个类型都能无损转换到的最小的类型，比如 i8 和 u8 是 i16
+- `Narrow<T>`：可能放不下的整数转换，必须选择放不下时怎么办：返回 None、截断或者取边界值
+
+    assert_eq!(5, add(2, 3i8));         // i32 和 i8，结果是 i32
+    assert_eq!(300, add(200u8, 100i8)); // u8 和 i8，结果是 i16
+    let x: u8 = 300i32.narrow_saturating(); // 255
+
+没有这样的类型时（比如 i64 与 f64，i64 的大数在 f64 中放不下），add 不能编译，需要先自己转换。
+isize、usize 的位数取决于平台，这里按不超过 64 位处理，所以它们可以无损转换为 64 与 128 位的整数。
+ */
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+const _: () = assert!(usize::BITS <= 64);
+
+pub trait Zero: Sized {
+    const ZERO: Self;
+
+    fn is_zero(&self) -> bool;
+}
+
+pub trait One: Sized {
+    const ONE: Self;
+}
+
+/// 溢出（浮点数是结果不是有限的数）或者除以 0 时返回 None
+pub trait CheckedOps: Sized {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+}
+
+/// 溢出时回绕，只保留低位
+pub trait WrappingOps: Sized {
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+}
+
+/// 所有的数值类型
+pub trait Num:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + Debug
+    + Display
+    + Zero
+    + One
+    + CheckedOps
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const NAME: &'static str;
+
+    /// 同一类的数中最宽的类型：有符号整数是 i128，无符号整数是 u128，浮点数是 f64，sum 用它累加
+    type Wide: Num;
+
+    fn widest(self) -> Self::Wide;
+
+    /// 和 `as f64` 一样，可能丢失精度
+    fn to_f64(self) -> f64;
+}
+
+pub trait Int: Num + Eq + Ord + WrappingOps {
+    const MIN: Self;
+    const MAX: Self;
+    const BITS: u32;
+    const SIGNED: bool;
+}
+
+pub trait Float: Num {
+    const NAN: Self;
+    const INFINITY: Self;
+
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_int {
+    ($($t:ty => $wide:ty, $signed:expr;)*) => {
+        $(
+            impl Zero for $t {
+                const ZERO: $t = 0;
+
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+            }
+
+            impl One for $t {
+                const ONE: $t = 1;
+            }
+
+            impl CheckedOps for $t {
+                fn checked_add(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_div(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_div(self, rhs)
+                }
+            }
+
+            impl WrappingOps for $t {
+                fn wrapping_add(self, rhs: $t) -> $t {
+                    <$t>::wrapping_add(self, rhs)
+                }
+
+                fn wrapping_sub(self, rhs: $t) -> $t {
+                    <$t>::wrapping_sub(self, rhs)
+                }
+
+                fn wrapping_mul(self, rhs: $t) -> $t {
+                    <$t>::wrapping_mul(self, rhs)
+                }
+            }
+
+            impl Num for $t {
+                const NAME: &'static str = stringify!($t);
+
+                type Wide = $wide;
+
+                fn widest(self) -> $wide {
+                    self as $wide
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+
+            impl Int for $t {
+                const MIN: $t = <$t>::MIN;
+                const MAX: $t = <$t>::MAX;
+                const BITS: u32 = <$t>::BITS;
+                const SIGNED: bool = $signed;
+            }
+        )*
+    };
+}
+
+impl_int! {
+    i8 => i128, true;
+    i16 => i128, true;
+    i32 => i128, true;
+    i64 => i128, true;
+    i128 => i128, true;
+    isize => i128, true;
+    u8 => u128, false;
+    u16 => u128, false;
+    u32 => u128, false;
+    u64 => u128, false;
+    u128 => u128, false;
+    usize => u128, false;
+}
+
+// 浮点数的结果不是有限的数时就是溢出了
+fn finite<T: Float>(value: T) -> Option<T> {
+    Some(value).filter(|v| v.is_finite())
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(
+            impl Zero for $t {
+                const ZERO: $t = 0.0;
+
+                fn is_zero(&self) -> bool {
+                    *self == 0.0
+                }
+            }
+
+            impl One for $t {
+                const ONE: $t = 1.0;
+            }
+
+            impl CheckedOps for $t {
+                fn checked_add(self, rhs: $t) -> Option<$t> {
+                    finite(self + rhs)
+                }
+
+                fn checked_sub(self, rhs: $t) -> Option<$t> {
+                    finite(self - rhs)
+                }
+
+                fn checked_mul(self, rhs: $t) -> Option<$t> {
+                    finite(self * rhs)
+                }
+
+                fn checked_div(self, rhs: $t) -> Option<$t> {
+                    finite(self / rhs)
+                }
+            }
+
+            impl Num for $t {
+                const NAME: &'static str = stringify!($t);
+
+                type Wide = f64;
+
+                fn widest(self) -> f64 {
+                    self as f64
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+
+            impl Float for $t {
+                const NAN: $t = <$t>::NAN;
+                const INFINITY: $t = <$t>::INFINITY;
+
+                fn is_finite(self) -> bool {
+                    <$t>::is_finite(self)
+                }
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+/// 无损转换为 T
+pub trait Widen<T>: Sized {
+    fn widen(self) -> T;
+}
+
+impl<T: Num> Widen<T> for T {
+    fn widen(self) -> T {
+        self
+    }
+}
+
+// 这里列出的转换都不会丢失信息，所以可以直接用 as
+macro_rules! widen {
+    ($($from:ident => $($to:ident)*;)*) => {
+        $($(
+            impl Widen<$to> for $from {
+                fn widen(self) -> $to {
+                    self as $to
+                }
+            }
+        )*)*
+    };
+}
+
+widen! {
+    i8 => i16 i32 i64 i128 isize f32 f64;
+    i16 => i32 i64 i128 isize f32 f64;
+    i32 => i64 i128 f64;
+    i64 => i128;
+    isize => i64 i128;
+    u8 => i16 i32 i64 i128 isize u16 u32 u64 u128 usize f32 f64;
+    u16 => i32 i64 i128 u32 u64 u128 usize f32 f64;
+    u32 => i64 i128 u64 u128 f64;
+    u64 => i128 u128;
+    usize => i128 u64 u128;
+    f32 => f64;
+}
+
+/// Self 与 Rhs 都能无损转换到的最小的类型
+pub trait Promote<Rhs: Num>: Num {
+    type Common: Num;
+
+    fn promote(self, rhs: Rhs) -> (Self::Common, Self::Common);
+}
+
+macro_rules! promote {
+    ($($lhs:ty: $($rhs:ty => $out:ty),*;)*) => {
+        $($(
+            impl Promote<$rhs> for $lhs {
+                type Common = $out;
+
+                fn promote(self, rhs: $rhs) -> ($out, $out) {
+                    (self.widen(), rhs.widen())
+                }
+            }
+        )*)*
+    };
+}
+
+// 没有列出的组合没有这样的类型，比如 u128 与 i8、i64 与 f64
+promote! {
+    i8: i8 => i8, i16 => i16, i32 => i32, i64 => i64, i128 => i128, isize => isize, u8 => i16, u16 => i32, u32 => i64, u64 => i128, usize => i128, f32 => f32, f64 => f64;
+    i16: i8 => i16, i16 => i16, i32 => i32, i64 => i64, i128 => i128, isize => isize, u8 => i16, u16 => i32, u32 => i64, u64 => i128, usize => i128, f32 => f32, f64 => f64;
+    i32: i8 => i32, i16 => i32, i32 => i32, i64 => i64, i128 => i128, isize => i64, u8 => i32, u16 => i32, u32 => i64, u64 => i128, usize => i128, f32 => f64, f64 => f64;
+    i64: i8 => i64, i16 => i64, i32 => i64, i64 => i64, i128 => i128, isize => i64, u8 => i64, u16 => i64, u32 => i64, u64 => i128, usize => i128;
+    i128: i8 => i128, i16 => i128, i32 => i128, i64 => i128, i128 => i128, isize => i128, u8 => i128, u16 => i128, u32 => i128, u64 => i128, usize => i128;
+    isize: i8 => isize, i16 => isize, i32 => i64, i64 => i64, i128 => i128, isize => isize, u8 => isize, u16 => i64, u32 => i64, u64 => i128, usize => i128;
+    u8: i8 => i16, i16 => i16, i32 => i32, i64 => i64, i128 => i128, isize => isize, u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize, f32 => f32, f64 => f64;
+    u16: i8 => i32, i16 => i32, i32 => i32, i64 => i64, i128 => i128, isize => i64, u8 => u16, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize, f32 => f32, f64 => f64;
+    u32: i8 => i64, i16 => i64, i32 => i64, i64 => i64, i128 => i128, isize => i64, u8 => u32, u16 => u32, u32 => u32, u64 => u64, u128 => u128, usize => u64, f32 => f64, f64 => f64;
+    u64: i8 => i128, i16 => i128, i32 => i128, i64 => i128, i128 => i128, isize => i128, u8 => u64, u16 => u64, u32 => u64, u64 => u64, u128 => u128, usize => u64;
+    u128: u8 => u128, u16 => u128, u32 => u128, u64 => u128, u128 => u128, usize => u128;
+    usize: i8 => i128, i16 => i128, i32 => i128, i64 => i128, i128 => i128, isize => i128, u8 => usize, u16 => usize, u32 => u64, u64 => u64, u128 => u128, usize => usize;
+    f32: i8 => f32, i16 => f32, i32 => f64, u8 => f32, u16 => f32, u32 => f64, f32 => f32, f64 => f64;
+    f64: i8 => f64, i16 => f64, i32 => f64, u8 => f64, u16 => f64, u32 => f64, f32 => f64, f64 => f64;
+}
+
+/// 可能放不下的整数转换
+pub trait Narrow<T>: Sized {
+    /// 放不下时返回 None，和 TryFrom 一样
+    fn narrow_checked(self) -> Option<T>;
+
+    /// 放不下时截断，只保留低位，和 `as` 一样
+    fn narrow_wrapping(self) -> T;
+
+    /// 放不下时取 T 的最小值或最大值
+    fn narrow_saturating(self) -> T;
+}
+
+macro_rules! narrow {
+    ($($from:ident)* => $to:tt) => {
+        $(narrow!(@each $from => $to);)*
+    };
+    (@each $from:ident => [$($to:ident)*]) => {
+        $(
+            impl Narrow<$to> for $from {
+                fn narrow_checked(self) -> Option<$to> {
+                    <$to>::try_from(self).ok()
+                }
+
+                fn narrow_wrapping(self) -> $to {
+                    self as $to
+                }
+
+                fn narrow_saturating(self) -> $to {
+                    match <$to>::try_from(self) {
+                        Ok(value) => value,
+                        Err(_) if self < <$from as Zero>::ZERO => <$to>::MIN,
+                        Err(_) => <$to>::MAX,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+narrow!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize => [i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize]);
+
+/// 把 a 和 b 无损转换为同一个类型后相加，溢出时和 `+` 一样
+pub fn add<A: Promote<B>, B: Num>(a: A, b: B) -> A::Common {
+    let (a, b) = a.promote(b);
+    a + b
+}
+
+/// 把 a 和 b 无损转换为同一个类型后相加，溢出时返回 None
+pub fn checked_add<A: Promote<B>, B: Num>(a: A, b: B) -> Option<A::Common> {
+    let (a, b) = a.promote(b);
+    a.checked_add(b)
+}
+
+/// 用同一类中最宽的类型累加，比如 i8 用 i128，仍然溢出时返回 None
+pub fn sum<T: Num>(values: impl IntoIterator<Item = T>) -> Option<T::Wide> {
+    values.into_iter().try_fold(<T::Wide as Zero>::ZERO, |total, value| total.checked_add(value.widest()))
+}
+
+/// 平均值，没有值或者累加溢出时返回 None
+pub fn mean<T: Num>(values: impl IntoIterator<Item = T>) -> Option<f64> {
+    let mut count = 0usize;
+    let total = sum(values.into_iter().inspect(|_| count += 1))?;
+    if count == 0 {
+        return None;
+    }
+    Some(total.to_f64() / count as f64)
+}
@@ -0,0 +1,581 @@
+/*
+带步长的范围
+
+    for n in 1..100 {}    // 1, 2, ..., 99
+    for n in 1..=100 {}   // 1, 2, ..., 100
+
+标准库的范围只能每次加 1，`(1..100).step_by(3)` 可以跳着取，但步长不能是负数，
+倒着取需要写成 `(1..100).rev()`，而且浮点数没有范围迭代器。
+这里的 `IntRange` 支持正负步长，`IntRange::new(10, 0, -3)` 依次产生 10, 7, 4, 1，
+计算长度时不会溢出，i128 与 u128 的整个取值范围也可以使用。
+
+浮点数不能用 `x += step` 的方式累加：0.1 不能被二进制精确表示，每次相加都会带来误差，
+加 10 次得到的是 0.9999999999999999。`FloatRange` 的第 i 个元素总是直接用 `start + step * i` 计算，
+误差不会累积；`linspace` 与 numpy 的同名函数一样，把一段区间平均分成若干个点，包含终点时最后一个点就是终点本身。
+ */
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::FusedIterator;
+use std::ops::{Add, Div, Mul, Range, RangeInclusive, Sub};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeError {
+    /// 步长为 0
+    ZeroStep,
+    /// 整数范围的步长为 i128::MIN，倒序时它的相反数无法表示
+    StepOverflow,
+    /// 起点、终点或步长是 NaN 或无穷大
+    NotFinite,
+    /// 元素的个数超过了 usize 的范围
+    TooLong,
+}
+
+impl Display for RangeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RangeError::ZeroStep => write!(f, "range step cannot be zero"),
+            RangeError::StepOverflow => write!(f, "range step cannot be i128::MIN"),
+            RangeError::NotFinite => write!(f, "range bounds and step must be finite"),
+            RangeError::TooLong => write!(f, "range has more than usize::MAX elements"),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// 可以用在 `IntRange` 中的整数类型
+///
+/// 为了让所有整数类型共用一套计算，把值按大小顺序映射到 u128：无符号数不变，
+/// 有符号数先扩展为 i128 再翻转符号位，负数排在正数前面，两个值的差就是映射后的差。
+pub trait RangeInt: Copy + Ord + Debug + Display {
+    fn to_key(self) -> u128;
+    fn from_key(key: u128) -> Self;
+}
+
+const SIGN: u128 = 1 << 127;
+
+macro_rules! impl_range_int {
+    (signed: $($s:ty)*; unsigned: $($u:ty)*) => {
+        $(
+            impl RangeInt for $s {
+                fn to_key(self) -> u128 {
+                    (self as i128 as u128) ^ SIGN
+                }
+
+                fn from_key(key: u128) -> $s {
+                    (key ^ SIGN) as i128 as $s
+                }
+            }
+        )*
+        $(
+            impl RangeInt for $u {
+                fn to_key(self) -> u128 {
+                    self as u128
+                }
+
+                fn from_key(key: u128) -> $u {
+                    key as $u
+                }
+            }
+        )*
+    };
+}
+
+impl_range_int!(signed: i8 i16 i32 i64 i128 isize; unsigned: u8 u16 u32 u64 u128 usize);
+
+/// 整数范围，step 为负数时从大到小
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntRange<T> {
+    start: T,
+    end: T,
+    step: i128,
+    inclusive: bool,
+}
+
+impl<T: RangeInt> IntRange<T> {
+    /// `start..end`，不包含 end
+    pub fn new(start: T, end: T, step: i128) -> Result<IntRange<T>, RangeError> {
+        match step {
+            0 => Err(RangeError::ZeroStep),
+            i128::MIN => Err(RangeError::StepOverflow),
+            _ => Ok(IntRange { start, end, step, inclusive: false }),
+        }
+    }
+
+    /// `start..=end`，包含 end（如果按步长能取到的话）
+    pub fn inclusive(start: T, end: T, step: i128) -> Result<IntRange<T>, RangeError> {
+        Ok(IntRange { inclusive: true, ..IntRange::new(start, end, step)? })
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn step(&self) -> i128 {
+        self.step
+    }
+
+    pub fn is_inclusive(&self) -> bool {
+        self.inclusive
+    }
+
+    /// 步长的方向与起点到终点的方向相反，或者起点等于不包含的终点时，范围为空
+    pub fn is_empty(&self) -> bool {
+        match (self.step > 0, self.inclusive) {
+            (true, false) => self.start >= self.end,
+            (true, true) => self.start > self.end,
+            (false, false) => self.start <= self.end,
+            (false, true) => self.start < self.end,
+        }
+    }
+
+    // 最后一个元素是第几个，范围为空时为 None；这个值总能放进 u128，元素个数却不一定
+    fn last_index(&self) -> Option<u128> {
+        if self.is_empty() {
+            return None;
+        }
+        let (start, end) = (self.start.to_key(), self.end.to_key());
+        let distance = if self.step > 0 { end - start } else { start - end };
+        // 不包含终点时，最后一个元素至少比终点差 1
+        let distance = if self.inclusive { distance } else { distance - 1 };
+        Some(distance / self.step.unsigned_abs())
+    }
+
+    /// 元素个数，超过 usize 的范围时为 None
+    pub fn checked_len(&self) -> Option<usize> {
+        match self.last_index() {
+            None => Some(0),
+            Some(last) => usize::try_from(last).ok()?.checked_add(1),
+        }
+    }
+
+    /// 元素个数，只有 u128 的整个取值范围会超过 u128，此时为 None
+    pub fn len_u128(&self) -> Option<u128> {
+        self.last_index().map_or(Some(0), |last| last.checked_add(1))
+    }
+
+    /// 第 index 个元素
+    pub fn get(&self, index: u128) -> Option<T> {
+        if index > self.last_index()? {
+            return None;
+        }
+        Some(self.nth_value(index))
+    }
+
+    fn nth_value(&self, index: u128) -> T {
+        // index 不超过 last_index，结果一定在起点与终点之间，这里的乘法与加减都不会溢出
+        let offset = index * self.step.unsigned_abs();
+        let start = self.start.to_key();
+        T::from_key(if self.step > 0 { start + offset } else { start - offset })
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.last_index().map(|last| self.nth_value(last))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let Some(last) = self.last() else {
+            return false;
+        };
+        let (low, high) = if self.step > 0 { (self.start, last) } else { (last, self.start) };
+        let distance = value.to_key().abs_diff(self.start.to_key());
+        low <= value && value <= high && distance.is_multiple_of(self.step.unsigned_abs())
+    }
+
+    /// 倒序：从最后一个元素开始，以相反的步长回到起点
+    pub fn reversed(&self) -> IntRange<T> {
+        match self.last() {
+            Some(last) => IntRange { start: last, end: self.start, step: -self.step, inclusive: true },
+            // 空的范围倒过来仍然是空的
+            None => IntRange { start: self.start, end: self.start, step: -self.step, inclusive: false },
+        }
+    }
+
+    pub fn iter(&self) -> IntIter<T> {
+        IntIter { range: *self, front: 0, back: self.last_index().and_then(|last| last.checked_add(1)), exhausted: self.is_empty() }
+    }
+}
+
+impl<T: RangeInt> Display for IntRange<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let dots = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{}{} step {}", self.start, dots, self.end, self.step)
+    }
+}
+
+impl<T: RangeInt> IntoIterator for IntRange<T> {
+    type Item = T;
+    type IntoIter = IntIter<T>;
+
+    fn into_iter(self) -> IntIter<T> {
+        self.iter()
+    }
+}
+
+impl<T: RangeInt> IntoIterator for &IntRange<T> {
+    type Item = T;
+    type IntoIter = IntIter<T>;
+
+    fn into_iter(self) -> IntIter<T> {
+        self.iter()
+    }
+}
+
+/// `IntRange` 的迭代器
+#[derive(Debug, Clone)]
+pub struct IntIter<T> {
+    range: IntRange<T>,
+    // 还没有产生的是第 front 到第 back - 1 个；back 为 None 表示 2^128，只有 u128 的整个范围会这样
+    front: u128,
+    back: Option<u128>,
+    exhausted: bool,
+}
+
+impl<T: RangeInt> IntIter<T> {
+    fn remaining(&self) -> Option<u128> {
+        match self.back {
+            _ if self.exhausted => Some(0),
+            Some(back) => Some(back - self.front),
+            None => (u128::MAX - self.front).checked_add(1),
+        }
+    }
+}
+
+impl<T: RangeInt> Iterator for IntIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.exhausted || self.back == Some(self.front) {
+            return None;
+        }
+        let value = self.range.nth_value(self.front);
+        match self.front.checked_add(1) {
+            Some(front) => self.front = front,
+            None => self.exhausted = true,
+        }
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining().and_then(|n| usize::try_from(n).ok()) {
+            Some(n) => (n, Some(n)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<T: RangeInt> DoubleEndedIterator for IntIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.exhausted || self.back == Some(self.front) {
+            return None;
+        }
+        let back = match self.back {
+            Some(back) => back - 1,
+            None => u128::MAX,
+        };
+        self.back = Some(back);
+        Some(self.range.nth_value(back))
+    }
+}
+
+impl<T: RangeInt> FusedIterator for IntIter<T> {}
+
+/// 可以用在 `FloatRange` 中的浮点类型
+pub trait RangeFloat:
+    Copy + PartialOrd + Debug + Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+{
+    const ZERO: Self;
+    const EPSILON: Self;
+
+    fn is_finite(self) -> bool;
+    fn abs(self) -> Self;
+    fn round(self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn from_usize(n: usize) -> Self;
+    // 超出范围时饱和，NaN 为 0，与 `as` 相同
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_range_float {
+    ($($t:ident)*) => {
+        $(
+            impl RangeFloat for $t {
+                const ZERO: $t = 0.0;
+                const EPSILON: $t = $t::EPSILON;
+
+                fn is_finite(self) -> bool {
+                    $t::is_finite(self)
+                }
+
+                fn abs(self) -> $t {
+                    $t::abs(self)
+                }
+
+                fn round(self) -> $t {
+                    $t::round(self)
+                }
+
+                fn floor(self) -> $t {
+                    $t::floor(self)
+                }
+
+                fn ceil(self) -> $t {
+                    $t::ceil(self)
+                }
+
+                fn from_usize(n: usize) -> $t {
+                    n as $t
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_range_float!(f32 f64);
+
+/// 浮点数范围，第 i 个元素是 `start + step * i`，`end` 不为 None 时最后一个元素就是 end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatRange<F> {
+    start: F,
+    step: F,
+    len: usize,
+    end: Option<F>,
+}
+
+// (stop - start) / step 由于舍入可能是 2.9999999999999996 或 3.0000000000000004，离整数足够近时当作整数
+fn snapped_steps<F: RangeFloat>(start: F, stop: F, step: F) -> Result<(F, bool), RangeError> {
+    if !(start.is_finite() && stop.is_finite() && step.is_finite()) {
+        return Err(RangeError::NotFinite);
+    }
+    if step == F::ZERO {
+        return Err(RangeError::ZeroStep);
+    }
+    let steps = (stop - start) / step;
+    if !steps.is_finite() {
+        return Err(RangeError::TooLong);
+    }
+    let nearest = steps.round();
+    let tolerance = F::EPSILON * F::from_usize(4) * nearest.abs();
+    match (steps - nearest).abs() <= tolerance {
+        true => Ok((nearest, true)),
+        false => Ok((steps, false)),
+    }
+}
+
+fn to_len<F: RangeFloat>(n: F) -> Result<usize, RangeError> {
+    let n = n.to_f64();
+    if n <= 0.0 {
+        Ok(0)
+    } else if n < usize::MAX as f64 {
+        Ok(n as usize)
+    } else {
+        Err(RangeError::TooLong)
+    }
+}
+
+impl<F: RangeFloat> FloatRange<F> {
+    /// `start..stop`，与 numpy 的 arange 相同，但按整数步数判断是否取到 stop 时容忍舍入误差：
+    /// `FloatRange::new(1.0, 1.3, 0.1)` 只有 1.0、1.1、1.2 三个元素
+    pub fn new(start: F, stop: F, step: F) -> Result<FloatRange<F>, RangeError> {
+        let (steps, _) = snapped_steps(start, stop, step)?;
+        Ok(FloatRange { start, step, len: to_len(steps.ceil())?, end: None })
+    }
+
+    /// `start..=stop`，stop 正好在步长上时最后一个元素就是 stop
+    pub fn inclusive(start: F, stop: F, step: F) -> Result<FloatRange<F>, RangeError> {
+        let (steps, exact) = snapped_steps(start, stop, step)?;
+        if steps < F::ZERO {
+            return Ok(FloatRange { start, step, len: 0, end: None });
+        }
+        let len = to_len(steps.floor())?.checked_add(1).ok_or(RangeError::TooLong)?;
+        Ok(FloatRange { start, step, len, end: if exact { Some(stop) } else { None } })
+    }
+
+    /// 把 start 到 stop 平均分为 num 个点，endpoint 为 true 时包含 stop，与 numpy 的 linspace 相同
+    pub fn linspace(start: F, stop: F, num: usize, endpoint: bool) -> Result<FloatRange<F>, RangeError> {
+        if !(start.is_finite() && stop.is_finite()) {
+            return Err(RangeError::NotFinite);
+        }
+        let div = if endpoint { num.saturating_sub(1) } else { num };
+        // 只有一个点时步长没有意义，取整段的长度
+        let step = if div == 0 { stop - start } else { (stop - start) / F::from_usize(div) };
+        let end = if endpoint && num > 1 { Some(stop) } else { None };
+        Ok(FloatRange { start, step, len: num, end })
+    }
+
+    pub fn start(&self) -> F {
+        self.start
+    }
+
+    pub fn step(&self) -> F {
+        self.step
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<F> {
+        match index {
+            i if i >= self.len => None,
+            i if i + 1 == self.len && self.end.is_some() => self.end,
+            i => Some(self.start + self.step * F::from_usize(i)),
+        }
+    }
+
+    pub fn first(&self) -> Option<F> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<F> {
+        self.get(self.len.checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> FloatIter<F> {
+        FloatIter { range: *self, front: 0, back: self.len }
+    }
+
+    pub fn to_vec(&self) -> Vec<F> {
+        self.iter().collect()
+    }
+}
+
+impl<F: RangeFloat> Display for FloatRange<F> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (self.first(), self.last()) {
+            (Some(first), Some(last)) => write!(f, "{}..={} step {} ({} values)", first, last, self.step, self.len),
+            _ => write!(f, "empty range from {} step {}", self.start, self.step),
+        }
+    }
+}
+
+impl<F: RangeFloat> IntoIterator for FloatRange<F> {
+    type Item = F;
+    type IntoIter = FloatIter<F>;
+
+    fn into_iter(self) -> FloatIter<F> {
+        self.iter()
+    }
+}
+
+impl<F: RangeFloat> IntoIterator for &FloatRange<F> {
+    type Item = F;
+    type IntoIter = FloatIter<F>;
+
+    fn into_iter(self) -> FloatIter<F> {
+        self.iter()
+    }
+}
+
+/// `FloatRange` 的迭代器
+#[derive(Debug, Clone)]
+pub struct FloatIter<F> {
+    range: FloatRange<F>,
+    front: usize,
+    back: usize,
+}
+
+impl<F: RangeFloat> Iterator for FloatIter<F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.range.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<F: RangeFloat> DoubleEndedIterator for FloatIter<F> {
+    fn next_back(&mut self) -> Option<F> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.range.get(self.back)
+    }
+}
+
+impl<F: RangeFloat> ExactSizeIterator for FloatIter<F> {}
+
+impl<F: RangeFloat> FusedIterator for FloatIter<F> {}
+
+/// 为标准库的范围加上步长：`(0..10).stepped(3)`、`(0.0f64..=1.0).stepped(0.25)`
+///
+/// 也可以写 `(10..0).stepped(-3)`，但 clippy 会把 `10..0` 当作写反了的空范围而报错，
+/// 倒着取时建议直接用 `IntRange::new(10, 0, -3)`
+pub trait Stepped {
+    /// 整数范围的步长是 i128，浮点数范围的步长与元素类型相同
+    type Step;
+    type Output;
+
+    fn stepped(self, step: Self::Step) -> Result<Self::Output, RangeError>;
+}
+
+macro_rules! impl_stepped {
+    (int: $($i:ty)*; float: $($f:ty)*) => {
+        $(
+            impl Stepped for Range<$i> {
+                type Step = i128;
+                type Output = IntRange<$i>;
+
+                fn stepped(self, step: i128) -> Result<IntRange<$i>, RangeError> {
+                    IntRange::new(self.start, self.end, step)
+                }
+            }
+
+            impl Stepped for RangeInclusive<$i> {
+                type Step = i128;
+                type Output = IntRange<$i>;
+
+                fn stepped(self, step: i128) -> Result<IntRange<$i>, RangeError> {
+                    IntRange::inclusive(*self.start(), *self.end(), step)
+                }
+            }
+        )*
+        $(
+            impl Stepped for Range<$f> {
+                type Step = $f;
+                type Output = FloatRange<$f>;
+
+                fn stepped(self, step: $f) -> Result<FloatRange<$f>, RangeError> {
+                    FloatRange::new(self.start, self.end, step)
+                }
+            }
+
+            impl Stepped for RangeInclusive<$f> {
+                type Step = $f;
+                type Output = FloatRange<$f>;
+
+                fn stepped(self, step: $f) -> Result<FloatRange<$f>, RangeError> {
+                    FloatRange::inclusive(*self.start(), *self.end(), step)
+                }
+            }
+        )*
+    };
+}
+
+impl_stepped!(int: i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize; float: f32 f64);
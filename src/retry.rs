@@ -0,0 +1,268 @@
+/*
+失败重试
+
+loop 可以用 break 带出一个值，重试就是这个写法最常见的用途：
+
+    let mut attempt = 0;
+    let result = loop {
+        attempt += 1;
+        match connect() {
+            Ok(conn) => break Ok(conn),
+            Err(e) if attempt == 5 => break Err(e),
+            Err(_) => thread::sleep(delay),
+        }
+    };
+
+`Retry` 把它包装起来：最多尝试几次、两次尝试之间等待多久（退避，backoff）、哪些错误值得重试，
+并返回成功的值或最后一个错误，以及尝试次数、每次等待的时间等统计。
+
+等待的时间有三种算法：
+- Fixed：每次相同
+- Exponential：每次乘以一个倍数，直到上限，避免在服务恢复前频繁重试
+- Jittered：在指数退避的延迟以内随机选择（full jitter），避免大量客户端在同一时刻一起重试；
+  随机数由 seed 决定，同样的 seed 得到同样的序列
+
+等待通过 `Clock` 完成，测试中使用 `ManualClock`，sleep 只是把时间往前拨，不会真的等待，结果完全确定。
+ */
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+/// 时钟：读取经过的时间，以及等待
+pub trait Clock {
+    /// 从时钟创建开始经过的时间
+    fn elapsed(&self) -> Duration;
+    fn sleep(&self, duration: Duration);
+}
+
+/// 真实的时钟，sleep 会阻塞当前线程
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// 手动拨动的时钟，sleep 立即返回，只记录时间
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+    sleeps: RefCell<Vec<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    /// 让时间前进，模拟操作本身花费的时间
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// 所有 sleep 的时长
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.borrow().clone()
+    }
+}
+
+impl Clock for ManualClock {
+    fn elapsed(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+        self.sleeps.borrow_mut().push(duration);
+    }
+}
+
+/// 两次尝试之间等待多久
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    Fixed(Duration),
+    /// 第 n 次等待 initial * factor^n，不超过 max
+    Exponential { initial: Duration, factor: u32, max: Duration },
+    /// 在 [0, 指数退避的延迟] 中随机选择
+    Jittered { initial: Duration, factor: u32, max: Duration, seed: u64 },
+}
+
+impl Backoff {
+    /// 依次产生每次等待的时间，是一个无限的迭代器
+    pub fn delays(&self) -> Delays {
+        let seed = match self {
+            Backoff::Jittered { seed, .. } => *seed,
+            _ => 0,
+        };
+        Delays { backoff: *self, n: 0, rng: SplitMix64(seed) }
+    }
+}
+
+// initial * factor^n，溢出或超过上限时为上限
+fn exponential(initial: Duration, factor: u32, max: Duration, n: u32) -> Duration {
+    factor.checked_pow(n).and_then(|m| initial.checked_mul(m)).map_or(max, |d| d.min(max))
+}
+
+/// `Backoff::delays` 返回的迭代器
+#[derive(Debug, Clone)]
+pub struct Delays {
+    backoff: Backoff,
+    n: u32,
+    rng: SplitMix64,
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let n = self.n;
+        self.n = self.n.saturating_add(1);
+        Some(match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, factor, max } => exponential(initial, factor, max, n),
+            Backoff::Jittered { initial, factor, max, .. } => {
+                let cap = exponential(initial, factor, max, n).as_nanos().min(u64::MAX as u128) as u64;
+                Duration::from_nanos(self.rng.below(cap.saturating_add(1)))
+            }
+        })
+    }
+}
+
+// 简单、可以指定种子的伪随机数生成器，见 https://prng.di.unimi.it/splitmix64.c
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // [0, n) 中的随机数，n 为 0 时返回 u64 范围内的任意值
+    fn below(&mut self, n: u64) -> u64 {
+        match n {
+            0 => self.next_u64(),
+            n => ((self.next_u64() as u128 * n as u128) >> 64) as u64,
+        }
+    }
+}
+
+/// 为什么停止重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Succeeded,
+    /// 已经用完了所有的尝试次数
+    Exhausted,
+    /// 错误不值得重试
+    NotRetryable,
+}
+
+/// 重试的统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// 调用操作的次数，包括第一次
+    pub attempts: u32,
+    /// 每次失败后等待的时间
+    pub delays: Vec<Duration>,
+    /// 从开始到结束经过的时间，包括操作本身和等待
+    pub elapsed: Duration,
+    pub stop: Stop,
+}
+
+impl Stats {
+    pub fn total_delay(&self) -> Duration {
+        self.delays.iter().sum()
+    }
+}
+
+/// 重试的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome<T, E> {
+    /// 第一次成功的值，或者最后一个错误
+    pub result: Result<T, E>,
+    pub stats: Stats,
+}
+
+impl<T, E> Outcome<T, E> {
+    pub fn into_result(self) -> Result<T, E> {
+        self.result
+    }
+}
+
+/// 重试的策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    max_attempts: u32,
+    backoff: Backoff,
+}
+
+impl Retry {
+    /// 最多尝试 max_attempts 次（至少一次），默认不等待
+    pub fn new(max_attempts: u32) -> Retry {
+        Retry { max_attempts: max_attempts.max(1), backoff: Backoff::Fixed(Duration::ZERO) }
+    }
+
+    pub fn backoff(self, backoff: Backoff) -> Retry {
+        Retry { backoff, ..self }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// 失败就重试，op 的参数是第几次尝试，从 1 开始
+    pub fn run<T, E>(&self, clock: &impl Clock, op: impl FnMut(u32) -> Result<T, E>) -> Outcome<T, E> {
+        self.run_if(clock, |_, _| true, op)
+    }
+
+    /// 只有 should_retry 对错误和这是第几次尝试返回 true 时才重试
+    pub fn run_if<T, E>(
+        &self,
+        clock: &impl Clock,
+        mut should_retry: impl FnMut(&E, u32) -> bool,
+        mut op: impl FnMut(u32) -> Result<T, E>,
+    ) -> Outcome<T, E> {
+        let start = clock.elapsed();
+        let mut delays = self.backoff.delays();
+        let mut waited = Vec::new();
+        let mut attempt = 0;
+        let (result, stop) = loop {
+            attempt += 1;
+            match op(attempt) {
+                Ok(value) => break (Ok(value), Stop::Succeeded),
+                Err(e) if attempt >= self.max_attempts => break (Err(e), Stop::Exhausted),
+                Err(e) if !should_retry(&e, attempt) => break (Err(e), Stop::NotRetryable),
+                Err(_) => {
+                    let delay = delays.next().unwrap_or_default();
+                    clock.sleep(delay);
+                    waited.push(delay);
+                }
+            }
+        };
+        let stats = Stats { attempts: attempt, delays: waited, elapsed: clock.elapsed() - start, stop };
+        Outcome { result, stats }
+    }
+}
@@ -0,0 +1,488 @@
+/*
+整除规则
+
+课程中用 if / else if 判断一个数能被谁整除：
+
+    if number % 4 == 0 {
+        println!("number is divisible by 4");
+    } else if number % 3 == 0 {
+        println!("number is divisible by 3");
+    } else if number % 2 == 0 {
+        println!("number is divisible by 2");
+    } else {
+        println!("number is not divisible by 4, 3, or 2");
+    }
+
+把每个分支看成一条规则 “条件 → 标签”，这类代码就可以用数据来描述：
+- FirstMatch 模式与 if / else if 相同，只取第一条满足的规则
+- AllMatches 模式取所有满足的规则，把标签连起来，FizzBuzz 就是 Fizz（3 的倍数）加 Buzz（5 的倍数）
+- 一条规则都不满足时，可以输出数字本身、一个默认标签或者什么都不输出
+
+规则可以在代码中构造，也可以从一段 TOML 格式的文本中读取（只支持下面用到的语法）：
+
+    mode = "all"            # first 或 all
+    separator = ""          # all 模式下标签之间的分隔符
+    fallback = "number"     # number、none 或者一个默认标签
+
+    [[rule]]
+    label = "Fizz"
+    divisible_by = 3
+
+    [[rule]]
+    label = "teen"
+    min = 13                # 同一条规则中的多个条件需要同时满足
+    max = 19
+ */
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// 无法解析的行，line 从 1 开始
+    Syntax { line: usize, text: String },
+    /// 不认识的键
+    UnknownKey { line: usize, key: String },
+    /// 值的类型或取值不对
+    InvalidValue { line: usize, key: String, expected: &'static str },
+    /// 没有这个名字的谓词
+    UnknownPredicate { line: usize, name: String },
+    /// 第 index 条规则（从 1 开始）没有 label 或没有任何条件
+    IncompleteRule { index: usize, missing: &'static str },
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RuleError::Syntax { line, text } => write!(f, "line {}: cannot parse `{}`", line, text),
+            RuleError::UnknownKey { line, key } => write!(f, "line {}: unknown key `{}`", line, key),
+            RuleError::InvalidValue { line, key, expected } => {
+                write!(f, "line {}: `{}` expects {}", line, key, expected)
+            }
+            RuleError::UnknownPredicate { line, name } => write!(f, "line {}: unknown predicate `{}`", line, name),
+            RuleError::IncompleteRule { index, missing } => write!(f, "rule {} has no {}", index, missing),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// 判断一个数的函数，用 Arc 包装，规则可以克隆，也可以在线程之间共享
+pub type Predicate = Arc<dyn Fn(i128) -> bool + Send + Sync>;
+
+/// 规则的条件
+#[derive(Clone)]
+pub enum Condition {
+    /// 能被 n 整除，n 为 0 时只有 0 满足
+    DivisibleBy(i128),
+    /// 在 [min, max] 中，None 表示这一侧没有限制
+    InRange { min: Option<i128>, max: Option<i128> },
+    /// 自定义的判断，name 用于打印
+    Predicate { name: String, test: Predicate },
+    /// 所有条件都满足，没有条件时总是满足
+    All(Vec<Condition>),
+    /// 任意一个条件满足
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    pub fn predicate(name: &str, test: impl Fn(i128) -> bool + Send + Sync + 'static) -> Condition {
+        Condition::Predicate { name: name.to_string(), test: Arc::new(test) }
+    }
+
+    pub fn matches(&self, n: i128) -> bool {
+        match self {
+            Condition::DivisibleBy(0) => n == 0,
+            // checked_rem 避免 i128::MIN % -1 溢出
+            Condition::DivisibleBy(d) => n.checked_rem(*d).unwrap_or(0) == 0,
+            Condition::InRange { min, max } => min.is_none_or(|min| n >= min) && max.is_none_or(|max| n <= max),
+            Condition::Predicate { test, .. } => test(n),
+            Condition::All(conditions) => conditions.iter().all(|c| c.matches(n)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.matches(n)),
+            Condition::Not(condition) => !condition.matches(n),
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let join = |f: &mut Formatter, conditions: &[Condition], op: &str| -> fmt::Result {
+            let parts: Vec<String> = conditions.iter().map(|c| format!("({})", c)).collect();
+            write!(f, "{}", parts.join(op))
+        };
+        match self {
+            Condition::DivisibleBy(d) => write!(f, "divisible by {}", d),
+            Condition::InRange { min: Some(min), max: Some(max) } => write!(f, "{} <= n <= {}", min, max),
+            Condition::InRange { min: Some(min), max: None } => write!(f, "n >= {}", min),
+            Condition::InRange { min: None, max: Some(max) } => write!(f, "n <= {}", max),
+            Condition::InRange { min: None, max: None } => write!(f, "any n"),
+            Condition::Predicate { name, .. } => write!(f, "{}", name),
+            Condition::All(conditions) if conditions.len() == 1 => write!(f, "{}", conditions[0]),
+            Condition::All(conditions) if conditions.is_empty() => write!(f, "always"),
+            Condition::All(conditions) => join(f, conditions, " and "),
+            Condition::Any(conditions) => join(f, conditions, " or "),
+            Condition::Not(condition) => write!(f, "not ({})", condition),
+        }
+    }
+}
+
+impl Debug for Condition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Condition({})", self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub label: String,
+    pub condition: Condition,
+}
+
+/// 有多条规则满足时怎样处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// 只取第一条，相当于 if / else if
+    FirstMatch,
+    /// 全部都取，相当于多个独立的 if
+    AllMatches,
+}
+
+/// 一条规则都不满足时的输出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fallback {
+    /// 数字本身，FizzBuzz 就是这样
+    Number,
+    Label(String),
+    /// 没有输出，`RuleSet::stream` 会跳过这个数
+    Nothing,
+}
+
+/// 按顺序排列的一组规则
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+    mode: Mode,
+    separator: String,
+    fallback: Fallback,
+}
+
+impl RuleSet {
+    /// 没有规则的规则集，不满足时输出数字本身，AllMatches 模式下标签直接连在一起
+    pub fn new(mode: Mode) -> RuleSet {
+        RuleSet { rules: Vec::new(), mode, separator: String::new(), fallback: Fallback::Number }
+    }
+
+    /// 经典的 FizzBuzz：3 的倍数为 Fizz，5 的倍数为 Buzz，两者都是时为 FizzBuzz
+    pub fn fizzbuzz() -> RuleSet {
+        RuleSet::new(Mode::AllMatches).divisible_by("Fizz", 3).divisible_by("Buzz", 5)
+    }
+
+    pub fn rule(mut self, label: &str, condition: Condition) -> RuleSet {
+        self.rules.push(Rule { label: label.to_string(), condition });
+        self
+    }
+
+    pub fn divisible_by(self, label: &str, divisor: i128) -> RuleSet {
+        self.rule(label, Condition::DivisibleBy(divisor))
+    }
+
+    /// 在 [min, max] 中
+    pub fn in_range(self, label: &str, min: i128, max: i128) -> RuleSet {
+        self.rule(label, Condition::InRange { min: Some(min), max: Some(max) })
+    }
+
+    pub fn predicate(self, label: &str, name: &str, test: impl Fn(i128) -> bool + Send + Sync + 'static) -> RuleSet {
+        self.rule(label, Condition::predicate(name, test))
+    }
+
+    pub fn separator(mut self, separator: &str) -> RuleSet {
+        self.separator = separator.to_string();
+        self
+    }
+
+    pub fn fallback(mut self, fallback: Fallback) -> RuleSet {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// 满足条件的规则的标签，FirstMatch 模式下最多一个
+    pub fn matches(&self, n: i128) -> Vec<&str> {
+        let mut labels = self.rules.iter().filter(|r| r.condition.matches(n)).map(|r| r.label.as_str());
+        match self.mode {
+            Mode::FirstMatch => labels.next().into_iter().collect(),
+            Mode::AllMatches => labels.collect(),
+        }
+    }
+
+    /// 最终的输出，Fallback::Nothing 且没有规则满足时为 None
+    pub fn classify(&self, n: i128) -> Option<String> {
+        let labels = self.matches(n);
+        if !labels.is_empty() {
+            return Some(labels.join(&self.separator));
+        }
+        match &self.fallback {
+            Fallback::Number => Some(n.to_string()),
+            Fallback::Label(label) => Some(label.clone()),
+            Fallback::Nothing => None,
+        }
+    }
+
+    /// 依次处理一串数，可以是范围，也可以是无限的迭代器，结果按需计算
+    pub fn stream<'a, I>(&'a self, values: I) -> impl Iterator<Item = (i128, String)> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: Into<i128>,
+    {
+        values.into_iter().filter_map(move |n| {
+            let n = n.into();
+            self.classify(n).map(|label| (n, label))
+        })
+    }
+
+    /// 统计每个标签出现的次数，按规则的顺序；用于分桶
+    pub fn histogram<I>(&self, values: I) -> Vec<(String, usize)>
+    where
+        I: IntoIterator,
+        I::Item: Into<i128>,
+    {
+        let mut counts: Vec<(String, usize)> = self.rules.iter().map(|r| (r.label.clone(), 0)).collect();
+        for n in values {
+            for label in self.matches(n.into()) {
+                if let Some(count) = counts.iter_mut().find(|(l, _)| l == label) {
+                    count.1 += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// 从 TOML 格式的文本读取规则，谓词只能使用内置的 even、odd、prime、square、positive、negative、zero
+    pub fn parse(spec: &str) -> Result<RuleSet, RuleError> {
+        RuleSet::parse_with(spec, &[])
+    }
+
+    /// 从 TOML 格式的文本读取规则，predicate 还可以使用传入的自定义谓词
+    pub fn parse_with(spec: &str, custom: &[(&str, Predicate)]) -> Result<RuleSet, RuleError> {
+        let mut set = RuleSet::new(Mode::FirstMatch);
+        // 正在读取的规则：标签和条件
+        let mut current: Option<(Option<String>, Vec<Condition>)> = None;
+        let mut count = 0;
+
+        for (i, raw) in spec.lines().enumerate() {
+            let line = i + 1;
+            let text = strip_comment(raw).trim();
+            if text.is_empty() {
+                continue;
+            }
+            if text == "[[rule]]" {
+                if let Some(rule) = current.take() {
+                    count += 1;
+                    set.rules.push(finish_rule(rule, count)?);
+                }
+                current = Some((None, Vec::new()));
+                continue;
+            }
+            let (key, value) = text.split_once('=').ok_or(RuleError::Syntax { line, text: text.to_string() })?;
+            let (key, value) = (key.trim(), parse_value(value.trim(), line)?);
+            let invalid = |expected| RuleError::InvalidValue { line, key: key.to_string(), expected };
+
+            let Some((label, conditions)) = current.as_mut() else {
+                match key {
+                    "mode" => {
+                        set.mode = match value.as_str() {
+                            Some("first") => Mode::FirstMatch,
+                            Some("all") => Mode::AllMatches,
+                            _ => return Err(invalid("\"first\" or \"all\"")),
+                        }
+                    }
+                    "separator" => set.separator = value.as_str().ok_or(invalid("a string"))?.to_string(),
+                    "fallback" => {
+                        set.fallback = match value.as_str() {
+                            Some("number") => Fallback::Number,
+                            Some("none") => Fallback::Nothing,
+                            Some(label) => Fallback::Label(label.to_string()),
+                            None => return Err(invalid("a string")),
+                        }
+                    }
+                    _ => return Err(RuleError::UnknownKey { line, key: key.to_string() }),
+                }
+                continue;
+            };
+
+            let ints = || value.as_ints().ok_or(invalid("an integer or an array of integers"));
+            let int = || match value {
+                Value::Int(n) => Ok(n),
+                _ => Err(invalid("an integer")),
+            };
+            match key {
+                "label" => *label = Some(value.as_str().ok_or(invalid("a string"))?.to_string()),
+                "divisible_by" => conditions.extend(ints()?.into_iter().map(Condition::DivisibleBy)),
+                "divisible_by_any" => conditions.push(Condition::Any(ints()?.into_iter().map(Condition::DivisibleBy).collect())),
+                "not_divisible_by" => conditions
+                    .extend(ints()?.into_iter().map(|d| Condition::Not(Box::new(Condition::DivisibleBy(d))))),
+                "min" => conditions.push(Condition::InRange { min: Some(int()?), max: None }),
+                "max" => conditions.push(Condition::InRange { min: None, max: Some(int()?) }),
+                "equals" => conditions.push(Condition::Any(
+                    ints()?.into_iter().map(|n| Condition::InRange { min: Some(n), max: Some(n) }).collect(),
+                )),
+                "predicate" => {
+                    let name = value.as_str().ok_or(invalid("a string"))?;
+                    let condition = custom
+                        .iter()
+                        .find(|(n, _)| *n == name)
+                        .map(|(n, test)| Condition::Predicate { name: n.to_string(), test: test.clone() })
+                        .or_else(|| builtin_predicate(name))
+                        .ok_or(RuleError::UnknownPredicate { line, name: name.to_string() })?;
+                    conditions.push(condition);
+                }
+                _ => return Err(RuleError::UnknownKey { line, key: key.to_string() }),
+            }
+        }
+        if let Some(rule) = current {
+            set.rules.push(finish_rule(rule, count + 1)?);
+        }
+        Ok(set)
+    }
+}
+
+fn finish_rule((label, conditions): (Option<String>, Vec<Condition>), index: usize) -> Result<Rule, RuleError> {
+    let label = label.ok_or(RuleError::IncompleteRule { index, missing: "label" })?;
+    if conditions.is_empty() {
+        return Err(RuleError::IncompleteRule { index, missing: "condition" });
+    }
+    let condition = if conditions.len() == 1 { conditions.into_iter().next().unwrap() } else { Condition::All(conditions) };
+    Ok(Rule { label, condition })
+}
+
+fn builtin_predicate(name: &str) -> Option<Condition> {
+    let test: fn(i128) -> bool = match name {
+        "even" => |n| n % 2 == 0,
+        "odd" => |n| n % 2 != 0,
+        "positive" => |n| n > 0,
+        "negative" => |n| n < 0,
+        "zero" => |n| n == 0,
+        "prime" => is_prime,
+        "square" => |n| n >= 0 && (n as u128).isqrt().pow(2) == n as u128,
+        _ => return None,
+    };
+    Some(Condition::predicate(name, test))
+}
+
+// Miller–Rabin 的底数：用前 13 个素数，对小于 3.3e24 的数（包括所有的 u64）结果是确定的。
+// 更大的数没有已知的确定性底数集合，这时结果是概率性的，但还没有找到能骗过这组底数的合数
+const PRIME_BASES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+// 试除到 d * d > n 对接近 i64::MAX 的素数要做 30 亿次除法，所以用 Miller–Rabin
+fn is_prime(n: i128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    let n = n as u128;
+    if let Some(p) = PRIME_BASES.iter().find(|p| n.is_multiple_of(**p)) {
+        return n == *p;
+    }
+    // n - 1 = d * 2^s，d 为奇数
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    PRIME_BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+// a * b % n，a、b 都小于 n。n 小于 2^127，所以 a + b 不会溢出
+fn mul_mod(a: u128, b: u128, n: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % n;
+    }
+    let (mut a, mut b, mut result) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = (result + a) % n;
+        }
+        a = (a + a) % n;
+        b >>= 1;
+    }
+    result
+}
+
+fn pow_mod(base: u128, mut exponent: u128, n: u128) -> u128 {
+    let (mut base, mut result) = (base % n, 1);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, n);
+        }
+        base = mul_mod(base, base, n);
+        exponent >>= 1;
+    }
+    result
+}
+
+// 去掉 # 之后的注释，字符串中的 # 保留，字符串中的 \" 不结束字符串
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i128),
+    Str(String),
+    Array(Vec<i128>),
+}
+
+impl Value {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_ints(&self) -> Option<Vec<i128>> {
+        match self {
+            Value::Int(n) => Some(vec![*n]),
+            Value::Array(ns) => Some(ns.clone()),
+            Value::Str(_) => None,
+        }
+    }
+}
+
+// 支持整数（可以有 _ 分隔）、双引号字符串和整数数组
+fn parse_value(text: &str, line: usize) -> Result<Value, RuleError> {
+    let syntax = || RuleError::Syntax { line, text: text.to_string() };
+    let int = |s: &str| s.trim().replace('_', "").parse::<i128>().map_err(|_| syntax());
+    if let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Ok(Value::Str(inner.replace("\\\"", "\"").replace("\\\\", "\\")));
+    }
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let items = inner.split(',').map(str::trim).filter(|s| !s.is_empty());
+        return Ok(Value::Array(items.map(int).collect::<Result<_, _>>()?));
+    }
+    int(text).map(Value::Int)
+}
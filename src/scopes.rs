@@ -0,0 +1,774 @@
+/*
+变量绑定与遮蔽（shadowing）分析
+
+let 可以用同一个名字再声明一个变量，后一个会遮蔽前一个：
+
+    let x = 5;
+    let y = {
+        let x = 3;  // 新的 scope 中的 x，只在这个 {} 中遮蔽外面的 x
+        x + 1
+    };
+    let x = x * 2;  // 同一个 scope 中的遮蔽，右边的 x 还是前一个 x
+
+分析器读取源码，列出每个函数中的每个绑定：在哪里声明、属于哪个 scope、遮蔽了哪个更早的绑定、
+最后一次在哪里被读取，以及从来没有被读取过的绑定（名字以 `_` 开头的除外，这是有意不使用的写法）。
+
+识别的绑定：函数参数、let、for 的模式、if let / while let / match 分支的模式、闭包参数、
+函数中的 const 与 static。只读取、不赋值的才算使用，`x = 1`、`x += 1` 只算赋值，和编译器的 unused 警告一致；
+格式化宏字符串中的 `{x}` 也算读取。
+
+这里只做词法分析和简单的语法识别，不做类型检查和宏展开：
+结构体的字段、方法和路径（`a.x`、`a::x`）不会被当作变量，但宏中的自定义语法可能被误认。
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::flow_graph::{build_trees, is_keyword, is_punct, text_of, tokenize, FlowError, Token, TokenKind, Tree};
+use crate::metrics::json_string;
+use crate::table::{Align, Table};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScopeError {
+    /// 括号没有闭合或不匹配，line 从 1 开始
+    UnbalancedDelimiter { line: usize },
+    /// 字符串、字符或块注释没有结束
+    UnterminatedLiteral { line: usize },
+    /// 源码中找不到这个函数
+    FunctionNotFound(String),
+}
+
+impl Display for ScopeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ScopeError::UnbalancedDelimiter { line } => write!(f, "line {}: unbalanced delimiter", line),
+            ScopeError::UnterminatedLiteral { line } => write!(f, "line {}: unterminated literal or comment", line),
+            ScopeError::FunctionNotFound(name) => write!(f, "function `{}` not found", name),
+        }
+    }
+}
+
+impl std::error::Error for ScopeError {}
+
+impl From<FlowError> for ScopeError {
+    // 词法分析只会产生这两种错误
+    fn from(e: FlowError) -> ScopeError {
+        match e {
+            FlowError::UnterminatedLiteral { line } => ScopeError::UnterminatedLiteral { line },
+            FlowError::UnbalancedDelimiter { line } => ScopeError::UnbalancedDelimiter { line },
+            other => unreachable!("not a lexical error: {}", other),
+        }
+    }
+}
+
+/// 源码中的位置，行和列都从 1 开始，列按字符计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// scope 的种类，也就是哪种语法打开了这个 scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Function,
+    Block,
+    If,
+    Else,
+    Loop,
+    Unsafe,
+    Arm,
+    Closure,
+}
+
+impl Display for ScopeKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ScopeKind::Function => "fn",
+            ScopeKind::Block => "block",
+            ScopeKind::If => "if",
+            ScopeKind::Else => "else",
+            ScopeKind::Loop => "loop",
+            ScopeKind::Unsafe => "unsafe",
+            ScopeKind::Arm => "arm",
+            ScopeKind::Closure => "closure",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    pub id: usize,
+    pub kind: ScopeKind,
+    /// 外层的 scope，函数的 scope 没有外层
+    pub parent: Option<usize>,
+    pub function: String,
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Display for Scope {
+    /// 比如 `block@16`，16 是 scope 开始的行
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}@{}", self.kind, self.start.line)
+    }
+}
+
+/// 绑定是怎样引入的
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    Parameter,
+    Let,
+    For,
+    /// if let、while let 与 match 分支中的模式
+    Pattern,
+    Closure,
+    Const,
+    Static,
+}
+
+impl Display for BindingKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            BindingKind::Parameter => "param",
+            BindingKind::Let => "let",
+            BindingKind::For => "for",
+            BindingKind::Pattern => "pattern",
+            BindingKind::Closure => "closure",
+            BindingKind::Const => "const",
+            BindingKind::Static => "static",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub id: usize,
+    pub name: String,
+    pub kind: BindingKind,
+    pub mutable: bool,
+    pub declared: Position,
+    pub scope: usize,
+    pub function: String,
+    /// 声明时同名的、仍然可见的绑定
+    pub shadows: Option<usize>,
+    /// 每次读取的位置
+    pub uses: Vec<Position>,
+    /// 每次赋值的位置，不包括声明
+    pub assignments: Vec<Position>,
+}
+
+impl Binding {
+    pub fn last_use(&self) -> Option<Position> {
+        self.uses.iter().copied().max()
+    }
+
+    /// 从来没有被读取过，名字以 `_` 开头的不算
+    pub fn is_unused(&self) -> bool {
+        self.uses.is_empty() && !self.name.starts_with('_')
+    }
+}
+
+/// 分析的结果
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Analysis {
+    pub scopes: Vec<Scope>,
+    pub bindings: Vec<Binding>,
+}
+
+impl Analysis {
+    /// 分析源码中的所有函数，包括 impl 中的方法和函数中嵌套的函数
+    pub fn from_source(src: &str) -> Result<Analysis, ScopeError> {
+        let trees = build_trees(src, &tokenize(src)?)?;
+        let mut analyzer = Analyzer::new(src);
+        analyzer.items(&trees);
+        Ok(Analysis { scopes: analyzer.scopes, bindings: analyzer.bindings })
+    }
+
+    /// 只保留名字为 name 的函数
+    pub fn from_function(src: &str, name: &str) -> Result<Analysis, ScopeError> {
+        let all = Analysis::from_source(src)?;
+        if !all.scopes.iter().any(|s| s.function == name) {
+            return Err(ScopeError::FunctionNotFound(name.to_string()));
+        }
+        Ok(Analysis {
+            scopes: all.scopes.into_iter().filter(|s| s.function == name).collect(),
+            bindings: all.bindings.into_iter().filter(|b| b.function == name).collect(),
+        })
+    }
+
+    /// 分析过的函数，按出现的顺序
+    pub fn functions(&self) -> Vec<&str> {
+        let scopes = self.scopes.iter().filter(|s| s.kind == ScopeKind::Function);
+        scopes.map(|s| s.function.as_str()).collect()
+    }
+
+    pub fn scope(&self, id: usize) -> Option<&Scope> {
+        self.scopes.iter().find(|s| s.id == id)
+    }
+
+    pub fn binding(&self, id: usize) -> Option<&Binding> {
+        self.bindings.iter().find(|b| b.id == id)
+    }
+
+    /// 名字为 name 的所有绑定，按声明的顺序
+    pub fn named(&self, name: &str) -> Vec<&Binding> {
+        self.bindings.iter().filter(|b| b.name == name).collect()
+    }
+
+    pub fn unused(&self) -> Vec<&Binding> {
+        self.bindings.iter().filter(|b| b.is_unused()).collect()
+    }
+
+    /// (遮蔽别人的绑定, 被遮蔽的绑定)
+    pub fn shadowing(&self) -> Vec<(&Binding, &Binding)> {
+        let pairs = self.bindings.iter().filter_map(|b| Some((b, self.binding(b.shadows?)?)));
+        pairs.collect()
+    }
+
+    // 被遮蔽的绑定在同一个 scope 还是外层的 scope
+    fn describe_shadow(&self, binding: &Binding) -> String {
+        match binding.shadows.and_then(|id| self.binding(id)) {
+            None => "-".to_string(),
+            Some(shadowed) if shadowed.scope == binding.scope => format!("{}@{} (same scope)", shadowed.name, shadowed.declared),
+            Some(shadowed) => format!("{}@{} (outer)", shadowed.name, shadowed.declared),
+        }
+    }
+
+    /// 每个函数一张表，最后是汇总
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for function in self.scopes.iter().filter(|s| s.kind == ScopeKind::Function) {
+            out.push_str(&format!("fn {} (line {})\n", function.function, function.start.line));
+            // let 右边的代码块中的绑定先被声明，按源码中的位置排列
+            let mut bindings: Vec<&Binding> =
+                self.bindings.iter().filter(|b| self.function_scope(b.scope) == Some(function.id)).collect();
+            bindings.sort_by_key(|b| b.declared);
+            let mut table = Table::new(["name", "kind", "declared", "scope", "shadows", "uses", "last use", "note"])
+                .align(5, Align::Right);
+            for b in bindings {
+                let scope = self.scope(b.scope).map_or("?".to_string(), Scope::to_string);
+                let last = b.last_use().map_or("-".to_string(), |p| p.to_string());
+                let note = match (b.is_unused(), b.assignments.is_empty()) {
+                    (true, true) => "unused",
+                    (true, false) => "assigned but never read",
+                    _ => "",
+                };
+                let name = if b.mutable { format!("mut {}", b.name) } else { b.name.clone() };
+                let (kind, declared, uses) = (b.kind.to_string(), b.declared.to_string(), b.uses.len().to_string());
+                table.row([name, kind, declared, scope, self.describe_shadow(b), uses, last, note.to_string()]);
+            }
+            if table.is_empty() {
+                out.push_str("(no bindings)\n");
+            } else {
+                out.push_str(&table.to_string());
+            }
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "{} functions, {} bindings, {} shadowed, {} unused\n",
+            self.functions().len(),
+            self.bindings.len(),
+            self.shadowing().len(),
+            self.unused().len()
+        ));
+        out
+    }
+
+    // 向外找到 scope 所在函数的 scope
+    fn function_scope(&self, mut id: usize) -> Option<usize> {
+        loop {
+            let scope = self.scope(id)?;
+            match scope.parent {
+                None => return Some(scope.id),
+                Some(parent) => id = parent,
+            }
+        }
+    }
+
+    /// `{"scopes": [...], "bindings": [...]}`，位置是 `[行, 列]`，没有的值为 null
+    pub fn to_json(&self) -> String {
+        let position = |p: Position| format!("[{}, {}]", p.line, p.column);
+        let optional = |id: Option<usize>| id.map_or("null".to_string(), |id| id.to_string());
+        let scopes: Vec<String> = self
+            .scopes
+            .iter()
+            .map(|s| {
+                format!(
+                    "    {{\"id\": {}, \"kind\": \"{}\", \"parent\": {}, \"function\": {}, \"start\": {}, \"end\": {}}}",
+                    s.id,
+                    s.kind,
+                    optional(s.parent),
+                    json_string(&s.function),
+                    position(s.start),
+                    position(s.end)
+                )
+            })
+            .collect();
+        let bindings: Vec<String> = self
+            .bindings
+            .iter()
+            .map(|b| {
+                let uses: Vec<String> = b.uses.iter().map(|&p| position(p)).collect();
+                let assignments: Vec<String> = b.assignments.iter().map(|&p| position(p)).collect();
+                format!(
+                    "    {{\"id\": {}, \"name\": {}, \"kind\": \"{}\", \"mutable\": {}, \"function\": {}, \"scope\": {}, \
+                     \"declared\": {}, \"shadows\": {}, \"uses\": [{}], \"assignments\": [{}], \"last_use\": {}, \"unused\": {}}}",
+                    b.id,
+                    json_string(&b.name),
+                    b.kind,
+                    b.mutable,
+                    json_string(&b.function),
+                    b.scope,
+                    position(b.declared),
+                    optional(b.shadows),
+                    uses.join(", "),
+                    assignments.join(", "),
+                    b.last_use().map_or("null".to_string(), position),
+                    b.is_unused()
+                )
+            })
+            .collect();
+        format!("{{\n  \"scopes\": [\n{}\n  ],\n  \"bindings\": [\n{}\n  ]\n}}", scopes.join(",\n"), bindings.join(",\n"))
+    }
+}
+
+// ---- 分析 ----
+
+const ASSIGNMENTS: [&str; 11] = ["=", "+=", "-=", "*=", "/=", "%=", "^=", "&=", "|=", "<<=", ">>="];
+
+struct Analyzer<'s> {
+    src: &'s str,
+    // 每一行开头的字节位置
+    line_starts: Vec<usize>,
+    scopes: Vec<Scope>,
+    bindings: Vec<Binding>,
+    // 当前可见的绑定，每层是 (scope 的编号, [(名字, 绑定的编号)])
+    visible: Vec<(usize, Vec<(&'s str, usize)>)>,
+    function: String,
+}
+
+impl<'s> Analyzer<'s> {
+    fn new(src: &'s str) -> Analyzer<'s> {
+        let line_starts = [0].into_iter().chain(src.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        Analyzer { src, line_starts, scopes: Vec::new(), bindings: Vec::new(), visible: Vec::new(), function: String::new() }
+    }
+
+    fn position(&self, token: Token) -> Position {
+        let start = self.line_starts[token.line - 1];
+        Position { line: token.line, column: self.src[start..token.start].chars().count() + 1 }
+    }
+
+    fn open_scope(&mut self, kind: ScopeKind, start: Token) {
+        let id = self.scopes.len();
+        let parent = self.visible.last().map(|(scope, _)| *scope);
+        let start = self.position(start);
+        self.scopes.push(Scope { id, kind, parent, function: self.function.clone(), start, end: start });
+        self.visible.push((id, Vec::new()));
+    }
+
+    fn close_scope(&mut self, end: Token) {
+        if let Some((id, _)) = self.visible.pop() {
+            self.scopes[id].end = self.position(end);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        let mut names = self.visible.iter().rev().flat_map(|(_, names)| names.iter().rev());
+        names.find(|(n, _)| *n == name).map(|(_, id)| *id)
+    }
+
+    fn declare(&mut self, token: Token, kind: BindingKind, mutable: bool) {
+        let name = text_of(self.src, &token);
+        let id = self.bindings.len();
+        let Some(scope) = self.visible.last().map(|(scope, _)| *scope) else { return };
+        self.bindings.push(Binding {
+            id,
+            name: name.to_string(),
+            kind,
+            mutable,
+            declared: self.position(token),
+            scope,
+            function: self.function.clone(),
+            shadows: self.lookup(name),
+            uses: Vec::new(),
+            assignments: Vec::new(),
+        });
+        if let Some((_, names)) = self.visible.last_mut() {
+            names.push((name, id));
+        }
+    }
+
+    fn declare_pattern(&mut self, pattern: &[Tree], kind: BindingKind) {
+        let mut found = Vec::new();
+        pattern_bindings(self.src, pattern, &mut found);
+        for (token, mutable) in found {
+            self.declare(token, kind, mutable);
+        }
+    }
+
+    // 函数之外只需要找到所有的函数
+    fn items(&mut self, trees: &[Tree]) {
+        let mut i = 0;
+        while i < trees.len() {
+            if is_keyword(self.src, &trees[i], "fn") {
+                if let Some(end) = self.function(trees, i) {
+                    i = end + 1;
+                    continue;
+                }
+            }
+            if let Tree::Group { children, .. } = &trees[i] {
+                self.items(children);
+            }
+            i += 1;
+        }
+    }
+
+    // trees[i] 是 fn，返回函数最后一个词法单元的位置；不是函数定义时返回 None
+    fn function(&mut self, trees: &[Tree], i: usize) -> Option<usize> {
+        let name = match trees.get(i + 1)? {
+            Tree::Leaf(token) if token.kind == TokenKind::Ident => text_of(self.src, token),
+            _ => return None,
+        };
+        let end = find(&trees[i + 2..], |t| t.brace().is_some() || is_punct(self.src, t, ";"))? + i + 2;
+        let Tree::Group { children, close, .. } = &trees[end] else {
+            // trait 中没有函数体的声明
+            return Some(end);
+        };
+        let params = trees[i + 2..end].iter().find_map(|t| match t {
+            Tree::Group { delimiter: '(', children, .. } => Some(children),
+            _ => None,
+        });
+
+        // 嵌套的函数不能使用外层函数的变量
+        let outer = (std::mem::replace(&mut self.function, name.to_string()), std::mem::take(&mut self.visible));
+        self.open_scope(ScopeKind::Function, trees[i].first());
+        for param in params.map(|p| split(self.src, p, ",")).unwrap_or_default() {
+            if !param.iter().any(|t| is_keyword(self.src, t, "self")) {
+                let pattern = &param[..find(param, |t| is_punct(self.src, t, ":")).unwrap_or(param.len())];
+                self.declare_pattern(pattern, BindingKind::Parameter);
+            }
+        }
+        self.walk(children, false);
+        self.close_scope(*close);
+        (self.function, self.visible) = outer;
+        Some(end)
+    }
+
+    // 一段语句或表达式；in_macro 表示在宏的参数中，其中的格式化字符串可能引用变量
+    fn walk(&mut self, trees: &[Tree], in_macro: bool) {
+        let src = self.src;
+        // 下一个 {} 是由哪种语法打开的
+        let mut pending = ScopeKind::Block;
+        let mut i = 0;
+        while i < trees.len() {
+            let next = trees.get(i + 1);
+            match &trees[i] {
+                Tree::Leaf(token) if token.kind == TokenKind::Ident => match text_of(src, token) {
+                    "let" => {
+                        i = self.let_statement(trees, i);
+                        pending = ScopeKind::Block;
+                        continue;
+                    }
+                    "fn" => {
+                        if let Some(end) = self.function(trees, i) {
+                            i = end + 1;
+                            continue;
+                        }
+                    }
+                    "const" | "static" if next.is_some_and(|t| t.brace().is_none() && !is_keyword(src, t, "fn")) => {
+                        i = self.item(trees, i);
+                        continue;
+                    }
+                    "for" => {
+                        if let Some(end) = self.for_loop(trees, i) {
+                            i = end;
+                            continue;
+                        }
+                    }
+                    "if" | "while" if next.is_some_and(|t| is_keyword(src, t, "let")) => {
+                        i = self.let_condition(trees, i);
+                        pending = ScopeKind::Block;
+                        continue;
+                    }
+                    "match" => {
+                        i = self.match_expression(trees, i);
+                        continue;
+                    }
+                    "if" => pending = ScopeKind::If,
+                    "else" => pending = ScopeKind::Else,
+                    "loop" | "while" => pending = ScopeKind::Loop,
+                    "unsafe" => pending = ScopeKind::Unsafe,
+                    _ => self.identifier(trees, i),
+                },
+                Tree::Leaf(token) if token.kind == TokenKind::Punct => match text_of(src, token) {
+                    "|" | "||" if is_closure_start(src, trees, i) => {
+                        i = self.closure(trees, i, in_macro);
+                        continue;
+                    }
+                    ";" => pending = ScopeKind::Block,
+                    _ => {}
+                },
+                Tree::Leaf(token) if token.kind == TokenKind::Literal && in_macro => self.format_string(*token),
+                Tree::Leaf(_) => {}
+                Tree::Group { delimiter: '{', open, close, children } => {
+                    // `Point { x, y }` 是结构体的字面值，不是代码块
+                    let is_struct = i > 0
+                        && pending == ScopeKind::Block
+                        && matches!(&trees[i - 1], Tree::Leaf(t) if t.kind == TokenKind::Ident
+                            && text_of(src, t).starts_with(|c: char| c.is_uppercase()));
+                    if is_struct {
+                        self.walk(children, in_macro);
+                    } else {
+                        self.open_scope(pending, *open);
+                        self.walk(children, false);
+                        self.close_scope(*close);
+                    }
+                    pending = ScopeKind::Block;
+                }
+                Tree::Group { children, .. } => {
+                    let is_macro = i > 0 && is_punct(src, &trees[i - 1], "!");
+                    self.walk(children, is_macro || in_macro);
+                }
+            }
+            i += 1;
+        }
+    }
+
+    // 读取或者赋值
+    fn identifier(&mut self, trees: &[Tree], i: usize) {
+        let Tree::Leaf(token) = &trees[i] else { return };
+        let src = self.src;
+        let prev = i.checked_sub(1).map(|p| &trees[p]);
+        let next = trees.get(i + 1);
+        // a.x、a::x、x::y、x!()、字段名 x: 1 都不是变量
+        let is_path = prev.is_some_and(|t| is_punct(src, t, ".") || is_punct(src, t, "::"))
+            || next.is_some_and(|t| is_punct(src, t, "::") || is_punct(src, t, "!") || is_punct(src, t, ":"));
+        if is_path {
+            return;
+        }
+        let Some(id) = self.lookup(text_of(src, token)) else { return };
+        let position = self.position(*token);
+        let is_deref = prev.is_some_and(|t| is_punct(src, t, "*"));
+        if !is_deref && next.is_some_and(|t| ASSIGNMENTS.iter().any(|op| is_punct(src, t, op))) {
+            self.bindings[id].assignments.push(position);
+        } else {
+            self.bindings[id].uses.push(position);
+        }
+    }
+
+    // 格式化字符串中的 {x}、{x:?}
+    fn format_string(&mut self, token: Token) {
+        let text = text_of(self.src, &token);
+        if !(text.starts_with('"') || text.starts_with('r')) {
+            return;
+        }
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            rest = &rest[open + 1..];
+            if let Some(after) = rest.strip_prefix('{') {
+                rest = after;
+                continue;
+            }
+            let end = rest.find(['}', ':']).unwrap_or(rest.len());
+            let name = &rest[..end];
+            let is_name = name.starts_with(|c: char| c == '_' || c.is_alphabetic())
+                && name.chars().all(|c| c == '_' || c.is_alphanumeric());
+            if let Some(id) = is_name.then(|| self.lookup(name)).flatten() {
+                let position = self.position(token);
+                self.bindings[id].uses.push(position);
+            }
+        }
+    }
+
+    // let 模式: 类型 = 表达式 else { .. };
+    // 右边的表达式中，同名的变量还是前一个绑定，所以先分析右边再声明
+    fn let_statement(&mut self, trees: &[Tree], i: usize) -> usize {
+        let src = self.src;
+        let end = find(&trees[i..], |t| is_punct(src, t, ";")).map_or(trees.len(), |e| e + i);
+        let eq = find(&trees[i..end], |t| is_punct(src, t, "=")).map(|e| e + i);
+        let pattern_end = eq.unwrap_or(end);
+        let colon = find(&trees[i..pattern_end], |t| is_punct(src, t, ":")).map(|c| c + i);
+        if let Some(eq) = eq {
+            self.walk(&trees[eq + 1..end], false);
+        }
+        self.declare_pattern(&trees[i + 1..colon.unwrap_or(pattern_end)], BindingKind::Let);
+        end + 1
+    }
+
+    // 函数中的 const NAME: 类型 = 值; 与 static mut NAME: 类型 = 值;
+    fn item(&mut self, trees: &[Tree], i: usize) -> usize {
+        let src = self.src;
+        let kind = if is_keyword(src, &trees[i], "const") { BindingKind::Const } else { BindingKind::Static };
+        let mutable = trees.get(i + 1).is_some_and(|t| is_keyword(src, t, "mut"));
+        let end = find(&trees[i..], |t| is_punct(src, t, ";")).map_or(trees.len(), |e| e + i);
+        if let Some(eq) = find(&trees[i..end], |t| is_punct(src, t, "=")) {
+            self.walk(&trees[i + eq + 1..end], false);
+        }
+        if let Some(Tree::Leaf(name)) = trees.get(i + 1 + mutable as usize) {
+            if name.kind == TokenKind::Ident {
+                self.declare(*name, kind, mutable);
+            }
+        }
+        end + 1
+    }
+
+    // for 模式 in 表达式 { .. }，返回循环之后的位置；`impl X for Y` 这样的不是循环，返回 None
+    fn for_loop(&mut self, trees: &[Tree], i: usize) -> Option<usize> {
+        let src = self.src;
+        let body = find(&trees[i..], |t| t.brace().is_some())? + i;
+        let keyword_in = find(&trees[i..body], |t| is_keyword(src, t, "in"))? + i;
+        self.walk(&trees[keyword_in + 1..body], false);
+        self.block(&trees[body], ScopeKind::Loop, &trees[i + 1..keyword_in], BindingKind::For);
+        Some(body + 1)
+    }
+
+    // if let / while let 模式 = 表达式 { .. }
+    fn let_condition(&mut self, trees: &[Tree], i: usize) -> usize {
+        let src = self.src;
+        let kind = if is_keyword(src, &trees[i], "if") { ScopeKind::If } else { ScopeKind::Loop };
+        let body = find(&trees[i..], |t| t.brace().is_some()).map_or(trees.len(), |b| b + i);
+        let Some(eq) = find(&trees[i..body], |t| is_punct(src, t, "=")).map(|e| e + i) else { return i + 1 };
+        self.walk(&trees[eq + 1..body], false);
+        if let Some(tree) = trees.get(body) {
+            self.block(tree, kind, &trees[i + 2..eq], BindingKind::Pattern);
+        }
+        body + 1
+    }
+
+    // 打开一个 scope，先声明 pattern 中的绑定，再分析 {} 中的代码
+    fn block(&mut self, tree: &Tree, kind: ScopeKind, pattern: &[Tree], binding: BindingKind) {
+        let Tree::Group { open, close, children, .. } = tree else { return };
+        self.open_scope(kind, *open);
+        self.declare_pattern(pattern, binding);
+        self.walk(children, false);
+        self.close_scope(*close);
+    }
+
+    // match 表达式 { 模式 if 条件 => 分支, .. }，每个分支是一个 scope
+    fn match_expression(&mut self, trees: &[Tree], i: usize) -> usize {
+        let src = self.src;
+        let Some(body) = find(&trees[i..], |t| t.brace().is_some()).map(|b| b + i) else { return i + 1 };
+        self.walk(&trees[i + 1..body], false);
+        let arms = trees[body].brace().unwrap_or_default();
+        let mut j = 0;
+        while let Some(arrow) = find(&arms[j..], |t| is_punct(src, t, "=>")).map(|a| a + j) {
+            let guard = find(&arms[j..arrow], |t| is_keyword(src, t, "if")).map(|g| g + j);
+            let end = match arms.get(arrow + 1) {
+                Some(tree) if tree.brace().is_some() => arrow + 2,
+                _ => find(&arms[arrow + 1..], |t| is_punct(src, t, ",")).map_or(arms.len(), |c| c + arrow + 1),
+            };
+            self.open_scope(ScopeKind::Arm, arms[j].first());
+            self.declare_pattern(&arms[j..guard.unwrap_or(arrow)], BindingKind::Pattern);
+            if let Some(guard) = guard {
+                self.walk(&arms[guard + 1..arrow], false);
+            }
+            match arms.get(arrow + 1).and_then(Tree::brace) {
+                Some(children) => self.walk(children, false),
+                None => self.walk(&arms[arrow + 1..end], false),
+            }
+            self.close_scope(last_token(&arms[end - 1]));
+            j = end + arms.get(end).is_some_and(|t| is_punct(src, t, ",")) as usize;
+        }
+        body + 1
+    }
+
+    // |参数| 表达式、|参数| -> 类型 { .. }，返回闭包之后的位置
+    fn closure(&mut self, trees: &[Tree], i: usize, in_macro: bool) -> usize {
+        let src = self.src;
+        let (params, mut start) = if is_punct(src, &trees[i], "||") {
+            (&trees[i..i], i + 1)
+        } else {
+            let close = find(&trees[i + 1..], |t| is_punct(src, t, "|")).map_or(trees.len(), |c| c + i + 1);
+            (&trees[i + 1..close], close + 1)
+        };
+        if trees.get(start).is_some_and(|t| is_punct(src, t, "->")) {
+            start = find(&trees[start..], |t| t.brace().is_some()).map_or(trees.len(), |b| b + start);
+        }
+        let end = match trees.get(start) {
+            Some(tree) if tree.brace().is_some() => start + 1,
+            _ => find(&trees[start.min(trees.len())..], |t| is_punct(src, t, ",") || is_punct(src, t, ";"))
+                .map_or(trees.len(), |e| e + start),
+        };
+
+        self.open_scope(ScopeKind::Closure, trees[i].first());
+        for param in split(src, params, ",") {
+            let pattern = &param[..find(param, |t| is_punct(src, t, ":")).unwrap_or(param.len())];
+            self.declare_pattern(pattern, BindingKind::Closure);
+        }
+        match trees.get(start).and_then(Tree::brace) {
+            Some(children) => self.walk(children, false),
+            None if start < end => self.walk(&trees[start..end], in_macro),
+            None => {}
+        }
+        self.close_scope(last_token(&trees[end.max(i + 1) - 1]));
+        end
+    }
+}
+
+fn find(trees: &[Tree], predicate: impl Fn(&Tree) -> bool) -> Option<usize> {
+    trees.iter().position(predicate)
+}
+
+// 按分隔符拆开，不进入括号内部
+fn split<'t>(src: &str, trees: &'t [Tree], separator: &str) -> Vec<&'t [Tree]> {
+    let parts = trees.split(|t| is_punct(src, t, separator));
+    parts.filter(|part| !part.is_empty()).collect()
+}
+
+fn last_token(tree: &Tree) -> Token {
+    match tree {
+        Tree::Leaf(token) => *token,
+        Tree::Group { close, .. } => *close,
+    }
+}
+
+// `|` 出现在表达式开头时是闭包，出现在两个值之间时是按位或
+fn is_closure_start(src: &str, trees: &[Tree], i: usize) -> bool {
+    let Some(prev) = i.checked_sub(1).map(|p| &trees[p]) else { return true };
+    ["move", "return"].iter().any(|k| is_keyword(src, prev, k))
+        || ["=", ",", "=>", ";"].iter().chain(&ASSIGNMENTS[1..]).any(|p| is_punct(src, prev, p))
+}
+
+// 模式中引入的变量：小写开头、不是路径或字段名的标识符，ref、mut 修饰后面的变量
+fn pattern_bindings(src: &str, trees: &[Tree], found: &mut Vec<(Token, bool)>) {
+    let mut mutable = false;
+    for (i, tree) in trees.iter().enumerate() {
+        match tree {
+            Tree::Leaf(token) if token.kind == TokenKind::Ident => {
+                let text = text_of(src, token);
+                match text {
+                    "mut" => {
+                        mutable = true;
+                        continue;
+                    }
+                    "ref" | "box" | "_" | "true" | "false" => continue,
+                    _ => {}
+                }
+                let prev = i.checked_sub(1).map(|p| &trees[p]);
+                let next = trees.get(i + 1);
+                // Some(x)、Point { x, .. } 中的 Some 与 Point，std::f64::consts::PI 这样的路径
+                let is_path = prev.is_some_and(|t| is_punct(src, t, "::"))
+                    || next.is_some_and(|t| {
+                        is_punct(src, t, "::") || matches!(t, Tree::Group { delimiter: '(' | '{', .. })
+                    });
+                // Point { x: px } 中的 x 是字段名
+                let is_field = next.is_some_and(|t| is_punct(src, t, ":"));
+                let is_constant = text.starts_with(|c: char| c.is_uppercase());
+                // a | b 两边的模式绑定同样的名字，只记一次
+                let seen = found.iter().any(|(t, _)| text_of(src, t) == text);
+                if !(is_path || is_field || is_constant || seen) {
+                    found.push((*token, mutable));
+                }
+                mutable = false;
+            }
+            Tree::Group { children, .. } => pattern_bindings(src, children, found),
+            Tree::Leaf(_) => {}
+        }
+    }
+}
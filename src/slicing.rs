@@ -0,0 +1,352 @@
+/*
+Python 风格的切片
+
+Rust 的切片只接受 `start..end`，下标越界时 panic：
+
+    let pick = &xs[1..4];
+    // println!("{}", arr[100]);  // panic
+
+Python 的切片 `xs[start:stop:step]` 更宽松：
+- 负数下标从末尾开始计算，-1 是最后一个元素
+- 越界的 start、stop 会被截断到合法范围，不会报错
+- step 可以大于 1 用来跳着取，也可以是负数用来倒着取，但不能是 0
+
+这里用 `Slice` 表示 `start:stop:step`，`SliceExt` 为切片和数组提供这套规则，
+`StrSliceExt` 为字符串提供：与 Python 一样按字符而不是按字节计算下标，
+按字节取子串时要求边界落在字符边界上，否则返回错误而不是 panic。
+ */
+
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::iter::FusedIterator;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SliceError {
+    /// step 为 0
+    ZeroStep,
+    /// 按字节取子串时，下标不在字符边界上
+    NotCharBoundary(usize),
+    /// 无法解析 `start:stop:step`
+    Invalid(String),
+}
+
+impl Display for SliceError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SliceError::ZeroStep => write!(f, "slice step cannot be zero"),
+            SliceError::NotCharBoundary(index) => write!(f, "byte index {} is not a char boundary", index),
+            SliceError::Invalid(s) => write!(f, "invalid slice `{}`, expected start:stop[:step]", s),
+        }
+    }
+}
+
+impl std::error::Error for SliceError {}
+
+/// `start:stop:step`，省略的部分为 None，与 Python 的 slice 对象相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Slice {
+    pub start: Option<isize>,
+    pub stop: Option<isize>,
+    pub step: Option<isize>,
+}
+
+impl Slice {
+    pub fn new(start: Option<isize>, stop: Option<isize>, step: Option<isize>) -> Slice {
+        Slice { start, stop, step }
+    }
+
+    /// `start:stop`
+    pub fn range(start: isize, stop: isize) -> Slice {
+        Slice::new(Some(start), Some(stop), None)
+    }
+
+    /// `start:`
+    pub fn starting(start: isize) -> Slice {
+        Slice::new(Some(start), None, None)
+    }
+
+    /// `:stop`
+    pub fn until(stop: isize) -> Slice {
+        Slice::new(None, Some(stop), None)
+    }
+
+    /// `::-1`，倒序
+    pub fn reversed() -> Slice {
+        Slice::new(None, None, Some(-1))
+    }
+
+    /// 设置 step：`Slice::range(1, -1).with_step(2)` 就是 `1:-1:2`
+    pub fn with_step(self, step: isize) -> Slice {
+        Slice { step: Some(step), ..self }
+    }
+
+    /// 对长度为 len 的序列计算实际取到的下标，规则与 Python 的 `slice.indices` 相同
+    pub fn indices(&self, len: usize) -> Result<Indices, SliceError> {
+        let step = self.step.unwrap_or(1);
+        if step == 0 {
+            return Err(SliceError::ZeroStep);
+        }
+        let len = len as isize;
+        // 负数加上长度，仍然越界的截断到 [lower, upper]
+        let adjust = |index: Option<isize>, default: isize, lower: isize, upper: isize| match index {
+            None => default,
+            Some(i) if i < 0 => (i + len).max(lower),
+            Some(i) => i.min(upper),
+        };
+
+        let (start, count) = if step > 0 {
+            let start = adjust(self.start, 0, 0, len);
+            let stop = adjust(self.stop, len, 0, len);
+            let count = if stop > start { (stop - start - 1) as usize / step as usize + 1 } else { 0 };
+            (start, count)
+        } else {
+            // 倒着取时 -1 表示第一个元素之前
+            let start = adjust(self.start, len - 1, -1, len - 1);
+            let stop = adjust(self.stop, -1, -1, len - 1);
+            let count = if start > stop { (start - stop - 1) as usize / step.unsigned_abs() + 1 } else { 0 };
+            (start, count)
+        };
+        Ok(Indices { start, step, front: 0, back: count })
+    }
+}
+
+impl Display for Slice {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let part = |x: Option<isize>| x.map(|x| x.to_string()).unwrap_or_default();
+        write!(f, "{}:{}", part(self.start), part(self.stop))?;
+        if let Some(step) = self.step {
+            write!(f, ":{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Slice {
+    type Err = SliceError;
+
+    /// 解析 `1:4`、`-3:`、`::-1`、`1:-1:2` 这样的写法，至少要有一个冒号
+    fn from_str(s: &str) -> Result<Slice, SliceError> {
+        let invalid = || SliceError::Invalid(s.to_string());
+        let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+        if !(2..=3).contains(&parts.len()) {
+            return Err(invalid());
+        }
+        let parse = |part: &str| -> Result<Option<isize>, SliceError> {
+            if part.is_empty() {
+                Ok(None)
+            } else {
+                part.parse().map(Some).map_err(|_| invalid())
+            }
+        };
+        Ok(Slice {
+            start: parse(parts[0])?,
+            stop: parse(parts[1])?,
+            step: match parts.get(2) {
+                Some(part) => parse(part)?,
+                None => None,
+            },
+        })
+    }
+}
+
+/// 切片取到的下标，按顺序产生
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Indices {
+    start: isize,
+    step: isize,
+    // 还没有产生的是第 front 到第 back - 1 个
+    front: usize,
+    back: usize,
+}
+
+impl Indices {
+    /// step 为 1 时下标是连续的，返回对应的范围
+    pub fn as_range(&self) -> Option<std::ops::Range<usize>> {
+        if self.step != 1 {
+            return None;
+        }
+        let start = self.start as usize + self.front;
+        Some(start..start + self.len())
+    }
+
+    fn nth_index(&self, n: usize) -> usize {
+        (self.start + n as isize * self.step) as usize
+    }
+}
+
+impl Iterator for Indices {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.nth_index(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Indices {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.nth_index(self.back))
+    }
+}
+
+impl ExactSizeIterator for Indices {}
+
+impl FusedIterator for Indices {}
+
+/// 切片取到的元素，step 不为 1 时元素不连续，所以是一个迭代器
+#[derive(Debug, Clone)]
+pub struct Strided<'a, T> {
+    items: &'a [T],
+    indices: Indices,
+}
+
+impl<'a, T> Strided<'a, T> {
+    /// step 为 1 时可以直接借用为切片
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        self.indices.as_range().map(|range| &self.items[range])
+    }
+
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.clone().cloned().collect()
+    }
+}
+
+impl<'a, T> Iterator for Strided<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.indices.next().map(|i| &self.items[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Strided<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.indices.next_back().map(|i| &self.items[i])
+    }
+}
+
+impl<T> ExactSizeIterator for Strided<'_, T> {}
+
+impl<T> FusedIterator for Strided<'_, T> {}
+
+// 负数下标加上长度，越界时返回 None
+fn normalize_index(index: isize, len: usize) -> Option<usize> {
+    let index = if index < 0 { index.checked_add_unsigned(len)? } else { index };
+    usize::try_from(index).ok().filter(|&i| i < len)
+}
+
+/// 为切片和数组提供 Python 风格的下标和切片
+pub trait SliceExt<T> {
+    /// 支持负数下标，越界时返回 None
+    fn py_get(&self, index: isize) -> Option<&T>;
+
+    /// 支持负数下标，越界时与 `xs[i]` 一样 panic
+    fn py_index(&self, index: isize) -> &T;
+
+    /// 按 `start:stop:step` 取元素，step 为 0 时返回错误
+    fn py_try_slice(&self, slice: Slice) -> Result<Strided<'_, T>, SliceError>;
+
+    /// 按 `start:stop:step` 取元素，越界的部分会被截断，step 为 0 时 panic
+    fn py_slice(&self, slice: Slice) -> Strided<'_, T> {
+        self.py_try_slice(slice).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<T> SliceExt<T> for [T] {
+    fn py_get(&self, index: isize) -> Option<&T> {
+        normalize_index(index, self.len()).map(|i| &self[i])
+    }
+
+    fn py_index(&self, index: isize) -> &T {
+        match self.py_get(index) {
+            Some(item) => item,
+            None => panic!("index out of bounds: the len is {} but the index is {}", self.len(), index),
+        }
+    }
+
+    fn py_try_slice(&self, slice: Slice) -> Result<Strided<'_, T>, SliceError> {
+        Ok(Strided { items: self, indices: slice.indices(self.len())? })
+    }
+}
+
+/// 为字符串提供 Python 风格的下标和切片，下标按字符计算
+pub trait StrSliceExt {
+    /// 第 index 个字符，支持负数下标，越界时返回 None
+    fn py_get(&self, index: isize) -> Option<char>;
+
+    /// 第 index 个字符，越界时 panic
+    fn py_index(&self, index: isize) -> char;
+
+    /// 按字符取子串，step 为 1 时借用原字符串，否则创建新的字符串
+    fn py_try_slice(&self, slice: Slice) -> Result<Cow<'_, str>, SliceError>;
+
+    /// 按字符取子串，step 为 0 时 panic
+    fn py_slice(&self, slice: Slice) -> Cow<'_, str> {
+        self.py_try_slice(slice).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// 按字节取子串，下标可以为负数、会被截断，但必须落在字符边界上，step 只能省略或为 1
+    fn byte_slice(&self, slice: Slice) -> Result<&str, SliceError>;
+}
+
+impl StrSliceExt for str {
+    fn py_get(&self, index: isize) -> Option<char> {
+        if index >= 0 {
+            self.chars().nth(index as usize)
+        } else {
+            self.chars().rev().nth((-(index + 1)) as usize)
+        }
+    }
+
+    fn py_index(&self, index: isize) -> char {
+        match self.py_get(index) {
+            Some(c) => c,
+            None => panic!("char index {} out of range for string with {} chars", index, self.chars().count()),
+        }
+    }
+
+    fn py_try_slice(&self, slice: Slice) -> Result<Cow<'_, str>, SliceError> {
+        // 每个字符的起始字节，最后加上字符串的长度作为结尾
+        let mut offsets: Vec<usize> = self.char_indices().map(|(i, _)| i).collect();
+        let indices = slice.indices(offsets.len())?;
+        offsets.push(self.len());
+
+        if let Some(range) = indices.as_range() {
+            return Ok(Cow::Borrowed(&self[offsets[range.start]..offsets[range.end]]));
+        }
+        Ok(Cow::Owned(indices.map(|i| &self[offsets[i]..offsets[i + 1]]).collect()))
+    }
+
+    fn byte_slice(&self, slice: Slice) -> Result<&str, SliceError> {
+        if slice.step.unwrap_or(1) != 1 {
+            return Err(SliceError::Invalid(slice.to_string()));
+        }
+        let range = slice.indices(self.len())?.as_range().expect("step is 1");
+        for index in [range.start, range.end] {
+            if !self.is_char_boundary(index) {
+                return Err(SliceError::NotCharBoundary(index));
+            }
+        }
+        Ok(&self[range])
+    }
+}
@@ -0,0 +1,103 @@
+/*
+简单的文本表格
+
+各个命令都需要把结果按列对齐打印到终端，列宽按显示宽度计算，中文等宽字符占 2 列。
+ */
+
+use std::fmt::{self, Display, Formatter};
+
+use unicode_width::UnicodeWidthStr;
+
+/// 列的对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// 用表头创建表格，所有列默认左对齐
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Table {
+        let headers: Vec<String> = headers.into_iter().map(Into::into).collect();
+        let aligns = vec![Align::Left; headers.len()];
+        Table { headers, aligns, rows: Vec::new() }
+    }
+
+    /// 设置某一列的对齐方式
+    pub fn align(mut self, column: usize, align: Align) -> Table {
+        if column >= self.aligns.len() {
+            self.aligns.resize(column + 1, Align::Left);
+        }
+        self.aligns[column] = align;
+        self
+    }
+
+    /// 添加一行，列数可以少于表头，缺少的列留空
+    pub fn row<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let columns = self.rows.iter().map(Vec::len).chain([self.headers.len()]).max().unwrap_or(0);
+        let mut widths = vec![0; columns];
+        for cells in self.rows.iter().chain([&self.headers]) {
+            for (i, cell) in cells.iter().enumerate() {
+                widths[i] = widths[i].max(cell.width());
+            }
+        }
+        widths
+    }
+
+    fn write_line(&self, f: &mut Formatter, cells: &[String], widths: &[usize]) -> fmt::Result {
+        let mut line = String::new();
+        for (i, width) in widths.iter().enumerate() {
+            let cell = cells.get(i).map(String::as_str).unwrap_or("");
+            let padding = " ".repeat(width - cell.width());
+            if i > 0 {
+                line.push_str("  ");
+            }
+            match self.aligns.get(i).copied().unwrap_or(Align::Left) {
+                Align::Left => {
+                    line.push_str(cell);
+                    line.push_str(&padding);
+                }
+                Align::Right => {
+                    line.push_str(&padding);
+                    line.push_str(cell);
+                }
+            }
+        }
+        // 行尾的空格没有意义，去掉方便测试比较
+        writeln!(f, "{}", line.trim_end())
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let widths = self.widths();
+        if !self.headers.is_empty() {
+            self.write_line(f, &self.headers, &widths)?;
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            writeln!(f, "{}", rule.join("  "))?;
+        }
+        for cells in &self.rows {
+            self.write_line(f, cells, &widths)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+/*
+记录所有权变化的字符串
+
+`TracedString` 包装了一个 String，并且有一个名字（通常就是变量名）。下面这些事情发生时，
+它会在当前线程的日志中记录一条：
+
+- alloc：创建时在堆上分配内存，记录容量
+- clone：深拷贝，在堆上分配新的内存并复制数据
+- move：转移所有权。Rust 的 move 只是按位复制栈上的指针、长度和容量，没有代码可以执行，
+  所以需要在转移的地方明确写出 `let s2 = s1.moved("s2");` 才能记录
+- drop：所有者离开 scope，释放堆上的内存。已经转移走的不会再 drop
+
+    let s1 = TracedString::new("s1", "hello");
+    let s2 = s1.moved("s2");
+    let s3 = s2.clone_as("s3");
+
+    alloc s1 "hello" (capacity 5)
+    move  s1 -> s2
+    clone s2 -> s3 (capacity 5)
+    drop  s3 (capacity 5)
+    drop  s2 (capacity 5)
+
+`take_log` 取出并清空日志，`print_log` 同时把它打印出来，测试时加上 `-- --nocapture` 才能看到。
+日志是每个线程各自的（thread_local），并行运行的测试不会互相影响。
+ */
+
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::Deref;
+
+/// 一条所有权的变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Alloc { name: &'static str, value: String, capacity: usize },
+    /// from 被深拷贝为 name
+    Clone { from: &'static str, name: &'static str, capacity: usize },
+    Move { from: &'static str, to: &'static str },
+    Drop { name: &'static str, capacity: usize },
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Event::Alloc { name, value, capacity } => write!(f, "alloc {} {:?} (capacity {})", name, value, capacity),
+            Event::Clone { from, name, capacity } => write!(f, "clone {} -> {} (capacity {})", from, name, capacity),
+            Event::Move { from, to } => write!(f, "move  {} -> {}", from, to),
+            Event::Drop { name, capacity } => write!(f, "drop  {} (capacity {})", name, capacity),
+        }
+    }
+}
+
+thread_local! {
+    static LOG: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(event: Event) {
+    LOG.with_borrow_mut(|log| log.push(event));
+}
+
+/// 取出当前线程的日志，日志被清空
+pub fn take_log() -> Vec<Event> {
+    LOG.take()
+}
+
+/// 取出当前线程的日志并打印到标准输出，每条一行
+pub fn print_log() -> Vec<Event> {
+    let log = take_log();
+    for event in &log {
+        println!("{}", event);
+    }
+    log
+}
+
+/// 记录所有权变化的 String
+pub struct TracedString {
+    name: &'static str,
+    value: String,
+    // 所有权已经转移给了另一个 TracedString，drop 时不再记录
+    moved: bool,
+}
+
+impl TracedString {
+    pub fn new(name: &'static str, value: &str) -> TracedString {
+        TracedString::from_string(name, value.to_string())
+    }
+
+    /// 分配 capacity 字节的空字符串
+    pub fn with_capacity(name: &'static str, capacity: usize) -> TracedString {
+        TracedString::from_string(name, String::with_capacity(capacity))
+    }
+
+    fn from_string(name: &'static str, value: String) -> TracedString {
+        record(Event::Alloc { name, value: value.clone(), capacity: value.capacity() });
+        TracedString { name, value, moved: false }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.value.capacity()
+    }
+
+    /// 把所有权转移给名为 to 的所有者：堆上的数据不复制，原来的所有者也不会 drop
+    pub fn moved(mut self, to: &'static str) -> TracedString {
+        record(Event::Move { from: self.name, to });
+        self.moved = true;
+        TracedString { name: to, value: std::mem::take(&mut self.value), moved: false }
+    }
+
+    /// 深拷贝，新的字符串名为 name
+    pub fn clone_as(&self, name: &'static str) -> TracedString {
+        let value = self.value.clone();
+        record(Event::Clone { from: self.name, name, capacity: value.capacity() });
+        TracedString { name, value, moved: false }
+    }
+
+    /// 取出里面的 String，和 drop 一样记录一次，之后的 String 不再跟踪
+    pub fn into_string(mut self) -> String {
+        record(Event::Drop { name: self.name, capacity: self.value.capacity() });
+        self.moved = true;
+        std::mem::take(&mut self.value)
+    }
+}
+
+impl Clone for TracedString {
+    /// 深拷贝，名字不变
+    fn clone(&self) -> TracedString {
+        self.clone_as(self.name)
+    }
+}
+
+impl Drop for TracedString {
+    fn drop(&mut self) {
+        if !self.moved {
+            record(Event::Drop { name: self.name, capacity: self.value.capacity() });
+        }
+    }
+}
+
+impl Deref for TracedString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for TracedString {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad(&self.value)
+    }
+}
+
+impl Debug for TracedString {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TracedString").field("name", &self.name).field("value", &self.value).finish()
+    }
+}
+
+impl PartialEq for TracedString {
+    /// 只比较内容
+    fn eq(&self, other: &TracedString) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialEq<&str> for TracedString {
+    fn eq(&self, other: &&str) -> bool {
+        self.value == *other
+    }
+}
+
+impl PartialEq<TracedString> for &str {
+    fn eq(&self, other: &TracedString) -> bool {
+        *self == other.value
+    }
+}
@@ -0,0 +1,151 @@
+/*
+长元组
+
+标准库只为不超过 12 个元素的元组实现了 Debug、PartialEq、PartialOrd 等 trait：
+
+    let too_long_tuple = (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13);
+    // println!("too long tuple: {:?}", too_long_tuple);  // 编译错误
+
+这里用宏为最多 32 个元素的元组实现了几个 trait，元素满足对应的约束就可以使用：
+
+    use learn_rust::tuple::TupleDebug;
+    println!("too long tuple: {:?}", too_long_tuple.debug());
+ */
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+
+/// 所有元组都实现，提供元素个数
+pub trait Tuple {
+    const LEN: usize;
+
+    fn len(&self) -> usize {
+        Self::LEN
+    }
+
+    fn is_empty(&self) -> bool {
+        Self::LEN == 0
+    }
+}
+
+/// 元素都实现了 Debug 的元组
+pub trait TupleDebug: Tuple {
+    /// 与标准库的格式相同：`(1, 2, 3)`，单个元素时为 `(1,)`，也支持 `{:#?}`
+    fn fmt_tuple(&self, f: &mut Formatter) -> fmt::Result;
+
+    /// 按顺序取出所有元素
+    fn debug_elements(&self) -> Vec<&dyn Debug>;
+
+    /// 返回一个实现了 Debug 的包装，可以直接用 `{:?}` 打印
+    fn debug(&self) -> DebugTuple<'_, Self>
+    where
+        Self: Sized,
+    {
+        DebugTuple(self)
+    }
+}
+
+/// 元素都实现了 PartialEq 的元组
+pub trait TupleEq: Tuple {
+    /// 逐个元素比较是否相等
+    fn tuple_eq(&self, other: &Self) -> bool;
+}
+
+/// 元素都实现了 PartialOrd 的元组
+pub trait TupleOrd: Tuple {
+    /// 按字典序比较：从第一个元素开始，遇到不相等的元素就返回它们的比较结果
+    fn tuple_partial_cmp(&self, other: &Self) -> Option<Ordering>;
+}
+
+/// 元素都是 'static 类型的元组，可以按 Any 遍历并向下转换
+pub trait TupleAny: Tuple {
+    fn any_elements(&self) -> Vec<&dyn Any>;
+}
+
+/// `TupleDebug::debug` 返回的包装
+pub struct DebugTuple<'a, T: ?Sized>(&'a T);
+
+impl<T: TupleDebug + ?Sized> Debug for DebugTuple<'_, T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.fmt_tuple(f)
+    }
+}
+
+// 为一个元数的元组实现所有 trait，参数是 `类型参数 下标` 的列表
+macro_rules! tuple_impl {
+    ($($T:ident $idx:tt)*) => {
+        impl<$($T),*> Tuple for ($($T,)*) {
+            const LEN: usize = {
+                let names: &[&str] = &[$(stringify!($T)),*];
+                names.len()
+            };
+        }
+
+        impl<$($T: Debug),*> TupleDebug for ($($T,)*) {
+            fn fmt_tuple(&self, f: &mut Formatter) -> fmt::Result {
+                // 没有字段时 debug_tuple("") 什么也不输出，单元类型要打印成 ()
+                if Self::LEN == 0 {
+                    return f.write_str("()");
+                }
+                let mut builder = f.debug_tuple("");
+                $(builder.field(&self.$idx);)*
+                builder.finish()
+            }
+
+            fn debug_elements(&self) -> Vec<&dyn Debug> {
+                vec![$(&self.$idx as &dyn Debug),*]
+            }
+        }
+
+        impl<$($T: PartialEq),*> TupleEq for ($($T,)*) {
+            #[allow(unused_variables)]
+            fn tuple_eq(&self, other: &Self) -> bool {
+                true $(&& self.$idx == other.$idx)*
+            }
+        }
+
+        impl<$($T: PartialOrd),*> TupleOrd for ($($T,)*) {
+            #[allow(unused_variables)]
+            fn tuple_partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                $(
+                    match self.$idx.partial_cmp(&other.$idx) {
+                        Some(Ordering::Equal) => {}
+                        ordering => return ordering,
+                    }
+                )*
+                Some(Ordering::Equal)
+            }
+        }
+
+        impl<$($T: Any),*> TupleAny for ($($T,)*) {
+            fn any_elements(&self) -> Vec<&dyn Any> {
+                vec![$(&self.$idx as &dyn Any),*]
+            }
+        }
+    };
+}
+
+// 依次为 0 ~ 32 个元素的元组调用 tuple_impl，[] 中是已经处理过的前缀
+macro_rules! tuple_impls {
+    ([$($done:tt)*]) => {
+        tuple_impl!($($done)*);
+    };
+    ([$($done:tt)*] $T:ident $idx:tt $($rest:tt)*) => {
+        tuple_impl!($($done)*);
+        tuple_impls!([$($done)* $T $idx] $($rest)*);
+    };
+}
+
+tuple_impls!([]
+    T0 0 T1 1 T2 2 T3 3 T4 4 T5 5 T6 6 T7 7
+    T8 8 T9 9 T10 10 T11 11 T12 12 T13 13 T14 14 T15 15
+    T16 16 T17 17 T18 18 T19 19 T20 20 T21 21 T22 22 T23 23
+    T24 24 T25 25 T26 26 T27 27 T28 28 T29 29 T30 30 T31 31
+);
+
+/// 标准库中实现了 Debug 等 trait 的最大元组长度
+pub const STD_MAX_ARITY: usize = 12;
+
+/// 这里实现的最大元组长度
+pub const MAX_ARITY: usize = 32;
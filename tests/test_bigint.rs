@@ -0,0 +1,134 @@
+use learn_rust::bigint::{BigInt, ParseBigIntError};
+use proptest::prelude::*;
+
+fn big(s: &str) -> BigInt {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_bigint_lesson_overflow() {
+    // test_integer_compute 中因为溢出而注释掉的表达式
+    let x = BigInt::from(4294967296i64);
+    let num = &(&x * &x) * &x;
+    assert_eq!("79228162514264337593543950336", num.to_string());
+
+    // 再乘一次就超出了 u128
+    let num = &num * &x;
+    assert_eq!(None, num.to_u128());
+    assert_eq!("340282366920938463463374607431768211456", num.to_string());
+    assert_eq!(num, BigInt::from(2).pow(128));
+}
+
+#[test]
+fn test_bigint_parse_and_display() {
+    assert_eq!("0", big("0").to_string());
+    assert_eq!("0", big("-0").to_string());
+    assert_eq!("-123", big("-123").to_string());
+    assert_eq!("1000000", big("1_000_000").to_string());
+    assert_eq!(BigInt::from(255), big("0xff"));
+    assert_eq!(BigInt::from(-255), big("-0xFF"));
+    assert_eq!("0xff", format!("{:#x}", big("255")));
+    assert_eq!("FF", format!("{:X}", big("255")));
+    assert_eq!("  -42", format!("{:5}", big("-42")));
+    assert_eq!("-0042", format!("{:05}", big("-42")));
+    assert_eq!("zz", BigInt::from(1295).to_str_radix(36));
+
+    assert_eq!(Err(ParseBigIntError::Empty), "".parse::<BigInt>());
+    assert_eq!(Err(ParseBigIntError::Empty), "-".parse::<BigInt>());
+    assert_eq!(Err(ParseBigIntError::InvalidDigit { digit: 'a', radix: 10 }), "12a".parse::<BigInt>());
+    assert_eq!(Err(ParseBigIntError::InvalidDigit { digit: 'g', radix: 16 }), "0xfg".parse::<BigInt>());
+    assert_eq!(Err(ParseBigIntError::InvalidDigit { digit: '-', radix: 16 }), "0x-5".parse::<BigInt>());
+    assert_eq!(Err(ParseBigIntError::InvalidDigit { digit: '-', radix: 16 }), "+0x-5".parse::<BigInt>());
+    assert_eq!(Err(ParseBigIntError::InvalidDigit { digit: '+', radix: 16 }), "-0x+5".parse::<BigInt>());
+
+    let s = "-123456789012345678901234567890123456789012345678901234567890";
+    assert_eq!(s, big(s).to_string());
+}
+
+#[test]
+fn test_bigint_div_rem() {
+    // 与原生整数一样向零取整，余数符号跟随被除数
+    assert_eq!((BigInt::from(-2), BigInt::from(-1)), BigInt::from(-7).div_rem(&BigInt::from(3)));
+    assert_eq!((BigInt::from(-2), BigInt::from(1)), BigInt::from(7).div_rem(&BigInt::from(-3)));
+    assert_eq!(None, BigInt::from(7).checked_div(&BigInt::zero()));
+
+    let a = big("340282366920938463463374607431768211456123");
+    let b = big("98765432109876543210");
+    let (q, r) = a.div_rem(&b);
+    assert_eq!(a, &(&q * &b) + &r);
+    assert!(r < b);
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn test_bigint_div_by_zero() {
+    let _ = BigInt::from(1) / BigInt::zero();
+}
+
+#[test]
+fn test_bigint_compare() {
+    let mut values = [big("10"), big("-10"), big("0"), big("-100000000000000000000"), big("100000000000000000000")];
+    values.sort();
+    let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    assert_eq!(vec!["-100000000000000000000", "-10", "0", "10", "100000000000000000000"], sorted);
+    assert_eq!(Some(i128::MIN), BigInt::from(i128::MIN).to_i128());
+    assert_eq!(None, (BigInt::from(i128::MIN) - BigInt::one()).to_i128());
+}
+
+// i128 范围内的值，和原生运算的结果比较
+proptest! {
+    #[test]
+    fn prop_add_sub_match_i128(a in any::<i64>(), b in any::<i64>()) {
+        let (x, y) = (BigInt::from(a), BigInt::from(b));
+        prop_assert_eq!(Some(a as i128 + b as i128), (&x + &y).to_i128());
+        prop_assert_eq!(Some(a as i128 - b as i128), (&x - &y).to_i128());
+    }
+
+    #[test]
+    fn prop_checked_ops_match_i128(a in any::<i128>(), b in any::<i128>()) {
+        let (x, y) = (BigInt::from(a), BigInt::from(b));
+        if let Some(sum) = a.checked_add(b) {
+            prop_assert_eq!(Some(sum), (&x + &y).to_i128());
+        }
+        if let Some(product) = a.checked_mul(b) {
+            prop_assert_eq!(Some(product), (&x * &y).to_i128());
+        }
+        if let (Some(q), Some(r)) = (a.checked_div(b), a.checked_rem(b)) {
+            prop_assert_eq!(Some((BigInt::from(q), BigInt::from(r))), x.checked_div_rem(&y));
+        }
+        prop_assert_eq!(a.cmp(&b), x.cmp(&y));
+    }
+
+    #[test]
+    fn prop_mul_div_match_i128(a in any::<i64>(), b in any::<i64>()) {
+        let (x, y) = (BigInt::from(a), BigInt::from(b));
+        prop_assert_eq!(Some(a as i128 * b as i128), (&x * &y).to_i128());
+        if b != 0 {
+            prop_assert_eq!(Some(a as i128 / b as i128), (&x / &y).to_i128());
+            prop_assert_eq!(Some(a as i128 % b as i128), (&x % &y).to_i128());
+        }
+    }
+
+    #[test]
+    fn prop_pow_matches_i128(a in -1000i128..1000, exp in 0u32..12) {
+        prop_assert_eq!(Some(a.pow(exp)), BigInt::from(a).pow(exp).to_i128());
+    }
+
+    #[test]
+    fn prop_parse_display_roundtrip(a in any::<i128>()) {
+        let x = BigInt::from(a);
+        prop_assert_eq!(a.to_string(), x.to_string());
+        prop_assert_eq!(&x, &a.to_string().parse::<BigInt>().unwrap());
+        prop_assert_eq!(format!("{:x}", a.unsigned_abs()), format!("{:x}", x.abs()));
+        prop_assert_eq!(&x, &BigInt::from_str_radix(&x.to_str_radix(16), 16).unwrap());
+    }
+
+    #[test]
+    fn prop_div_rem_identity(a in "-?[1-9][0-9]{0,60}", b in "-?[1-9][0-9]{0,30}") {
+        // 超出 i128 的值，检查 a == q * b + r 且 |r| < |b|
+        let (x, y) = (big(&a), big(&b));
+        let (q, r) = x.div_rem(&y);
+        prop_assert_eq!(&x, &(&(&q * &y) + &r));
+        prop_assert!(r.abs() < y.abs());
+    }
+}
@@ -0,0 +1,140 @@
+use learn_rust::bitwise::{self, Operand, OperandError, Outcome};
+use learn_rust::int_type::IntType;
+
+#[test]
+fn test_int_type() {
+    assert_eq!(Ok(IntType::U8), "u8".parse());
+    assert!("u7".parse::<IntType>().is_err());
+    assert_eq!(8, IntType::I8.bits());
+    assert_eq!(-128, IntType::I8.min());
+    assert_eq!(127, IntType::I8.max());
+    assert_eq!(u128::MAX, IntType::U128.max());
+    assert_eq!(i128::MIN, IntType::I128.min());
+    assert_eq!(-1, IntType::I8.to_i128(0xff));
+    assert_eq!("255", IntType::U8.format_decimal(0xff));
+}
+
+#[test]
+fn test_bitwise_parse() {
+    assert_eq!(0b0011, Operand::parse(IntType::U32, "0b0011").unwrap().raw);
+    assert_eq!(0xff, Operand::parse(IntType::I8, "-1").unwrap().raw);
+    // 带前缀的写法表示位模式
+    assert_eq!("-1", Operand::parse(IntType::I8, "0xff").unwrap().decimal());
+    assert_eq!("-128", Operand::parse(IntType::I8, "-128").unwrap().decimal());
+    assert_eq!(i128::MIN.to_string(), Operand::parse(IntType::I128, &i128::MIN.to_string()).unwrap().decimal());
+    assert_eq!(1_000, Operand::parse(IntType::U16, "1_000").unwrap().raw);
+
+    assert!(matches!(Operand::parse(IntType::I8, "128"), Err(OperandError::OutOfRange { .. })));
+    assert!(matches!(Operand::parse(IntType::U8, "-1"), Err(OperandError::OutOfRange { .. })));
+    assert!(matches!(Operand::parse(IntType::U8, "0x100"), Err(OperandError::OutOfRange { .. })));
+    assert!(matches!(Operand::parse(IntType::U8, "abc"), Err(OperandError::Invalid(_))));
+    assert!(matches!(Operand::parse(IntType::U8, ""), Err(OperandError::Invalid(_))));
+}
+
+#[test]
+fn test_bitwise_lesson_examples() {
+    // 与 test_integer_compute 里的例子相同
+    let a = Operand::parse(IntType::U32, "0b0011").unwrap();
+    let b = Operand::parse(IntType::U32, "0b0101").unwrap();
+    assert_eq!(0b0001, (a & b).raw);
+    assert_eq!(0b0111, (a | b).raw);
+    assert_eq!(0b0110, (a ^ b).raw);
+
+    let one = Operand::parse(IntType::U32, "1").unwrap();
+    assert_eq!(Some(32), one.checked_shl(5).map(|v| v.raw));
+    let x = Operand::parse(IntType::U32, "0x80").unwrap();
+    assert_eq!(Some(0x20), x.checked_shr(2).map(|v| v.raw));
+
+    assert_eq!("0000_0011", Operand::parse(IntType::U8, "3").unwrap().binary());
+    assert_eq!("0x0003", Operand::parse(IntType::U16, "3").unwrap().hex());
+}
+
+#[test]
+fn test_bitwise_matches_native_u8_i8() {
+    // 所有 u8、i8 的组合都和原生运算比较一遍
+    for a in 0..=255u8 {
+        for n in 0..=10u32 {
+            let op = Operand::from_raw(IntType::U8, a as u128);
+            assert_eq!(a.checked_shl(n).map(u128::from), op.checked_shl(n).map(|v| v.raw));
+            assert_eq!(a.checked_shr(n).map(u128::from), op.checked_shr(n).map(|v| v.raw));
+            assert_eq!(a.wrapping_shl(n) as u128, op.wrapping_shl(n).raw);
+            assert_eq!(a.rotate_left(n) as u128, op.rotate_left(n).raw);
+            assert_eq!(a.rotate_right(n) as u128, op.rotate_right(n).raw);
+
+            let s = a as i8;
+            let op = Operand::from_i128(IntType::I8, s as i128);
+            assert_eq!(s.checked_shr(n).map(|v| v as i128), op.checked_shr(n).map(|v| IntType::I8.to_i128(v.raw)));
+            assert_eq!(s.wrapping_shr(n) as i128, IntType::I8.to_i128(op.wrapping_shr(n).raw));
+        }
+        for b in 0..=255u8 {
+            let x = Operand::from_raw(IntType::U8, a as u128);
+            let y = Operand::from_raw(IntType::U8, b as u128);
+            assert_eq!((a & b) as u128, (x & y).raw);
+            assert_eq!((a | b) as u128, (x | y).raw);
+            assert_eq!((a ^ b) as u128, (x ^ y).raw);
+        }
+        let x = Operand::from_raw(IntType::I8, a as u128);
+        assert_eq!((!(a as i8)).to_string(), (!x).decimal());
+        assert_eq!((a as i8).count_ones(), x.count_ones());
+        assert_eq!((a as i8).leading_zeros(), x.leading_zeros());
+    }
+}
+
+#[test]
+fn test_bitwise_wide_types() {
+    let a = Operand::from_raw(IntType::U128, u128::MAX);
+    assert_eq!(0, a.leading_zeros());
+    assert_eq!(128, a.count_ones());
+    assert_eq!(None, a.checked_shl(128));
+    assert_eq!(u128::MAX.wrapping_shl(130), a.wrapping_shl(130).raw);
+
+    let a = Operand::from_i128(IntType::I64, -8);
+    assert_eq!(Some(-1), a.checked_shr(63).map(|v| IntType::I64.to_i128(v.raw)));
+    assert_eq!((-8i64).rotate_left(60) as i128, IntType::I64.to_i128(a.rotate_left(60).raw));
+}
+
+#[test]
+fn test_bitwise_shift_overflow() {
+    let a = Operand::parse(IntType::U8, "3").unwrap();
+    let b = Operand::parse(IntType::U8, "9").unwrap();
+    let ops = bitwise::operations(a, b);
+    let (_, shl) = ops.iter().find(|(expr, _)| expr == "a << b").unwrap();
+    // 3u8 << 9 在调试模式下 panic，发布模式下相当于 3u8 << 1
+    assert_eq!(&Outcome::Overflow { wrapped: Operand::from_raw(IntType::U8, 6) }, shl);
+
+    let cases = bitwise::shift_cases(a, Some(9));
+    assert_eq!(vec![7, 8, 9], cases.iter().map(|c| c.amount).collect::<Vec<_>>());
+    assert!(cases[0].checked_shl.is_some());
+    assert!(cases[1].checked_shl.is_none());
+    assert_eq!(3, cases[1].wrapping_shl.raw);
+
+    let text = bitwise::render(a, b);
+    assert!(text.contains("a & b"));
+    assert!(text.contains("overflow"));
+    assert!(text.contains("n % 8"));
+}
+
+#[test]
+fn test_bitwise_shift_amount_above_u32() {
+    // 4294967297 作为 u32 是 1，但 Rust 按完整的值检查，1u64 << 4294967297u64 在调试模式下 panic
+    let a = Operand::parse(IntType::U64, "1").unwrap();
+    let b = Operand::parse(IntType::U64, "4294967297").unwrap();
+    let ops = bitwise::operations(a, b);
+    let (_, shl) = ops.iter().find(|(expr, _)| expr == "a << b").unwrap();
+    assert_eq!(&Outcome::Overflow { wrapped: Operand::from_raw(IntType::U64, 2) }, shl);
+    assert_eq!(2, 1u64.wrapping_shl(4294967297u64 as u32));
+
+    let cases = bitwise::shift_cases(a, b.shift_amount());
+    assert_eq!(4294967297, cases[3].amount);
+    assert_eq!(None, cases[3].checked_shl);
+    assert_eq!(2, cases[3].wrapping_shl.raw);
+
+    // 负数的位数同样溢出，发布模式下按补码的低位移动：-1i8 移动 7 位
+    let a = Operand::parse(IntType::I8, "1").unwrap();
+    let b = Operand::parse(IntType::I8, "-1").unwrap();
+    assert!(b.shift_overflows(8));
+    let ops = bitwise::operations(a, b);
+    let (_, shl) = ops.iter().find(|(expr, _)| expr == "a << b").unwrap();
+    assert_eq!(&Outcome::Overflow { wrapped: Operand::from_i128(IntType::I8, i8::MIN as i128) }, shl);
+    assert_eq!(3, bitwise::shift_cases(a, b.shift_amount()).len());
+}
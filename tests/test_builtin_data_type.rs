@@ -63,6 +63,13 @@ fn test_integer_type() {
     let num = 0o77; // 0o 前缀八进制
     let num = 0b1111_0000;  // 0b 二进制
     let byte = b'A'; // 字节，只能声明为 u8 类型
+    // 多字节的整数在内存中按字节序存放，写入文件或网络时要约定好用大端还是小端
+    assert_eq!([0x00, 0x41], (byte as u16).to_be_bytes());
+    assert_eq!([0x41, 0x00], (byte as u16).to_le_bytes());
+
+    // 该选哪种类型？能装下所有可能的值的最小类型，比如年龄 0 ~ 150 用 u8
+    let advice = learn_rust::int_fit::advise([0, 150]).unwrap();
+    assert_eq!("u8", advice.recommended().unwrap().ty.to_string());
 
 }
 
@@ -80,6 +87,11 @@ fn test_integer_compute() {
     // 发布模式下的编译则不会检查溢出
     // let num = 1u32 - 2;
     // let num = 4294967296i64 * 4294967296i64 * 4294967296i64;
+    // 超出原生整数范围的结果可以用任意精度整数 BigInt 计算
+    let x = learn_rust::bigint::BigInt::from(4294967296i64);
+    let num = &(&x * &x) * &x;
+    println!("4294967296 * 4294967296 * 4294967296 = {}", num);
+    assert_eq!("79228162514264337593543950336", num.to_string());
 
     // 整数相除, 得到是整数去余数处理
     let num = 64 / 5;
@@ -93,12 +105,20 @@ fn test_integer_compute() {
     let num = 54 % 5;
     assert_eq!(4, num);
 
+    // 有负数时，`/` 向零取整，`%` 的符号与被除数相同
+    assert_eq!(-12, -64 / 5);
+    assert_eq!(-4, -54 % 5);
+    // 需要非负的余数时使用 rem_euclid
+    assert_eq!(1, (-54i32).rem_euclid(5));
+    // 运行 `learn_rust divide -54 5` 可以对比所有的除法，`learn_rust divide MIN -1` 可以看到哪些会 panic
+
     // 位运算
     println!("0011 AND 0101 is {:04b}", 0b0011u32 & 0b0101);
     println!("0011 OR 0101 is {:04b}", 0b0011u32 | 0b0101);
     println!("0011 XOR 0101 is {:04b}", 0b0011u32 ^ 0b0101);
     println!("1 << 5 is {}", 1u32 << 5);
     println!("0x80 >> 2 is 0x{:x}", 0x80u32 >> 2);
+    // 运行 `learn_rust bitwise 0b0011 0b0101 u32` 可以看到所有位运算的对照表
 
 }
 
@@ -115,6 +135,13 @@ fn test_float_type(){
     let money : f32 = 2.0;
 
     assert_eq!(2.0, money);
+
+    // 注意：浮点数是二进制的，0.1 这样的小数无法精确表示，所以不要用浮点数保存金额
+    assert_ne!(0.3, 0.1 + 0.2);
+    // 金额应该用定点小数保存，learn_rust::money::Money 内部以 “分” 为单位
+    let money: learn_rust::money::Money = "¥2.00".parse().unwrap();
+    assert_eq!(200, money.minor_units());
+    assert_eq!("¥2.00", money.to_string());
 }
 
 #[test]
@@ -144,11 +171,25 @@ fn test_char_type() {
          U+E000 ~ U+10FFFF
      */
     let ch = 'z';
+    assert_eq!(1, ch.len_utf8());
     let ch : char = '中';
+    assert_eq!(3, ch.len_utf8());
     let ch : char = '\0';
+    assert_eq!(0, ch as u32);
     let ch : char = '\n';
+    assert!(ch.is_control());
     let ch : char = '😊';
+    assert_eq!(4, ch.len_utf8());
+    assert_eq!(2, ch.len_utf16()); // 超过 U+FFFF，utf-16 需要两个码元
 
+    // 代理区的码点不能转换为 char
+    assert_eq!(None, char::from_u32(0xD800));
+    assert_eq!(None, char::from_u32(0x110000));
+
+    // 更详细的信息可以用 learn_rust::chars 查看，也可以运行 `learn_rust char 😊`
+    let info = learn_rust::chars::inspect(ch);
+    assert_eq!(0x1F60A, info.code_point);
+    assert_eq!("So", info.category);
 }
 
 #[test]
@@ -186,6 +227,9 @@ fn test_tuple_type() {
     // 但很长的元组无法打印
     let too_long_tuple = (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13);
     // println!("too long tuple: {:?}", too_long_tuple);
+    // 标准库只为不超过 12 个元素的元组实现了 Debug，更长的元组可以借助 learn_rust::tuple 打印
+    use learn_rust::tuple::TupleDebug;
+    println!("too long tuple: {:?}", too_long_tuple.debug());
 
 }
 
@@ -215,6 +259,12 @@ fn test_list_type() {
 
     // 越界的下标时,编译会通过,运行会引发致命错误（panic）
     // println!("{}", arr[100]);
+    // 不想 panic 可以用 get，越界时返回 None
+    assert_eq!(None, arr.get(100));
+    // learn_rust::slicing 提供 Python 风格的负数下标与切片
+    use learn_rust::slicing::{Slice, SliceExt};
+    assert_eq!(&5, arr.py_index(-1));
+    assert_eq!(vec![5, 4, 3, 2, 100], arr.py_slice(Slice::reversed()).to_vec());
 
 }
 
@@ -245,5 +295,12 @@ fn test_array_and_slice() {
     assert_eq!(3, pick[1]);
     assert_eq!(4, pick[2]);
 
+    // 二维数组：数组的元素也可以是数组，长度同样是类型的一部分
+    let grid: [[i32; 3]; 2] = [[1, 2, 3], [4, 5, 6]];
+    assert_eq!(6, grid[1][2]);
+    // learn_rust::matrix::Matrix 用常量泛型包装了二维数组，相乘时在编译期检查行列数
+    let m = learn_rust::matrix::Matrix::new(grid);
+    assert_eq!(learn_rust::matrix::Matrix::new([[14, 32], [32, 77]]), m * m.transpose());
+
 }
 
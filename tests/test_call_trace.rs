@@ -0,0 +1,162 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use learn_rust::call_trace::{self, trace, Event};
+
+#[trace]
+fn add(x: i32, y: i32) -> i32 {
+    x + y
+}
+
+// 嵌套调用：深度加 1
+#[trace]
+fn add_three(x: i32, y: i32, z: i32) -> i32 {
+    add(add(x, y), z)
+}
+
+#[trace]
+fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or("")
+}
+
+#[trace]
+fn double(s: &str) -> Result<i32, std::num::ParseIntError> {
+    let n: i32 = s.parse()?;
+    Ok(n * 2)
+}
+
+#[trace]
+fn evens(n: u32) -> impl Iterator<Item = u32> {
+    (0..n).filter(|x| x % 2 == 0)
+}
+
+// 没有实现 Debug 的参数需要跳过
+struct Secret;
+
+#[trace(skip(secret))]
+fn check(secret: Secret, attempts: u8) -> bool {
+    let _ = secret;
+    attempts < 3
+}
+
+#[trace]
+fn explode(n: u8) -> u8 {
+    if n > 0 {
+        panic!("boom");
+    }
+    n
+}
+
+// 返回从参数中借出的 &mut
+#[trace]
+fn first(v: &mut [i32]) -> &mut i32 {
+    &mut v[0]
+}
+
+#[trace]
+fn fail(message: &str) -> ! {
+    panic!("{}", message)
+}
+
+struct Counter(i32);
+
+impl Counter {
+    #[trace]
+    fn bump(&mut self, by: i32) -> i32 {
+        self.0 += by;
+        if self.0 > 10 {
+            return 10;
+        }
+        self.0
+    }
+}
+
+// 去掉耗时，只比较其余的部分
+fn summary(events: &[Event]) -> Vec<String> {
+    let strip = |event: &Event| match event {
+        Event::Exit { function, depth, value, .. } => format!("{}< {} = {}", "  ".repeat(*depth), function, value),
+        Event::Unwind { function, depth, .. } => format!("{}< {} panicked", "  ".repeat(*depth), function),
+        enter => enter.to_string(),
+    };
+    events.iter().map(strip).collect()
+}
+
+#[test]
+fn test_nested_calls() {
+    let (value, events) = call_trace::capture(|| add_three(1, 2, 3));
+    assert_eq!(6, value);
+    if cfg!(feature = "trace") {
+        let expected = [
+            "> add_three(x: 1, y: 2, z: 3)",
+            "  > add(x: 1, y: 2)",
+            "  < add = 3",
+            "  > add(x: 3, y: 3)",
+            "  < add = 6",
+            "< add_three = 6",
+        ];
+        assert_eq!(expected.to_vec(), summary(&events));
+        // 外层的耗时包括内层
+        let elapsed = |event: &Event| match event {
+            Event::Exit { elapsed, .. } => *elapsed,
+            _ => Duration::ZERO,
+        };
+        assert!(elapsed(&events[5]) >= elapsed(&events[2]));
+    } else {
+        // 没有打开 feature 时 #[trace] 不产生任何代码
+        assert!(events.is_empty());
+    }
+}
+
+#[test]
+fn test_return_and_question_mark() {
+    let mut counter = Counter(5);
+    let (values, events) = call_trace::capture(|| {
+        (first_word("hello world"), double("21"), double("x").is_err(), evens(5).count(), counter.bump(20))
+    });
+    assert_eq!(("hello", Ok(42), true, 3, 10), values);
+
+    let mut v = vec![1, 2];
+    let (_, first_events) = call_trace::capture(|| *first(&mut v) += 10);
+    assert_eq!(vec![11, 2], v);
+    if cfg!(feature = "trace") {
+        assert_eq!(vec!["> first(v: [1, 2])", "< first = 1"], summary(&first_events));
+    }
+    if cfg!(feature = "trace") {
+        let expected = [
+            "> first_word(s: \"hello world\")",
+            "< first_word = \"hello\"",
+            "> double(s: \"21\")",
+            "< double = Ok(42)",
+            "> double(s: \"x\")",
+            "< double = Err(ParseIntError { kind: InvalidDigit })",
+            "> evens(n: 5)",
+            "< evens = Filter { iter: 0..5 }",
+            // self 不显示
+            "> bump(by: 20)",
+            "< bump = 10",
+        ];
+        assert_eq!(expected.to_vec(), summary(&events));
+    }
+}
+
+#[test]
+fn test_skip_and_panic() {
+    let (ok, events) = call_trace::capture(|| check(Secret, 1));
+    assert!(ok);
+    if cfg!(feature = "trace") {
+        assert_eq!(vec!["> check(attempts: 1)", "< check = true"], summary(&events));
+    }
+
+    // panic hook 是整个进程共用的，测试并行运行，所以不替换它，panic 的消息会打印出来
+    let (result, events) = call_trace::capture(|| panic::catch_unwind(AssertUnwindSafe(|| add(1, explode(1) as i32))));
+    assert!(result.is_err());
+    if cfg!(feature = "trace") {
+        assert_eq!(vec!["> explode(n: 1)", "< explode panicked"], summary(&events));
+        let (result, events) = call_trace::capture(|| panic::catch_unwind(|| fail("stop")));
+        assert!(result.is_err());
+        assert_eq!(vec!["> fail(message: \"stop\")", "< fail panicked"], summary(&events));
+        // panic 之后深度恢复为 0
+        let (_, events) = call_trace::capture(|| add(1, 1));
+        assert_eq!(0, events[0].depth());
+    }
+}
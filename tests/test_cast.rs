@@ -0,0 +1,121 @@
+use learn_rust::cast::{self, Cast, CastKind, Lossless, NumType, Value};
+use learn_rust::int_type::IntType;
+
+fn ty(name: &str) -> NumType {
+    name.parse().unwrap()
+}
+
+fn cast_str(from: &str, value: &str, to: &str) -> Cast {
+    Cast::new(Value::parse(ty(from), value).unwrap(), ty(to))
+}
+
+#[test]
+fn test_cast_integer() {
+    // 截断：只保留低 8 位
+    let c = cast_str("i32", "300", "u8");
+    assert_eq!(Value::U8(44), c.result);
+    assert_eq!(CastKind::Truncated, c.kind);
+    assert_eq!(Some("u8::try_from(300i32) == Err(TryFromIntError(()))".to_string()), c.lossless_call());
+
+    // 按位重新解释
+    let c = cast_str("i8", "-1", "u8");
+    assert_eq!(Value::U8(255), c.result);
+    assert_eq!(CastKind::Reinterpreted, c.kind);
+    let c = cast_str("i8", "-1", "u32");
+    assert_eq!(Value::U32(u32::MAX), c.result);
+    assert_eq!(CastKind::Reinterpreted, c.kind);
+
+    // 能放下时是精确的，并且有 From
+    let c = cast_str("i8", "3", "i32");
+    assert_eq!(Value::I32(3), c.result);
+    assert_eq!(CastKind::Exact, c.kind);
+    assert_eq!(Lossless::From, c.lossless);
+    assert_eq!(Some("i32::from(3i8) == 3".to_string()), c.lossless_call());
+    assert_eq!(Some("u8::try_from(200u32) == Ok(200)".to_string()), cast_str("u32", "200", "u8").lossless_call());
+}
+
+#[test]
+fn test_cast_float_to_int() {
+    let c = cast_str("f64", "2.7", "i32");
+    assert_eq!(Value::I32(2), c.result);
+    assert_eq!(CastKind::FractionDropped, c.kind);
+    assert_eq!(Value::I32(-1), cast_str("f64", "-1.9", "i32").result);
+
+    // 饱和
+    let c = cast_str("f64", "1e10", "i32");
+    assert_eq!(Value::I32(i32::MAX), c.result);
+    assert_eq!(CastKind::Saturated, c.kind);
+    assert_eq!(Value::U8(0), cast_str("f32", "-5", "u8").result);
+    assert_eq!(CastKind::Saturated, cast_str("f32", "-5", "u8").kind);
+    assert_eq!(Value::I64(i64::MIN), cast_str("f64", "-inf", "i64").result);
+
+    // NaN 转为 0
+    let c = cast_str("f64", "NaN", "u16");
+    assert_eq!(Value::U16(0), c.result);
+    assert_eq!(CastKind::NanToZero, c.kind);
+    assert_eq!(Lossless::None, c.lossless);
+    assert_eq!(None, c.lossless_call());
+}
+
+#[test]
+fn test_cast_to_float() {
+    // 2^24 + 1 无法用 f32 精确表示
+    let c = cast_str("i32", "16777217", "f32");
+    assert_eq!(Value::F32(16777216.0), c.result);
+    assert_eq!(CastKind::Rounded, c.kind);
+    assert_eq!(CastKind::Exact, cast_str("i32", "16777217", "f64").kind);
+    assert_eq!(CastKind::Rounded, cast_str("f64", "0.1", "f32").kind);
+    assert_eq!(CastKind::Infinity, cast_str("f64", "1e300", "f32").kind);
+    assert_eq!(CastKind::Infinity, cast_str("u128", "MAX", "f32").kind);
+    assert_eq!(CastKind::Exact, cast_str("f64", "NaN", "f32").kind);
+    // 与课程里的 64 as f64 一致
+    assert_eq!(Value::F64(64.0), cast_str("i32", "64", "f64").result);
+}
+
+#[test]
+fn test_cast_lossless_table() {
+    let int = |name: &str| NumType::Int(name.parse::<IntType>().unwrap());
+    assert_eq!(Lossless::Identity, cast::lossless(int("i32"), int("i32")));
+    assert_eq!(Lossless::From, cast::lossless(int("u8"), int("i16")));
+    assert_eq!(Lossless::TryFrom, cast::lossless(int("i8"), int("u64")));
+    assert_eq!(Lossless::TryFrom, cast::lossless(int("u8"), int("i8")));
+    // usize 与 u64 之间没有 From
+    assert_eq!(Lossless::TryFrom, cast::lossless(int("usize"), int("u64")));
+    assert_eq!(Lossless::TryFrom, cast::lossless(int("u32"), int("usize")));
+    assert_eq!(Lossless::From, cast::lossless(int("u16"), int("usize")));
+    assert_eq!(Lossless::From, cast::lossless(int("i16"), int("isize")));
+    assert_eq!(Lossless::From, cast::lossless(int("i32"), NumType::F64));
+    assert_eq!(Lossless::None, cast::lossless(int("i32"), NumType::F32));
+    assert_eq!(Lossless::From, cast::lossless(NumType::F32, NumType::F64));
+    assert_eq!(Lossless::None, cast::lossless(NumType::F64, NumType::F32));
+
+    // 与标准库实际提供的实现核对
+    assert_eq!(Ok(5usize), usize::try_from(5u64));
+    assert_eq!(1.5f64, f64::from(1.5f32));
+    assert_eq!(3isize, isize::from(3i16));
+}
+
+#[test]
+fn test_cast_matches_try_from() {
+    // 整数之间 `as` 精确时 TryFrom 一定成功，反之一定失败
+    for value in Value::samples(NumType::Int(IntType::I16)) {
+        let Value::I16(v) = value else { unreachable!() };
+        let c = Cast::new(value, NumType::Int(IntType::U8));
+        assert_eq!(u8::try_from(v).is_ok(), c.kind == CastKind::Exact);
+        let c = Cast::new(value, NumType::Int(IntType::I8));
+        assert_eq!(i8::try_from(v).is_ok(), c.kind == CastKind::Exact);
+    }
+}
+
+#[test]
+fn test_cast_render() {
+    let matrix = cast::render_matrix();
+    assert!(matrix.contains("From"));
+    for t in NumType::ALL {
+        assert!(matrix.lines().any(|line| line.starts_with(&format!("{} ", t))));
+    }
+
+    let text = cast::render_casts(&Value::samples(NumType::F64), Some(NumType::Int(IntType::I32)));
+    assert!(text.contains("NaN becomes 0"));
+    assert!(text.contains("saturated"));
+}
@@ -0,0 +1,81 @@
+use learn_rust::chars::{self, CharError, CodePoint};
+
+#[test]
+fn test_char_encoding() {
+    // ascii 字符，utf-8 和 utf-16 都只需要一个单元
+    let info = chars::inspect('z');
+    assert_eq!(0x7A, info.code_point);
+    assert_eq!(vec![0x7A], info.utf8);
+    assert_eq!(vec![0x007A], info.utf16);
+    assert_eq!("Ll", info.category);
+    assert_eq!(Some(1), info.width);
+
+    // 中文 utf-8 需要 3 个字节，终端里占 2 列
+    let info = chars::inspect('中');
+    assert_eq!(0x4E2D, info.code_point);
+    assert_eq!(vec![0xE4, 0xB8, 0xAD], info.utf8);
+    assert_eq!(vec![0x4E2D], info.utf16);
+    assert_eq!("Lo", info.category);
+    assert_eq!(Some(2), info.width);
+
+    // 超过 U+FFFF 的字符，utf-16 需要一对代理码元
+    let info = chars::inspect('😊');
+    assert_eq!(0x1F60A, info.code_point);
+    assert_eq!(vec![0xF0, 0x9F, 0x98, 0x8A], info.utf8);
+    assert_eq!(vec![0xD83D, 0xDE0A], info.utf16);
+    assert!(info.needs_surrogate_pair());
+    assert_eq!("So", info.category);
+    assert_eq!("\\u{1f60a}", info.escape);
+}
+
+#[test]
+fn test_char_control() {
+    // 控制字符没有显示宽度，转义形式就是 Rust 字面量里的写法
+    let info = chars::inspect('\n');
+    assert_eq!("Cc", info.category);
+    assert_eq!("Control", info.category_name());
+    assert_eq!(None, info.width);
+    assert_eq!("\\n", info.escape);
+
+    let info = chars::inspect('\0');
+    assert_eq!(vec![0], info.utf8);
+    assert_eq!("\\u{0}", info.escape);
+}
+
+#[test]
+fn test_char_parse_input() {
+    assert_eq!(Ok('中'), chars::parse_char("中"));
+    assert_eq!(Ok('中'), chars::parse_char("U+4E2D"));
+    assert_eq!(Ok('中'), chars::parse_char("u+4e2d"));
+    assert_eq!(Ok('\0'), chars::parse_char("U+0000"));
+    assert_eq!(Ok(char::MAX), chars::parse_char("U+10FFFF"));
+
+    assert_eq!(Err(CharError::Empty), chars::parse_char(""));
+    assert_eq!(Err(CharError::NotSingleChar("ab".to_string())), chars::parse_char("ab"));
+    assert_eq!(Err(CharError::InvalidHex("U+XYZ".to_string())), chars::parse_char("U+XYZ"));
+    assert_eq!(Err(CharError::InvalidHex("U+".to_string())), chars::parse_char("U+"));
+}
+
+#[test]
+fn test_char_reject_surrogate() {
+    // 代理区的两端都不能作为 char
+    assert_eq!(Err(CharError::Surrogate(0xD800)), chars::parse_char("U+D800"));
+    assert_eq!(Err(CharError::Surrogate(0xDFFF)), chars::parse_char("U+DFFF"));
+    // 代理区两侧是合法的
+    assert!(chars::parse_char("U+D7FF").is_ok());
+    assert!(chars::parse_char("U+E000").is_ok());
+
+    let message = chars::parse_char("U+D83D").unwrap_err().to_string();
+    assert!(message.contains("surrogate"));
+    assert!(message.contains("U+D800..U+DFFF"));
+
+    assert_eq!(Err(CharError::OutOfRange(0x110000)), chars::parse_char("U+110000"));
+}
+
+#[test]
+fn test_char_classify() {
+    assert_eq!(CodePoint::Scalar('a'), CodePoint::classify(0x61));
+    assert!(CodePoint::classify(0xDC00).is_surrogate());
+    assert!(CodePoint::classify(0x110000).is_out_of_range());
+    assert!(CodePoint::classify(u32::MAX).is_out_of_range());
+}
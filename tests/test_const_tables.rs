@@ -0,0 +1,81 @@
+use learn_rust::const_tables::{
+    self, crc32, crc32_table, crc32_update, decimal_digits, popcount, pow10_u64, CRC32_POLYNOMIAL, CRC32_TABLE,
+    POPCOUNT, POW10_U128, POW10_U16, POW10_U32, POW10_U64, POW10_U8,
+};
+
+// 运行时逐位计算，不用查表
+fn crc32_bitwise(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ CRC32_POLYNOMIAL } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[test]
+fn test_compile_time() {
+    // 只有编译时能确定的值才能用在这些位置：const、static、数组长度、const 泛型参数
+    const CHECK: u32 = crc32(b"The quick brown fox jumps over the lazy dog");
+    static DIGITS: u32 = decimal_digits(u64::MAX);
+    let bits = [0u8; POPCOUNT[0xFF] as usize];
+    const _: () = assert!(popcount(u64::MAX) == 64);
+
+    // 编译期计算的结果放在 inline const 块中，同样在编译时求值
+    let table = const { crc32_table(0x82F6_3B78) };
+
+    assert_eq!(0x414F_A339, CHECK);
+    assert_eq!(20, DIGITS);
+    assert_eq!(8, bits.len());
+    assert_eq!(0xE306_9283, crc32c(&table, b"123456789"));
+}
+
+// 用另一个多项式（CRC-32C）的表计算
+fn crc32c(table: &[u32; 256], data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &b| table[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+#[test]
+fn test_crc32_matches_runtime() {
+    // 第 i 项就是字节 i 移位 8 次后的余数
+    for (i, entry) in CRC32_TABLE.iter().enumerate() {
+        let mut crc = i as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ CRC32_POLYNOMIAL } else { crc >> 1 };
+        }
+        assert_eq!(crc, *entry);
+    }
+    let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    assert_eq!(crc32_bitwise(&data), crc32(&data));
+    assert_eq!(crc32(&data), crc32_update(crc32(&data[..300]), &data[300..]));
+    assert_eq!(0, crc32(b""));
+}
+
+#[test]
+fn test_pow10_matches_runtime() {
+    assert_eq!(POW10_U8.to_vec(), (0..3).map(|n| 10u8.pow(n)).collect::<Vec<_>>());
+    assert_eq!(POW10_U16.to_vec(), (0..5).map(|n| 10u16.pow(n)).collect::<Vec<_>>());
+    assert_eq!(POW10_U32.to_vec(), (0..10).map(|n| 10u32.pow(n)).collect::<Vec<_>>());
+    assert_eq!(POW10_U64.to_vec(), (0..20).map(|n| 10u64.pow(n)).collect::<Vec<_>>());
+    assert_eq!(POW10_U128.to_vec(), (0..39).map(|n| 10u128.pow(n)).collect::<Vec<_>>());
+    // 表中最后一项再乘 10 就会溢出
+    assert_eq!(None, POW10_U128[38].checked_mul(10));
+
+    assert_eq!(Some(1000), pow10_u64(3));
+    assert_eq!(None, pow10_u64(20));
+    for n in [0u64, 9, 10, 99, 100, 12345, u64::MAX] {
+        assert_eq!(n.to_string().len() as u32, decimal_digits(n));
+    }
+}
+
+#[test]
+fn test_popcount_matches_runtime() {
+    for (i, count) in POPCOUNT.iter().enumerate() {
+        assert_eq!((i as u8).count_ones(), *count as u32);
+    }
+    for n in [0u64, 1, 0xF0F0, u64::MAX, 0x8000_0000_0000_0001] {
+        assert_eq!(n.count_ones(), const_tables::popcount(n));
+    }
+}
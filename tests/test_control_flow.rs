@@ -1,3 +1,12 @@
+use std::time::Duration;
+
+use learn_rust::flow_graph::FlowGraph;
+use learn_rust::loop_trace::LoopTrace;
+use learn_rust::ranges::{FloatRange, IntRange};
+use learn_rust::retry::{Backoff, ManualClock, Retry};
+use learn_rust::rules::{Fallback, Mode, RuleSet};
+use learn_rust::trace_loop;
+
 
 
 #[test]
@@ -29,6 +38,14 @@ fn test_if_else_if() {
     } else {
         println!("number is not divisible by 4, 3, or 2");
     }
+
+    // 同样的判断写成规则：FirstMatch 模式与 if / else if 一样，只取第一条满足的规则
+    let rules = RuleSet::new(Mode::FirstMatch)
+        .divisible_by("number is divisible by 4", 4)
+        .divisible_by("number is divisible by 3", 3)
+        .divisible_by("number is divisible by 2", 2)
+        .fallback(Fallback::Label("number is not divisible by 4, 3, or 2".to_string()));
+    assert_eq!(Some("number is divisible by 3".to_string()), rules.classify(number));
 }
 
 #[test]
@@ -78,6 +95,30 @@ fn test_nest_loop() {
     }
 
     assert_eq!(3, nest_loop());
+
+    // 用 trace_loop! 跟踪同样的循环，可以看到每一步的执行过程，以及 count = 4 从未执行
+    let trace = LoopTrace::new();
+    let mut count = 0;
+    // 和上面一样，'inner 没有用到，count = 4 执行不到
+    #[allow(unreachable_code, unused_labels)]
+    {
+        trace_loop!(trace, [count], 'outer: loop {
+            count = 2;
+            'inner: loop {
+                count = 3;
+                break 'outer;
+            }
+            count = 4;
+        });
+    }
+    println!("{}", trace);
+    assert_eq!(3, count);
+    assert_eq!(vec!["count = 4"], trace.never_executed());
+
+    // 画出 nest_loop 的控制流图：break 'outer 直接跳到 return count，count = 4 所在的块没有入边
+    let graph = FlowGraph::from_function(include_str!("test_control_flow.rs"), "nest_loop").unwrap();
+    println!("{}", graph.render_ascii());
+    assert_eq!(graph.block_of("count = 4").into_iter().collect::<Vec<_>>(), graph.unreachable());
 }
 
 #[test]
@@ -92,6 +133,18 @@ fn test_return_from_loop() {
     };
 
     assert_eq!(20, result);
+
+    // 同样的写法可以用来重试：成功时 break 带出结果，次数用完时带出最后一个错误
+    let clock = ManualClock::new();
+    let outcome = Retry::new(5).backoff(Backoff::Fixed(Duration::from_millis(100))).run(&clock, |attempt| {
+        if attempt < 3 {
+            Err("not ready")
+        } else {
+            Ok(attempt * 2)
+        }
+    });
+    assert_eq!(Ok(6), outcome.result);
+    assert_eq!(3, outcome.stats.attempts);
 }
 
 #[test]
@@ -136,5 +189,17 @@ fn test_range() {
         num = n;
     }
     assert_eq!(100, num);
+
+    // 步长可以是负数，倒着取：100, 75, 50, 25, 0
+    let mut values = Vec::new();
+    for n in IntRange::inclusive(100, 0, -25).unwrap() {
+        values.push(n);
+    }
+    assert_eq!(vec![100, 75, 50, 25, 0], values);
+
+    // 浮点数的范围：每个值直接计算，不会因为反复累加 0.1 而产生误差
+    let points = FloatRange::inclusive(0.0, 1.0, 0.1).unwrap();
+    assert_eq!(11, points.len());
+    assert_eq!(Some(1.0), points.last());
 }
 
@@ -94,6 +94,11 @@ fn test_struct_use() {
     let Pair(integer, decimal) = pair;
     assert_eq!(integer, pair.0);
     assert_eq!(decimal, pair.1);
+
+    // 结构体的大小：两个 f32 正好 8 个字节，没有填充
+    // 字段偏移、填充与 niche 可以用 `learn_rust layout Point` 查看
+    assert_eq!(8, std::mem::size_of::<Point>());
+    assert_eq!(0, std::mem::size_of::<Nil>());
 }
 
 
@@ -0,0 +1,83 @@
+use learn_rust::bitwise::Operand;
+use learn_rust::division::{self, Outcome};
+use learn_rust::int_type::IntType;
+
+// 按表达式取出结果
+fn outcome(rows: &[division::Row], expr: &str) -> Outcome {
+    rows.iter().find(|r| r.expr == expr).unwrap().outcome.clone()
+}
+
+fn value(s: &str) -> Outcome {
+    Outcome::Value(s.to_string())
+}
+
+#[test]
+fn test_division_negative_operands() {
+    // (a, b, a / b, a % b, div_euclid, rem_euclid, floor, ceil)
+    let cases = [
+        ("7", "2", "3", "1", "3", "1", "3", "4"),
+        ("-7", "2", "-3", "-1", "-4", "1", "-4", "-3"),
+        ("7", "-2", "-3", "1", "-3", "1", "-4", "-3"),
+        ("-7", "-2", "3", "-1", "4", "1", "3", "4"),
+        ("-8", "2", "-4", "0", "-4", "0", "-4", "-4"),
+    ];
+    for (a, b, div, rem, div_euclid, rem_euclid, floor, ceil) in cases {
+        let rows = division::explore_str(IntType::I32, a, b).unwrap();
+        assert_eq!(value(div), outcome(&rows, "a / b"), "{} / {}", a, b);
+        assert_eq!(value(rem), outcome(&rows, "a % b"), "{} % {}", a, b);
+        assert_eq!(value(div_euclid), outcome(&rows, "a.div_euclid(b)"), "{} div_euclid {}", a, b);
+        assert_eq!(value(rem_euclid), outcome(&rows, "a.rem_euclid(b)"), "{} rem_euclid {}", a, b);
+        assert_eq!(value(floor), outcome(&rows, "floor(a / b)"), "floor {} / {}", a, b);
+        assert_eq!(value(ceil), outcome(&rows, "ceil(a / b)"), "ceil {} / {}", a, b);
+    }
+}
+
+#[test]
+fn test_division_panics() {
+    // MIN / -1 溢出
+    let rows = division::explore_str(IntType::I8, "MIN", "-1").unwrap();
+    assert_eq!(Outcome::Panic("attempt to divide with overflow"), outcome(&rows, "a / b"));
+    assert_eq!(Outcome::Panic("attempt to calculate the remainder with overflow"), outcome(&rows, "a % b"));
+    assert!(outcome(&rows, "a.div_euclid(b)").is_panic());
+    assert_eq!(value("-128"), outcome(&rows, "a.wrapping_div(b)"));
+    assert_eq!(Outcome::Float { value: 128.0, lossy: false }, outcome(&rows, "a as f64 / b as f64"));
+
+    // 除数为 0
+    let rows = division::explore_str(IntType::U32, "7", "0").unwrap();
+    assert_eq!(Outcome::Panic("attempt to divide by zero"), outcome(&rows, "a / b"));
+    assert!(rows.iter().filter(|r| r.expr != "a as f64 / b as f64").all(|r| r.outcome.is_panic()));
+    assert_eq!(Outcome::Float { value: f64::INFINITY, lossy: false }, outcome(&rows, "a as f64 / b as f64"));
+}
+
+#[test]
+fn test_division_float_precision() {
+    let rows = division::explore_str(IntType::U64, "MAX", "3").unwrap();
+    assert!(matches!(outcome(&rows, "a as f64 / b as f64"), Outcome::Float { lossy: true, .. }));
+    let rows = division::explore_str(IntType::I64, "64", "5").unwrap();
+    assert_eq!(Outcome::Float { value: 12.8, lossy: false }, outcome(&rows, "a as f64 / b as f64"));
+}
+
+#[test]
+fn test_division_matches_float_floor_ceil() {
+    // i8 的所有组合，floor 与 ceil 和浮点计算的结果一致
+    for a in i8::MIN..=i8::MAX {
+        for b in i8::MIN..=i8::MAX {
+            if b == 0 || (a == i8::MIN && b == -1) {
+                continue;
+            }
+            let x = Operand::from_i128(IntType::I8, a as i128);
+            let y = Operand::from_i128(IntType::I8, b as i128);
+            let rows = division::explore(x, y);
+            let exact = a as f64 / b as f64;
+            assert_eq!(value(&(exact.floor() as i8).to_string()), outcome(&rows, "floor(a / b)"));
+            assert_eq!(value(&(exact.ceil() as i8).to_string()), outcome(&rows, "ceil(a / b)"));
+        }
+    }
+}
+
+#[test]
+fn test_division_render() {
+    let text = division::render(Operand::parse(IntType::I32, "-7").unwrap(), Operand::parse(IntType::I32, "2").unwrap());
+    assert!(text.contains("a.rem_euclid(b)"));
+    assert!(text.contains("-3.5"));
+}
@@ -0,0 +1,126 @@
+use learn_rust::cast::{NumType, Value};
+use learn_rust::endian::{self, ByteReader, ByteRepr, ByteWriter, BytesError, Endian};
+
+#[test]
+fn test_int_bytes() {
+    assert_eq!([0x12, 0x34, 0x56, 0x78], 0x12345678u32.to_bytes(Endian::Big));
+    assert_eq!([0x78, 0x56, 0x34, 0x12], 0x12345678u32.to_bytes(Endian::Little));
+    assert_eq!(0x12345678u32.to_ne_bytes(), 0x12345678u32.to_bytes(Endian::Native));
+    assert_eq!([0xff, 0xfe], (-2i16).to_bytes(Endian::Big));
+    assert_eq!(Ok(-2i16), i16::from_bytes([0xfe, 0xff], Endian::Little));
+    assert_eq!(16, <u128 as ByteRepr>::SIZE);
+    assert_eq!(std::mem::size_of::<usize>(), usize::MAX.to_bytes(Endian::Big).len());
+
+    // 本机字节序一定是大端或小端之一
+    let native = 1u16.to_bytes(Endian::Native);
+    let resolved = Endian::Native.resolve();
+    assert_eq!(native, 1u16.to_bytes(resolved));
+    assert_ne!(Endian::Native, resolved);
+}
+
+#[test]
+fn test_float_bool_char_bytes() {
+    // 0.1 的 IEEE 754 表示
+    assert_eq!([0x3f, 0xb9, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a], 0.1f64.to_bytes(Endian::Big));
+    assert_eq!([0x00, 0x00, 0x80, 0x3f], 1.0f32.to_bytes(Endian::Little));
+    let nan = f64::from_bytes(f64::NAN.to_bytes(Endian::Big), Endian::Big).unwrap();
+    assert!(nan.is_nan());
+
+    assert_eq!([1], true.to_bytes(Endian::Big));
+    assert_eq!(Ok(false), bool::from_bytes([0], Endian::Little));
+    assert_eq!(Err(BytesError::InvalidBool(2)), bool::from_bytes([2], Endian::Little));
+
+    assert_eq!([0x00, 0x00, 0x4e, 0x2d], '中'.to_bytes(Endian::Big));
+    assert_eq!(Ok('中'), char::from_bytes([0x2d, 0x4e, 0x00, 0x00], Endian::Little));
+    // 代理区与超过 U+10FFFF 的码点都不是合法的 char
+    assert_eq!(Err(BytesError::InvalidChar(0xD800)), char::from_bytes([0x00, 0x00, 0xd8, 0x00], Endian::Big));
+    assert_eq!(Err(BytesError::InvalidChar(0x110000)), char::from_slice(&[0x00, 0x11, 0x00, 0x00], Endian::Big));
+
+    assert_eq!(Err(BytesError::WrongLength { expected: 4, actual: 3 }), u32::from_slice(&[1, 2, 3], Endian::Big));
+}
+
+#[test]
+fn test_round_trip() {
+    for endian in [Endian::Big, Endian::Little, Endian::Native] {
+        for value in [i64::MIN, -1, 0, 1, i64::MAX] {
+            assert_eq!(Ok(value), i64::from_bytes(value.to_bytes(endian), endian));
+        }
+        for value in [f32::MIN, -0.0, 0.1, f32::INFINITY] {
+            let back = f32::from_bytes(value.to_bytes(endian), endian).unwrap();
+            assert_eq!(value.to_bits(), back.to_bits());
+        }
+        for value in ['\0', 'A', 'é', '🦀', char::MAX] {
+            assert_eq!(Ok(value), char::from_bytes(value.to_bytes(endian), endian));
+        }
+    }
+}
+
+#[test]
+fn test_reader_and_writer() {
+    // 一个简单的报文：u16 长度、u32 编号、bool 标志、char
+    let mut writer = ByteWriter::new(Endian::Big);
+    writer.write(3u16).write(0xdeadbeefu32).write(true).write('中');
+    let bytes = writer.into_bytes();
+    assert_eq!(2 + 4 + 1 + 4, bytes.len());
+    assert_eq!("00 03 de ad be ef 01 00 00 4e 2d", endian::hex(&bytes));
+
+    let mut reader = ByteReader::new(&bytes, Endian::Big);
+    assert_eq!(Ok(3u16), reader.read());
+    assert_eq!(Ok(0xdeadbeefu32), reader.read());
+    assert_eq!(Ok(true), reader.read());
+    assert_eq!(7, reader.position());
+    // 字节不够时返回错误，位置不变
+    assert_eq!(Err(BytesError::UnexpectedEof { offset: 7, needed: 8, available: 4 }), reader.read::<u64>());
+    assert_eq!(Ok('中'), reader.read());
+    assert!(reader.is_empty());
+    assert!(reader.read::<u8>().is_err());
+
+    // 读取失败的值不会前进
+    let mut reader = ByteReader::new(&[1, 5, 0], Endian::Little);
+    assert_eq!(Ok(vec![true]), reader.read_many::<bool>(1));
+    assert_eq!(Err(BytesError::InvalidBool(5)), reader.read::<bool>());
+    assert_eq!(1, reader.position());
+    assert_eq!(Ok(&[5u8, 0][..]), reader.read_bytes(2));
+}
+
+#[test]
+fn test_read_all() {
+    let values = [1u16, 2, 0xabcd];
+    let bytes = endian::write_all(&values, Endian::Little);
+    assert_eq!(vec![1, 0, 2, 0, 0xcd, 0xab], bytes);
+    assert_eq!(Ok(values.to_vec()), endian::read_all::<u16>(&bytes, Endian::Little));
+    assert_eq!(Ok(vec![0x0100, 0x0200, 0xcdab]), endian::read_all::<u16>(&bytes, Endian::Big));
+    assert_eq!(
+        Err(BytesError::TrailingBytes { offset: 4, count: 2 }),
+        endian::read_all::<u32>(&bytes, Endian::Little)
+    );
+    assert_eq!(Ok(Vec::<u64>::new()), endian::read_all::<u64>(&[], Endian::Big));
+}
+
+#[test]
+fn test_hexdump() {
+    let bytes: Vec<u8> = (b'A'..=b'R').collect();
+    assert_eq!(
+        "00000000  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|\n\
+         00000010  51 52                                             |QR|\n",
+        endian::hexdump(&bytes)
+    );
+    assert_eq!("", endian::hexdump(&[]));
+    assert!(endian::hexdump(&[0, 0x7f, b' ']).ends_with("|.. |\n"));
+
+    let value = Value::parse(NumType::F32, "1").unwrap();
+    assert_eq!(vec![0x3f, 0x80, 0, 0], endian::value_to_bytes(value, Endian::Big));
+    let out = endian::render("u16 258", |e| 258u16.to_bytes(e).to_vec());
+    assert!(out.contains("big             01 02"));
+    assert!(out.contains("little          02 01"));
+}
+
+#[test]
+fn test_reader_huge_length() {
+    let mut reader = ByteReader::new(&[1, 2, 3], Endian::Big);
+    reader.read::<u8>().unwrap();
+    assert_eq!(
+        Err(BytesError::UnexpectedEof { offset: 1, needed: usize::MAX, available: 2 }),
+        reader.read_bytes(usize::MAX)
+    );
+}
@@ -0,0 +1,164 @@
+use learn_rust::flow_graph::{self, BlockKind, Edge, EdgeKind, FlowError, FlowGraph};
+
+const LESSONS: &str = r#"
+fn function_loop() -> i32{
+    let mut count = 0;
+    loop {
+        count += 1;
+        if count == 100 {
+            break;
+        }
+    }
+    return count;
+}
+
+fn nest_loop() -> i32 {
+    let mut count = 0;
+    'outer: loop {
+        count = 2;
+        'inner: loop {
+            count = 3;
+            break 'outer;   // 退出 'outer 的循环
+        }
+        count = 4; // 这行不会执行
+    }
+    return count;
+}
+
+fn if_else_if(number: i32) {
+    if number % 4 == 0 {
+        println!("number is divisible by 4");
+    } else if number % 3 == 0 {
+        println!("number is divisible by 3");
+    } else {
+        println!("number is not divisible by 4 or 3");
+    }
+}
+
+fn rows() -> Vec<(i32, char)> {
+    let mut pairs = Vec::new();
+    'rows: for i in 0..3 {
+        let mut j = 0;
+        while j < 3 {
+            match j {
+                0 => pairs.push((i, 'a')),
+                1 if i == 2 => return pairs,
+                _ => {
+                    continue 'rows;
+                }
+            }
+            j += 1;
+        }
+    }
+    pairs
+}
+"#;
+
+fn edge(graph: &FlowGraph, from: usize, to: usize) -> Option<&Edge> {
+    graph.edges.iter().find(|e| e.from == from && e.to == to)
+}
+
+#[test]
+fn test_nest_loop() {
+    let graph = FlowGraph::from_function(LESSONS, "nest_loop").unwrap();
+    let four = graph.block_of("count = 4").unwrap();
+    let three = graph.block_of("count = 3").unwrap();
+    let ret = graph.block_of("return count").unwrap();
+
+    // count = 4 所在的块没有入边
+    assert_eq!(vec![four], graph.unreachable());
+    assert_eq!(0, graph.predecessors(four).count());
+    assert_eq!(vec!["count = 3", "break 'outer"], graph.blocks[three].statements);
+    assert_eq!(Some(EdgeKind::Break(Some("'outer".to_string()))), edge(&graph, three, ret).map(|e| e.kind.clone()));
+
+    // count = 4 之后回到 'outer 的开头
+    let outer = graph.blocks.iter().position(|b| b.kind == BlockKind::Loop("'outer: loop".to_string())).unwrap();
+    assert!(edge(&graph, four, outer).unwrap().is_back());
+    assert_eq!(Some(EdgeKind::Return), edge(&graph, ret, graph.exit()).map(|e| e.kind.clone()));
+}
+
+#[test]
+fn test_function_loop() {
+    let graph = FlowGraph::from_function(LESSONS, "function_loop").unwrap();
+    let branch = graph.blocks.iter().position(|b| b.kind == BlockKind::Branch("if count == 100".to_string())).unwrap();
+    let head = graph.blocks.iter().position(|b| b.kind == BlockKind::Loop("loop".to_string())).unwrap();
+    let brk = graph.block_of("break").unwrap();
+
+    assert_eq!(EdgeKind::True, edge(&graph, branch, brk).unwrap().kind);
+    // 条件不成立时回到循环开头
+    let back = edge(&graph, branch, head).unwrap();
+    assert_eq!(EdgeKind::False, back.kind);
+    assert!(back.is_back());
+    assert!(graph.unreachable().is_empty());
+}
+
+#[test]
+fn test_if_else_if() {
+    let graph = FlowGraph::from_function(LESSONS, "if_else_if").unwrap();
+    let branches: Vec<&BlockKind> = graph.blocks.iter().map(|b| &b.kind).filter(|k| matches!(k, BlockKind::Branch(_))).collect();
+    assert_eq!(2, branches.len());
+
+    // 三个分支最后都到达出口
+    let exit = graph.exit();
+    assert_eq!(3, graph.predecessors(exit).count());
+    let last = graph.block_of(r#"println!("number is not divisible by 4 or 3")"#).unwrap();
+    assert!(edge(&graph, last, exit).is_some());
+}
+
+#[test]
+fn test_for_while_match() {
+    let graph = FlowGraph::from_function(LESSONS, "rows").unwrap();
+    let kinds: Vec<EdgeKind> = graph.edges.iter().map(|e| e.kind.clone()).collect();
+    for kind in [EdgeKind::Next, EdgeKind::Done, EdgeKind::True, EdgeKind::False, EdgeKind::Return] {
+        assert!(kinds.contains(&kind), "{:?}", kind);
+    }
+    assert!(kinds.contains(&EdgeKind::Arm("1 if i == 2".to_string())));
+
+    // continue 'rows 跳过了 while，回到 for 的开头
+    let rows = graph.blocks.iter().position(|b| b.kind == BlockKind::Loop("'rows: for i in 0..3".to_string())).unwrap();
+    let cont = graph.block_of("continue 'rows").unwrap();
+    assert_eq!(EdgeKind::Continue(Some("'rows".to_string())), edge(&graph, cont, rows).unwrap().kind);
+    // for 结束后执行最后的 pairs，然后到达出口
+    let tail = graph.block_of("pairs").unwrap();
+    assert_eq!(EdgeKind::Done, edge(&graph, rows, tail).unwrap().kind);
+}
+
+#[test]
+fn test_output() {
+    let graph = FlowGraph::from_function(LESSONS, "nest_loop").unwrap();
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph \"nest_loop\" {"));
+    assert!(dot.contains("label=\"break 'outer\", color=red"));
+    assert!(dot.contains("fillcolor=lightgrey"));
+
+    let graph = FlowGraph::from_function(LESSONS, "if_else_if").unwrap();
+    assert!(graph.to_dot().contains(r#"println!(\"number is divisible by 4\")\l"#));
+
+    let graph = FlowGraph::from_function(LESSONS, "function_loop").unwrap();
+    let ascii = graph.render_ascii();
+    assert!(ascii.starts_with("fn function_loop\n"));
+    assert!(ascii.contains("B2  loop"));
+    assert!(ascii.contains("-> B2  false  (back)"));
+    // 回边在左侧画出连线，指向循环开头
+    assert!(ascii.lines().any(|line| line.ends_with("> B2  loop")));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(Err(FlowError::FunctionNotFound("missing".to_string())), FlowGraph::from_function(LESSONS, "missing"));
+    assert_eq!(
+        Err(FlowError::UnknownLabel { line: 1, label: "'b".to_string() }),
+        FlowGraph::from_function("fn f() { 'a: loop { break 'b; } }", "f")
+    );
+    assert_eq!(
+        Err(FlowError::OutsideLoop { line: 2, keyword: "continue".to_string() }),
+        FlowGraph::from_function("fn f() {\n continue; }", "f")
+    );
+    assert_eq!(Err(FlowError::UnbalancedDelimiter { line: 1 }), FlowGraph::from_function("fn f() { (] }", "f"));
+    assert_eq!(Err(FlowError::UnterminatedLiteral { line: 1 }), FlowGraph::from_function("fn f() { \"abc }", "f"));
+
+    // 字符、字符串与注释中的括号不影响解析
+    let graph = FlowGraph::from_function("fn f<'a>(s: &'a str) -> char { let c = '{'; /* } */ let t = \"}\"; '\\'' }", "f");
+    assert_eq!(vec!["let c = '{'", "let t = \"}\"", "'\\''"], graph.unwrap().blocks[1].statements);
+    assert_eq!(vec!["function_loop", "nest_loop", "if_else_if", "rows"], flow_graph::function_names(LESSONS).unwrap());
+}
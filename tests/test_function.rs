@@ -1,3 +1,6 @@
+use learn_rust::call_trace::trace;
+use learn_rust::numeric::{self, Narrow};
+
 
 
 
@@ -29,6 +32,9 @@ fn test_function_declare() {
 
 #[test]
 fn test_function_params_type() {
+    // 打开 trace feature 后可以看到每次调用的参数和返回值：
+    // cargo test --features trace --test test_function -- --nocapture
+    #[trace]
     fn add(x:i32, y:i32) -> i32 {
         return x + y;
     }
@@ -36,5 +42,19 @@ fn test_function_params_type() {
 
     let a: i8 = 3;
     assert_eq!(5, add(2, a as i32)); // 需要将类型做转换
+
+    // i8 一定能放进 i32，这种无损转换也可以用 From，比 `as` 更安全
+    assert_eq!(5, add(2, i32::from(a)));
+    // 放不下时 `as` 会截断，而 TryFrom 会返回错误，运行 `learn_rust cast` 可以查看所有类型之间的转换
+    assert_eq!(44, 300i32 as u8);
+    assert!(u8::try_from(300i32).is_err());
+
+    // 用 trait 约束的泛型函数可以接受所有的数值类型，两个参数会无损转换到同一个类型，不需要手动转换
+    assert_eq!(5, numeric::add(2, a));
+    assert_eq!(5.5, numeric::add(2.5f32, a));
+    assert_eq!(Some(6), numeric::sum([1i8, 2, 3]));
+    // 反过来收窄可能放不下，需要明确选择：返回 None、截断或者取边界值
+    let b: Option<i8> = numeric::add(2i32, a).narrow_checked();
+    assert_eq!(Some(5), b);
 }
 
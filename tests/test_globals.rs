@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use learn_rust::globals::{self, race, Counter, Shared};
+
+const THREADS: usize = 8;
+const ITERATIONS: usize = 10_000;
+
+#[test]
+fn test_counter_is_exact() {
+    static COUNT: Counter = Counter::new(0);
+    globals::hammer(THREADS, ITERATIONS, || {
+        COUNT.increment();
+    });
+    assert_eq!((THREADS * ITERATIONS) as u64, COUNT.get());
+    assert_eq!((THREADS * ITERATIONS) as u64, COUNT.reset());
+    assert_eq!(0, COUNT.get());
+    assert_eq!(5, COUNT.add(5));
+}
+
+#[test]
+fn test_shared_is_exact() {
+    static TOTAL: Shared<(u64, Vec<usize>)> = Shared::new((0, Vec::new()));
+    globals::hammer(THREADS, ITERATIONS, || {
+        TOTAL.with(|(count, seen)| {
+            *count += 1;
+            if *count % 1000 == 0 {
+                seen.push(*count as usize);
+            }
+        });
+    });
+    let (count, seen) = TOTAL.get();
+    assert_eq!((THREADS * ITERATIONS) as u64, count);
+    assert_eq!((1..=80).map(|i| i * 1000).collect::<Vec<_>>(), seen);
+    assert_eq!((count, seen), TOTAL.replace((0, Vec::new())));
+}
+
+#[test]
+fn test_unique_ids_and_once() {
+    let ids = Mutex::new(Vec::new());
+    globals::hammer(THREADS, 1000, || {
+        let id = globals::next_id();
+        ids.lock().unwrap().push(id);
+    });
+    let ids = ids.into_inner().unwrap();
+    let unique: HashSet<u64> = ids.iter().copied().collect();
+    assert_eq!(ids.len(), unique.len());
+
+    // OnceLock 只会初始化一次，即使多个线程同时访问
+    static INITS: Counter = Counter::new(0);
+    static VALUE: OnceLock<u64> = OnceLock::new();
+    globals::hammer(THREADS, 100, || {
+        let value = VALUE.get_or_init(|| INITS.increment() * 42);
+        assert_eq!(42, *value);
+    });
+    assert_eq!(1, INITS.get());
+    assert_eq!(globals::start_time(), globals::start_time());
+}
+
+#[test]
+fn test_race_loses_updates() {
+    // 两个线程都读到 0 再写回，结果是 1 而不是 2
+    assert_eq!(1, race::lost_update());
+
+    // 分开读写时结果不会超过正确的值，通常会少；具体少多少每次都不同
+    let total = race::racy_total(THREADS, ITERATIONS);
+    assert!(total <= (THREADS * ITERATIONS) as u64);
+    assert!(total > 0);
+}
+
+#[test]
+fn test_poisoned_shared() {
+    static LOG: Shared<Vec<&str>> = Shared::new(Vec::new());
+    let result = std::thread::spawn(|| {
+        LOG.with(|log| {
+            log.push("before panic");
+            panic!("boom");
+        })
+    })
+    .join();
+    assert!(result.is_err());
+    // 锁被 poisoned 后仍然可以使用
+    LOG.with(|log| log.push("after panic"));
+    assert_eq!(vec!["before panic", "after panic"], LOG.get());
+}
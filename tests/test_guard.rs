@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use learn_rust::guard::{self, ScopeTimer, When};
+use learn_rust::metrics::Registry;
+use learn_rust::{defer, defer_on_success, defer_on_unwind};
+
+// 执行 f，返回是否 panic 了。panic hook 是整个进程共用的，测试并行运行，所以不替换它，panic 的消息会打印出来
+fn catch(f: impl FnOnce()) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(f)).is_err()
+}
+
+#[test]
+fn test_drop_order() {
+    let log = RefCell::new(Vec::new());
+    {
+        let _outer = guard::guard(|| log.borrow_mut().push("outer"));
+        defer! { log.borrow_mut().push("first defer"); }
+        {
+            defer! { log.borrow_mut().push("inner"); }
+            log.borrow_mut().push("inner body");
+        }
+        defer!(log.borrow_mut().push("second defer"));
+        log.borrow_mut().push("body");
+    }
+    // 内层 scope 先结束；同一个 scope 中后声明的先执行
+    assert_eq!(vec!["inner body", "inner", "body", "second defer", "first defer", "outer"], log.into_inner());
+}
+
+#[test]
+fn test_early_exit() {
+    fn find(log: &RefCell<Vec<String>>, items: &[i32], target: i32) -> Option<usize> {
+        defer! { log.borrow_mut().push(format!("searched for {}", target)); }
+        for (i, item) in items.iter().enumerate() {
+            defer! { log.borrow_mut().push(format!("checked {}", i)); }
+            if *item == target {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    let log = RefCell::new(Vec::new());
+    assert_eq!(Some(1), find(&log, &[5, 7, 9], 7));
+    assert_eq!(vec!["checked 0", "checked 1", "searched for 7"], log.into_inner());
+}
+
+#[test]
+fn test_panic() {
+    let log = RefCell::new(Vec::new());
+    let panicked = catch(|| {
+        defer! { log.borrow_mut().push("always"); }
+        defer_on_success! { log.borrow_mut().push("success"); }
+        defer_on_unwind! { log.borrow_mut().push("rollback"); }
+        {
+            defer! { log.borrow_mut().push("inner"); }
+            panic!("boom");
+        }
+    });
+    assert!(panicked);
+    assert_eq!(vec!["inner", "rollback", "always"], log.take());
+
+    // 没有 panic 时，只在展开时执行的守卫不会执行
+    let panicked = catch(|| {
+        defer_on_success! { log.borrow_mut().push("success"); }
+        defer_on_unwind! { log.borrow_mut().push("rollback"); }
+    });
+    assert!(!panicked);
+    assert_eq!(vec!["success"], log.take());
+}
+
+#[test]
+fn test_dismiss() {
+    let log = RefCell::new(Vec::new());
+    let transfer = |fail: bool| {
+        log.borrow_mut().push("debit");
+        let rollback = guard::guard(|| log.borrow_mut().push("refund"));
+        if fail {
+            return;
+        }
+        log.borrow_mut().push("credit");
+        // 成功后撤销回滚
+        rollback.dismiss();
+    };
+    transfer(false);
+    transfer(true);
+    assert_eq!(vec!["debit", "credit", "debit", "refund"], log.into_inner());
+
+    let g = guard::on_unwind(|| {});
+    assert_eq!(When::OnUnwind, g.when());
+    assert_eq!("ScopeGuard { when: OnUnwind, armed: true }", format!("{:?}", g));
+}
+
+#[test]
+fn test_scope_timer() {
+    let reports = RefCell::new(Vec::new());
+    {
+        let _timer = ScopeTimer::with("outer", |label, elapsed| reports.borrow_mut().push((label.to_string(), elapsed)));
+        {
+            let _timer = ScopeTimer::with("inner", |label, elapsed| reports.borrow_mut().push((label.to_string(), elapsed)));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        ScopeTimer::with("dismissed", |_, _| panic!("dismissed timer must not report")).dismiss();
+    }
+    let reports = reports.into_inner();
+    assert_eq!(vec!["inner", "outer"], reports.iter().map(|(label, _)| label.as_str()).collect::<Vec<_>>());
+    assert!(reports[0].1 >= Duration::from_millis(5));
+    assert!(reports[1].1 >= reports[0].1);
+
+    // panic 时也会报告
+    let registry = Registry::new();
+    let histogram = registry.histogram("section_seconds", "", &[1.0]).unwrap();
+    assert!(catch(|| {
+        let _timer = ScopeTimer::histogram("section", &histogram);
+        panic!("boom");
+    }));
+    assert_eq!(1, histogram.count());
+}
@@ -0,0 +1,89 @@
+use learn_rust::bigint::BigInt;
+use learn_rust::int_fit::{self, FitError};
+use learn_rust::int_type::IntType;
+
+fn big(n: i128) -> BigInt {
+    BigInt::from(n)
+}
+
+#[test]
+fn test_person_age() {
+    // Person { age: u8 }：年龄在 0 ~ 150 之间
+    let advice = int_fit::advise_range(big(0), big(150)).unwrap();
+    let recommended = advice.recommended().unwrap();
+    assert_eq!(IntType::U8, recommended.ty);
+    assert_eq!(big(105), recommended.headroom_above);
+    assert_eq!(big(0), recommended.headroom_below);
+    // i8 最大只有 127，装不下 150
+    assert_eq!(IntType::I16, advice.signed.as_ref().unwrap().ty);
+}
+
+#[test]
+fn test_advise_samples() {
+    let advice = int_fit::advise([27, 3, 99]).unwrap();
+    assert_eq!((big(3), big(99)), (advice.min.clone(), advice.max.clone()));
+    // 位数相同时优先无符号
+    assert_eq!(IntType::U8, advice.recommended().unwrap().ty);
+    assert_eq!(IntType::I8, advice.signed.unwrap().ty);
+
+    // 有负数时只能用有符号类型
+    let advice = int_fit::advise([-5, 40000]).unwrap();
+    assert_eq!(None, advice.unsigned);
+    assert_eq!(IntType::I32, advice.recommended().unwrap().ty);
+
+    // 有符号类型位数更少时推荐有符号
+    let advice = int_fit::advise([-1, 0]).unwrap();
+    assert_eq!(IntType::I8, advice.recommended().unwrap().ty);
+
+    // 边界值正好装下，余量为 0
+    let advice = int_fit::advise([i8::MIN as i128, i8::MAX as i128]).unwrap();
+    let fit = advice.recommended().unwrap();
+    assert_eq!((IntType::I8, big(0), big(0)), (fit.ty, fit.headroom_below.clone(), fit.headroom_above.clone()));
+    assert_eq!(100.0, fit.percent_of_max());
+
+    let advice = int_fit::advise([u64::MAX as u128 + 1]).unwrap();
+    assert_eq!(IntType::U128, advice.recommended().unwrap().ty);
+    assert_eq!(IntType::I128, advice.signed.unwrap().ty);
+
+    // 超出 128 位就没有合适的类型了
+    let huge = BigInt::from(u128::MAX) + BigInt::one();
+    let advice = int_fit::advise([huge]).unwrap();
+    assert_eq!(None, advice.recommended());
+    assert!(int_fit::render(&advice).contains("BigInt"));
+
+    assert_eq!(Err(FitError::Empty), int_fit::advise(Vec::<i32>::new()));
+    assert_eq!(Err(FitError::InvalidRange { min: big(2), max: big(1) }), int_fit::advise_range(big(2), big(1)));
+}
+
+#[test]
+fn test_advise_csv() {
+    let csv = "name,age,balance\n\
+               Peter,27,-300\n\
+               \"Doe, John\",150,\"1_000_000\"\n\
+               \n\
+               Anna,,12\n";
+    let advice = int_fit::advise_csv(csv, "age").unwrap();
+    assert_eq!((big(27), big(150)), (advice.min.clone(), advice.max.clone()));
+    assert_eq!(IntType::U8, advice.recommended().unwrap().ty);
+
+    // 列号从 0 开始
+    let advice = int_fit::advise_csv(csv, "2").unwrap();
+    assert_eq!((big(-300), big(1_000_000)), (advice.min.clone(), advice.max.clone()));
+    assert_eq!(IntType::I32, advice.recommended().unwrap().ty);
+
+    assert_eq!(Err(FitError::ColumnNotFound("height".to_string())), int_fit::advise_csv(csv, "height"));
+    assert_eq!(
+        Err(FitError::InvalidValue { line: 2, value: "1.5".to_string() }),
+        int_fit::advise_csv("age\n1.5\n", "age")
+    );
+    assert_eq!(Err(FitError::Empty), int_fit::advise_csv("age\n", "age"));
+    assert_eq!(Err(FitError::Empty), int_fit::advise_csv("", "age"));
+}
+
+#[test]
+fn test_render_fit() {
+    let out = int_fit::render(&int_fit::advise([0, 150]).unwrap());
+    assert!(out.starts_with("min = 0, max = 150"));
+    assert!(out.contains("58.8%"));
+    assert!(out.ends_with("recommended: u8\n"));
+}
@@ -0,0 +1,118 @@
+use learn_rust::layout::{self, Shape, Tag};
+
+#[test]
+fn test_struct_layout() {
+    let point = layout::lookup("Point").unwrap();
+    assert_eq!((8, 4), (point.size, point.align));
+    assert!(point.padding().is_empty());
+
+    let rectangle = layout::lookup("Rectangle").unwrap();
+    assert_eq!((16, 4), (rectangle.size, rectangle.align));
+
+    let pair = layout::lookup("pair").unwrap();
+    assert_eq!(8, pair.size);
+    assert!(!pair.has_niche());
+
+    let nil = layout::lookup("Nil").unwrap();
+    assert_eq!(0, nil.size);
+    assert_eq!(1, nil.option_size);
+    assert!(layout::render(&nil).contains("zero-sized"));
+
+    // Person 有 &str 与 u8 两个字段，大小要向上取整到 &str 的对齐
+    let person = layout::lookup("Person<'a>").unwrap();
+    assert_eq!(person, layout::lookup("Person").unwrap());
+    assert_eq!(std::mem::align_of::<&str>(), person.align);
+    assert_eq!(2 * std::mem::size_of::<usize>() + std::mem::align_of::<usize>(), person.size);
+    let padding: usize = person.padding().iter().map(|r| r.len()).sum();
+    assert_eq!(std::mem::size_of::<usize>() - 1, padding);
+    // 引用不为空，所以 Option<Person> 不会变大
+    assert!(person.has_niche());
+
+    assert_eq!(None, layout::lookup("Vec<u8>"));
+}
+
+#[test]
+fn test_fields_do_not_overlap() {
+    for ty in layout::all() {
+        let groups = match &ty.shape {
+            Shape::Struct(fields) => vec![fields.clone()],
+            Shape::Enum(variants) => variants.iter().map(|v| v.fields.clone()).collect(),
+        };
+        for fields in groups {
+            let mut used = vec![false; ty.size];
+            for field in &fields {
+                assert!(field.offset + field.size <= ty.size, "{} {}", ty.name, field.name);
+                for byte in field.bytes() {
+                    assert!(!used[byte], "{} {} overlaps", ty.name, field.name);
+                    used[byte] = true;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_enum_niche() {
+    // &str 的指针不为空，None 用空指针表示
+    let option = layout::lookup("Option<&str>").unwrap();
+    assert_eq!(std::mem::size_of::<&str>(), option.size);
+    assert_eq!(Some(Tag::Niche { variant: "Some", field: "0".to_string() }), option.tag());
+
+    // u32 的所有取值都合法，需要单独的 tag
+    let option = layout::lookup("Option<u32>").unwrap();
+    assert_eq!(8, option.size);
+    assert!(matches!(option.tag(), Some(Tag::Bytes(bytes)) if !bytes.is_empty()));
+    assert!(!layout::lookup("u32").unwrap().has_niche());
+    assert!(layout::lookup("char").unwrap().has_niche());
+    assert!(layout::lookup("bool").unwrap().has_niche());
+    // Option<T> 这一行比较的是它与 T
+    assert!(option.inner_size == Some(4) && !option.has_niche());
+    assert!(layout::lookup("Option<&str>").unwrap().has_niche());
+    assert!(layout::render_summary().lines().any(|line| line.starts_with("Option<&str>") && line.ends_with("yes")));
+    assert!(layout::render(&layout::lookup("Option<&str>").unwrap()).contains("same size as &str: None uses a niche"));
+
+    // C 风格的枚举只有 tag，没有用到的辨别值就是 niche
+    let number = layout::lookup("Number").unwrap();
+    assert_eq!((1, true), (number.size, number.has_niche()));
+    // 整个值都是 tag
+    assert!(matches!(number.tag(), Some(Tag::Bytes(bytes)) if bytes.len() == 1 && bytes[0] == (0..1)));
+    let color = layout::lookup("Color").unwrap();
+    assert_eq!(std::mem::size_of::<i32>(), color.size);
+    let Shape::Enum(variants) = &color.shape else { panic!("Color is an enum") };
+    assert_eq!(vec!["Red", "Green", "Blue"], variants.iter().map(|v| v.name).collect::<Vec<_>>());
+
+    // Paste(String) 占满了整个 WebEvent，其他取值只能放在 String 的 niche 中
+    let event = layout::lookup("WebEvent").unwrap();
+    assert_eq!(std::mem::size_of::<String>(), event.size);
+    assert_eq!(Some(Tag::Niche { variant: "Paste", field: "0".to_string() }), event.tag());
+    let Shape::Enum(variants) = &event.shape else { panic!("WebEvent is an enum") };
+    let names: Vec<&str> = variants.iter().map(|v| v.name).collect();
+    assert_eq!(vec!["PageLoad", "PageUnload", "KeyPress", "Paste", "Click"], names);
+    assert_eq!("char", variants[2].fields[0].ty);
+    assert_eq!("String", variants[3].fields[0].ty);
+    assert_eq!(None, layout::lookup("Point").unwrap().tag());
+}
+
+#[test]
+fn test_render_layout() {
+    let out = layout::render(&layout::lookup("Point").unwrap());
+    assert!(out.contains("size 8, align 4"));
+    assert!(out.contains("padding: none"));
+    assert!(out.contains("AAAABBBB"));
+    assert!(out.contains("A = x, B = y"));
+
+    let out = layout::render(&layout::lookup("Person").unwrap());
+    assert!(out.contains("None uses a niche"));
+    assert!(out.contains("B......."));
+
+    let out = layout::render(&layout::lookup("WebEvent").unwrap());
+    assert!(out.contains("tag: Paste.0 fills all"));
+    assert!(out.contains("Click"));
+
+    assert_eq!("String", layout::short_type_name::<String>());
+    assert_eq!("Option<Vec<String>>", layout::short_type_name::<Option<Vec<String>>>());
+    assert_eq!("&str", layout::short_type_name::<&str>());
+
+    let summary = layout::render_summary();
+    assert_eq!(layout::all().len() + 2, summary.lines().count());
+}
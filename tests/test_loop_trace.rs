@@ -0,0 +1,138 @@
+use learn_rust::loop_trace::{Event, Exit, LoopKind, LoopTrace};
+use learn_rust::trace_loop;
+
+#[test]
+#[allow(unused_labels, unreachable_code, unused_assignments)]
+fn test_nest_loop_trace() {
+    let trace = LoopTrace::new();
+    let mut count = 0;
+    trace_loop!(trace, [count], 'outer: loop {
+        count = 2;
+        'inner: loop {
+            count = 3;
+            break 'outer;
+        }
+        count = 4;
+    });
+
+    assert_eq!(3, count);
+    assert_eq!(vec!["count = 4"], trace.never_executed());
+    assert_eq!(1, trace.executed("count = 3"));
+    assert_eq!(0, trace.executed("count = 4"));
+
+    let events: Vec<Event> = trace.steps().into_iter().map(|s| s.event).collect();
+    assert!(events.contains(&Event::Break { label: Some("'outer") }));
+    assert!(events.contains(&Event::Exit { label: Some("'inner"), iterations: 1, exit: Exit::OuterBreak("'outer") }));
+    assert!(events.contains(&Event::Exit { label: Some("'outer"), iterations: 1, exit: Exit::Break(Some("'outer")) }));
+
+    // 每一步都记录了 count 当时的值
+    let step = trace.steps().into_iter().find(|s| s.event == Event::Statement("count = 2")).unwrap();
+    assert_eq!(1, step.depth);
+    assert_eq!(vec![("count", "2".to_string())], step.bindings);
+
+    let text = trace.to_string();
+    assert!(text.contains("break 'outer"));
+    assert!(text.contains("never executed:\n    count = 4"));
+}
+
+#[test]
+fn test_break_value() {
+    let trace = LoopTrace::new();
+    let mut count = 0;
+    let result = trace_loop!(trace, [count], loop {
+        count += 1;
+        if count == 10 {
+            break count * 2;
+        }
+    });
+
+    assert_eq!(20, result);
+    assert_eq!(10, trace.executed("count += 1"));
+    assert!(trace.never_executed().is_empty());
+    let last = trace.steps().pop().unwrap();
+    assert_eq!(Event::Exit { label: None, iterations: 10, exit: Exit::Break(None) }, last.event);
+}
+
+#[test]
+fn test_while_and_for() {
+    let trace = LoopTrace::new();
+    let mut number = 3;
+    trace_loop!(trace, [number], while number != 0 {
+        number -= 1;
+    });
+    assert_eq!(0, number);
+    let steps = trace.steps();
+    assert_eq!(Event::Enter { label: None, kind: LoopKind::While }, steps[0].event);
+    assert_eq!(Event::Exit { label: None, iterations: 3, exit: Exit::Finished }, steps.last().unwrap().event);
+
+    let trace = LoopTrace::new();
+    let mut sum = 0;
+    trace_loop!(trace, [sum], for (i, x) in [10, 20, 30].iter().enumerate() {
+        if i == 1 {
+            continue;
+        } else {
+            sum += x;
+        }
+    });
+    assert_eq!(40, sum);
+    assert_eq!(2, trace.executed("sum += x"));
+    assert_eq!(Event::Exit { label: None, iterations: 3, exit: Exit::Finished }, trace.steps().pop().unwrap().event);
+
+    // while let 与 for 一样，条件不满足时正常结束
+    let trace = LoopTrace::new();
+    let mut stack = vec![1, 2];
+    let mut top = 0;
+    trace_loop!(trace, [top], while let Some(x) = stack.pop() {
+        top = x;
+    });
+    assert_eq!(1, top);
+    assert_eq!(Event::Exit { label: None, iterations: 2, exit: Exit::Finished }, trace.steps().pop().unwrap().event);
+}
+
+#[test]
+#[allow(unused_labels)]
+fn test_labelled_continue() {
+    let trace = LoopTrace::new();
+    let mut pairs = Vec::new();
+    trace_loop!(trace, [], 'rows: for i in 0..3 {
+        'cols: for j in 0..3 {
+            if j > i {
+                continue 'rows;
+            }
+            pairs.push((i, j));
+        }
+    });
+
+    assert_eq!(vec![(0, 0), (1, 0), (1, 1), (2, 0), (2, 1), (2, 2)], pairs);
+    assert_eq!(vec![3], trace.iterations("'rows"));
+    // 前两次内层循环被 continue 'rows 打断，最后一次正常结束
+    let exits: Vec<Exit> = trace
+        .steps()
+        .into_iter()
+        .filter_map(|s| match s.event {
+            Event::Exit { label: Some("'cols"), exit, .. } => Some(exit),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec![Exit::OuterContinue("'rows"), Exit::OuterContinue("'rows"), Exit::Finished], exits);
+    assert_eq!(vec![2, 3, 3], trace.iterations("'cols"));
+}
+
+#[test]
+fn test_early_return() {
+    fn find(items: &[i32], target: i32, trace: &LoopTrace) -> Option<usize> {
+        let mut index = 0;
+        trace_loop!(trace, [index], for item in items {
+            if *item == target {
+                return Some(index);
+            }
+            index += 1;
+        });
+        None
+    }
+
+    let trace = LoopTrace::new();
+    assert_eq!(Some(1), find(&[5, 7, 9], 7, &trace));
+    assert_eq!(1, trace.executed("return Some(index)"));
+    assert_eq!(Event::Exit { label: None, iterations: 2, exit: Exit::Early }, trace.steps().pop().unwrap().event);
+}
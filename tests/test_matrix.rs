@@ -0,0 +1,78 @@
+use learn_rust::matrix::Matrix;
+
+#[test]
+fn test_matrix_index_and_slices() {
+    let mut m = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(2, m.rows());
+    assert_eq!(3, m.cols());
+    assert_eq!(3, Matrix::<i32, 2, 3>::COLS);
+
+    assert_eq!(6, m[(1, 2)]);
+    assert_eq!(6, m[1][2]);
+    assert_eq!(&[4, 5, 6], m.row(1));
+    assert_eq!([2, 5], m.column(1));
+    assert_eq!(vec![&3, &6], m.column_iter(2).collect::<Vec<_>>());
+
+    m[(0, 0)] = 10;
+    m.row_mut(1)[0] = 40;
+    assert_eq!([[10, 2, 3], [40, 5, 6]], m.into_array());
+
+    // 越界时 get 返回 None，下标访问会 panic
+    assert_eq!(None, m.get(2, 0));
+    assert_eq!(None, m.get(0, 3));
+    assert_eq!(Some(&5), m.get(1, 1));
+    assert!(std::panic::catch_unwind(|| m[(2, 0)]).is_err());
+
+    // 完全在栈上：大小就是所有元素的大小
+    assert_eq!(6 * 4, std::mem::size_of::<Matrix<i32, 2, 3>>());
+}
+
+#[test]
+fn test_matrix_transpose_and_multiply() {
+    let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+    let t: Matrix<i32, 3, 2> = a.transpose();
+    assert_eq!(Matrix::new([[1, 4], [2, 5], [3, 6]]), t);
+    assert_eq!(a, t.transpose());
+
+    // (2 × 3) * (3 × 2) = (2 × 2)
+    let product: Matrix<i32, 2, 2> = a * t;
+    assert_eq!(Matrix::new([[14, 32], [32, 77]]), product);
+    // (3 × 2) * (2 × 3) = (3 × 3)
+    let product: Matrix<i32, 3, 3> = t * a;
+    assert_eq!([17, 22, 27], *product.row(0));
+
+    // 维度不匹配无法编译：Matrix<i32, 2, 3> 没有实现 Mul<Matrix<i32, 2, 3>>
+    // let wrong = a * a;
+
+    let v = Matrix::new([[1], [1], [1]]);
+    assert_eq!(Matrix::new([[6], [15]]), a * v);
+}
+
+#[test]
+fn test_matrix_identity() {
+    let id = Matrix::<i32, 3, 3>::identity();
+    assert_eq!(Matrix::new([[1, 0, 0], [0, 1, 0], [0, 0, 1]]), id);
+    assert_eq!(3, id.trace());
+
+    let m = Matrix::new([[2, -1, 0], [1, 3, 5], [7, 0, 4]]);
+    assert_eq!(m, m * id);
+    assert_eq!(m, id * m);
+    assert_eq!(Matrix::zero(), m - m);
+    assert_eq!(m.scale(2), m + m);
+
+    let f = Matrix::<f64, 2, 2>::identity().scale(0.5);
+    assert_eq!(1.0, f.trace());
+}
+
+#[test]
+fn test_matrix_display() {
+    let m = Matrix::new([[1, -2], [30, 4]]);
+    assert_eq!("[ 1  -2]\n[30   4]", m.to_string());
+
+    let f = Matrix::new([[1.0, 0.25], [-3.5, 10.0]]);
+    assert_eq!("[   1  0.25]\n[-3.5    10]", f.to_string());
+    assert_eq!("[ 1.0   0.2]\n[-3.5  10.0]", format!("{:.1}", f));
+
+    let words = Matrix::new([["a", "bb"], ["ccc", "d"]]);
+    assert_eq!("[  a  bb]\n[ccc   d]", words.to_string());
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use learn_rust::globals;
+use learn_rust::metrics::{self, Kind, MetricsError, Registry, Value};
+
+#[test]
+fn test_counters_and_gauges() {
+    let registry = Registry::new();
+    let requests = registry.counter("requests_total", "requests handled").unwrap();
+    let running = registry.gauge("running", "tasks in progress").unwrap();
+
+    globals::hammer(8, 1000, || {
+        requests.increment();
+        running.add(1.0);
+        running.add(-1.0);
+    });
+    // 同一个名字取出的是同一个指标
+    registry.counter("requests_total", "ignored").unwrap().add(5);
+    running.set(2.5);
+
+    let snapshot = registry.snapshot();
+    assert_eq!(Some(&Value::Counter(8005)), snapshot.get("requests_total"));
+    assert_eq!(Some(&Value::Gauge(2.5)), snapshot.get("running"));
+    assert_eq!("requests handled", snapshot.metrics[0].help);
+
+    // 清零后句柄仍然有效
+    registry.reset();
+    assert_eq!(Some(&Value::Counter(0)), registry.snapshot().get("requests_total"));
+    requests.increment();
+    assert_eq!(Some(&Value::Counter(1)), registry.snapshot().get("requests_total"));
+    assert_eq!(-1.5, running.add(-1.5));
+}
+
+#[test]
+fn test_histogram() {
+    let registry = Registry::new();
+    let sizes = registry.histogram("size_bytes", "", &[10.0, 100.0, 1000.0]).unwrap();
+    for size in [5.0, 10.0, 50.0, 500.0, 5000.0] {
+        sizes.observe(size);
+    }
+    let expected = Value::Histogram { buckets: vec![(10.0, 2), (100.0, 3), (1000.0, 4)], sum: 5565.0, count: 5 };
+    assert_eq!(Some(&expected), registry.snapshot().get("size_bytes"));
+
+    // NaN 计入个数，但不会让总和变成 NaN
+    sizes.observe(f64::NAN);
+    assert_eq!(6, sizes.count());
+    assert_eq!(5565.0, sizes.sum());
+    sizes.observe(1.0);
+    assert_eq!(5566.0, sizes.sum());
+    assert!(registry.snapshot().to_json().contains(r#""sum": 5566"#));
+
+    let latency = registry.histogram("latency_seconds", "", &metrics::DEFAULT_BUCKETS).unwrap();
+    latency.observe_duration(Duration::from_millis(3));
+    assert_eq!(42, latency.time(|| 42));
+    globals::hammer(4, 250, || latency.observe(0.2));
+    assert_eq!(1002, latency.count());
+    assert!(latency.sum() >= 200.0);
+
+    registry.reset();
+    assert_eq!(0, sizes.count());
+    assert_eq!(0.0, sizes.sum());
+}
+
+#[test]
+fn test_errors() {
+    let registry = Registry::new();
+    registry.counter("hits", "").unwrap();
+    assert_eq!(
+        MetricsError::KindMismatch { name: "hits".to_string(), existing: Kind::Counter, requested: Kind::Gauge },
+        registry.gauge("hits", "").unwrap_err()
+    );
+    assert_eq!("metric `hits` is a counter, not a gauge", registry.gauge("hits", "").unwrap_err().to_string());
+    assert_eq!(MetricsError::InvalidName("1st".to_string()), registry.counter("1st", "").unwrap_err());
+    assert_eq!(MetricsError::InvalidName("a-b".to_string()), registry.counter("a-b", "").unwrap_err());
+    assert!(registry.counter("ns:a_1", "").is_ok());
+
+    assert_eq!(MetricsError::InvalidBuckets(vec![1.0, 1.0]), registry.histogram("h", "", &[1.0, 1.0]).unwrap_err());
+    assert!(registry.histogram("h", "", &[f64::INFINITY]).is_err());
+    registry.histogram("h", "", &[1.0, 2.0]).unwrap();
+    assert_eq!(
+        MetricsError::BucketsMismatch { name: "h".to_string(), existing: vec![1.0, 2.0] },
+        registry.histogram("h", "", &[1.0]).unwrap_err()
+    );
+}
+
+#[test]
+fn test_export() {
+    let registry = Registry::new();
+    registry.counter("jobs_total", "jobs run\nso far").unwrap().add(3);
+    registry.gauge("temperature", "").unwrap().set(f64::NEG_INFINITY);
+    let h = registry.histogram("wait_seconds", "time \"waiting\"", &[0.5, 1.0]).unwrap();
+    h.observe(0.25);
+    h.observe(2.0);
+    let snapshot = registry.snapshot();
+
+    let prometheus = "\
+# HELP jobs_total jobs run\\nso far
+# TYPE jobs_total counter
+jobs_total 3
+# TYPE temperature gauge
+temperature -Inf
+# HELP wait_seconds time \"waiting\"
+# TYPE wait_seconds histogram
+wait_seconds_bucket{le=\"0.5\"} 1
+wait_seconds_bucket{le=\"1\"} 1
+wait_seconds_bucket{le=\"+Inf\"} 2
+wait_seconds_sum 2.25
+wait_seconds_count 2
+";
+    assert_eq!(prometheus, snapshot.to_prometheus());
+
+    let json = r#"{
+  "jobs_total": {"type": "counter", "help": "jobs run\nso far", "value": 3},
+  "temperature": {"type": "gauge", "help": "", "value": null},
+  "wait_seconds": {"type": "histogram", "help": "time \"waiting\"", "buckets": [[0.5, 1], [1, 1]], "sum": 2.25, "count": 2}
+}"#;
+    assert_eq!(json, snapshot.to_json());
+    assert_eq!("{}", Registry::new().snapshot().to_json());
+}
+
+#[test]
+fn test_global_registry() {
+    // 全局注册表在各个测试之间共享，使用不会冲突的名字
+    let runs = metrics::global().counter("test_global_registry_runs", "").unwrap();
+    runs.increment();
+    let again = metrics::global().counter("test_global_registry_runs", "").unwrap();
+    assert!(again.get() >= 1);
+    assert!(metrics::global().snapshot().get("test_global_registry_runs").is_some());
+}
@@ -0,0 +1,170 @@
+use learn_rust::decimal::{self, Decimal, DecimalError, Rounding};
+use learn_rust::money::{Currency, Money, MoneyError};
+
+fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_decimal_parse_and_display() {
+    assert_eq!("2.00", dec("2.00").to_string());
+    assert_eq!(2, dec("2.00").scale());
+    assert_eq!("-0.05", dec("-0.05").to_string());
+    assert_eq!("0.000001", Decimal::new(1, 6).to_string());
+    assert_eq!("  1.5", format!("{:5}", dec("1.5")));
+    // 小数位数不同但数值相等
+    assert_eq!(dec("2.0"), dec("2.00"));
+    assert!(dec("-1.1") < dec("1.01"));
+
+    assert!(matches!("".parse::<Decimal>(), Err(DecimalError::Invalid(_))));
+    assert!(matches!("1.".parse::<Decimal>(), Err(DecimalError::Invalid(_))));
+    assert!(matches!(".5".parse::<Decimal>(), Err(DecimalError::Invalid(_))));
+    assert!(matches!("1.2.3".parse::<Decimal>(), Err(DecimalError::Invalid(_))));
+    assert_eq!(Err(DecimalError::ScaleTooLarge(19)), "0.0000000000000000001".parse::<Decimal>());
+}
+
+#[test]
+fn test_decimal_exact_arithmetic() {
+    // 浮点数：0.1 + 0.2 != 0.3
+    assert_ne!(0.3, 0.1 + 0.2);
+    // 定点小数没有这个问题
+    assert_eq!(dec("0.3"), dec("0.1") + dec("0.2"));
+    assert_eq!("0.30", (dec("0.10") + dec("0.2")).to_string());
+    assert_eq!(dec("-0.1"), dec("0.1") - dec("0.2"));
+    assert_eq!("0.30", (dec("0.10") * 3).to_string());
+    assert_eq!(Err(DecimalError::Overflow), Decimal::new(i128::MAX, 0).checked_add(&Decimal::new(1, 0)));
+}
+
+#[test]
+fn test_decimal_min_units() {
+    // i128::MIN 没有对应的正数，可以由 new 或者减法得到
+    let min = Decimal::new(-i128::MAX, 2).checked_sub(&Decimal::new(1, 2)).unwrap();
+    assert_eq!(Decimal::new(i128::MIN, 2), min);
+    assert_eq!(Err(DecimalError::Overflow), min.checked_abs());
+    assert_eq!(Err(DecimalError::Overflow), min.checked_neg());
+    assert_eq!(Ok(Decimal::new(i128::MAX, 2)), Decimal::new(-i128::MAX, 2).checked_abs());
+
+    assert_eq!("-1701411834604692317316873037158841057.28", min.to_string());
+    assert_eq!("-1701411834604692317316873037158841057", min.round(0, Rounding::TowardZero).unwrap().to_string());
+    assert_eq!("-1701411834604692317316873037158841057.3", min.round(1, Rounding::HalfUp).unwrap().to_string());
+
+    let money = Money::from_decimal(min, Currency::Usd).unwrap();
+    assert_eq!("-$1701411834604692317316873037158841057.28", money.to_string());
+}
+
+#[test]
+fn test_decimal_rounding() {
+    let cases = [
+        // (值, 四舍五入, 银行家舍入, 截断)
+        ("2.5", "3", "2", "2"),
+        ("3.5", "4", "4", "3"),
+        ("-2.5", "-3", "-2", "-2"),
+        ("2.51", "3", "3", "2"),
+        ("2.49", "2", "2", "2"),
+        ("-2.49", "-2", "-2", "-2"),
+    ];
+    for (value, half_up, half_even, toward_zero) in cases {
+        let value = dec(value);
+        assert_eq!(half_up, value.round(0, Rounding::HalfUp).unwrap().to_string());
+        assert_eq!(half_even, value.round(0, Rounding::HalfEven).unwrap().to_string());
+        assert_eq!(toward_zero, value.round(0, Rounding::TowardZero).unwrap().to_string());
+    }
+    assert_eq!("1.24", dec("1.245").round(2, Rounding::HalfEven).unwrap().to_string());
+    assert_eq!("1.25", dec("1.245").round(2, Rounding::HalfUp).unwrap().to_string());
+    assert_eq!("1.2450", dec("1.245").rescale(4, Rounding::HalfUp).unwrap().to_string());
+}
+
+#[test]
+fn test_decimal_from_f64() {
+    // 2.0 和 0.5 可以用二进制精确表示
+    let conversion = Decimal::from_f64(2.0, 2, Rounding::HalfEven).unwrap();
+    assert!(conversion.exact);
+    assert_eq!("2.00", conversion.value.to_string());
+    assert_eq!(dec("0.5"), Decimal::from_f64_exact(0.5, 1).unwrap());
+
+    // 0.1 不能，转换时会报告发生了舍入
+    let conversion = Decimal::from_f64(0.1, 2, Rounding::HalfEven).unwrap();
+    assert!(!conversion.exact);
+    assert_eq!("0.10", conversion.value.to_string());
+    assert_eq!("0.1000000000000000055511151231257827021181583404541015625", conversion.float_value);
+    assert!(matches!(Decimal::from_f64_exact(0.1, 2), Err(DecimalError::Inexact { .. })));
+
+    // 1.005 实际上比 1.005 小一点，所以四舍五入得到 1.00
+    let conversion = Decimal::from_f64(1.005, 2, Rounding::HalfUp).unwrap();
+    assert_eq!("1.00", conversion.value.to_string());
+    assert_eq!("-0.30", Decimal::from_f64(-0.3, 2, Rounding::HalfUp).unwrap().value.to_string());
+
+    assert!(matches!(Decimal::from_f64(f64::NAN, 2, Rounding::HalfUp), Err(DecimalError::NotFinite(_))));
+    assert_eq!(Err(DecimalError::Overflow), Decimal::from_f64(1e300, 2, Rounding::HalfUp).map(|c| c.value));
+    assert_eq!("1e300".parse::<f64>().unwrap(), decimal::exact_decimal(1e300).parse::<f64>().unwrap());
+    assert_eq!(0.25, dec("0.25").to_f64());
+}
+
+#[test]
+fn test_money_display_and_parse() {
+    let money = Money::from_minor(200, Currency::Cny);
+    assert_eq!("¥2.00", money.to_string());
+    assert_eq!("-$0.50", Money::from_minor(-50, Currency::Usd).to_string());
+    assert_eq!("JP¥300", Money::from_minor(300, Currency::Jpy).to_string());
+
+    assert_eq!(money, "¥2.00".parse().unwrap());
+    assert_eq!(money, "¥2".parse().unwrap());
+    assert_eq!(money, "2.00 CNY".parse().unwrap());
+    assert_eq!(money, "cny 2".parse().unwrap());
+    assert_eq!(Money::from_minor(-150, Currency::Eur), "-€1.5".parse().unwrap());
+    assert_eq!(Money::from_minor(300, Currency::Jpy), "JPY 300".parse().unwrap());
+
+    assert!(matches!("¥0.001".parse::<Money>(), Err(MoneyError::TooPrecise { .. })));
+    assert!(matches!("JPY 1.5".parse::<Money>(), Err(MoneyError::TooPrecise { .. })));
+    assert!(matches!("2.00 XYZ".parse::<Money>(), Err(MoneyError::UnknownCurrency(_))));
+    assert!(matches!("¥abc".parse::<Money>(), Err(MoneyError::Decimal(_))));
+    // 只能有一个符号
+    assert_eq!(Money::from_minor(-100, Currency::Usd), "$-1.00".parse().unwrap());
+    assert!(matches!("-$-1.00".parse::<Money>(), Err(MoneyError::Decimal(_))));
+    assert!(matches!("-$+1.00".parse::<Money>(), Err(MoneyError::Decimal(_))));
+    assert!(matches!("-JPY -300".parse::<Money>(), Err(MoneyError::Decimal(_))));
+
+    // 输出再解析回来，币种和金额都不变
+    for currency in Currency::ALL {
+        for minor in [0, 300, -12345] {
+            let money = Money::from_minor(minor, currency);
+            assert_eq!(money, money.to_string().parse().unwrap(), "{}", money);
+        }
+    }
+}
+
+#[test]
+fn test_money_arithmetic() {
+    let price: Money = "¥19.99".parse().unwrap();
+    let total = price.checked_mul_int(3).unwrap();
+    assert_eq!("¥59.97", total.to_string());
+    assert_eq!(5997, total.minor_units());
+
+    let paid: Money = "¥100".parse().unwrap();
+    assert_eq!("¥40.03", paid.checked_sub(&total).unwrap().to_string());
+    assert_eq!("-¥40.03", total.checked_sub(&paid).unwrap().to_string());
+
+    let dollars: Money = "$1".parse().unwrap();
+    assert_eq!(Err(MoneyError::CurrencyMismatch(Currency::Cny, Currency::Usd)), paid.checked_add(&dollars));
+
+    // 按比例计算时需要显式选择舍入方式
+    let tax = Money::from_decimal_rounded(dec("0.125"), Currency::Cny, Rounding::HalfEven).unwrap();
+    assert_eq!("¥0.12", tax.to_string());
+    let tax = Money::from_decimal_rounded(dec("0.125"), Currency::Cny, Rounding::HalfUp).unwrap();
+    assert_eq!("¥0.13", tax.to_string());
+}
+
+#[test]
+fn test_money_from_f64() {
+    let converted = Money::from_f64(2.0, Currency::Cny, Rounding::HalfEven).unwrap();
+    assert!(converted.exact);
+    assert_eq!("¥2.00", converted.money.to_string());
+
+    let converted = Money::from_f64(0.1 + 0.2, Currency::Cny, Rounding::HalfEven).unwrap();
+    assert!(!converted.exact);
+    assert_eq!("¥0.30", converted.money.to_string());
+    assert_eq!("0.3000000000000000444089209850062616169452667236328125", converted.float_value);
+
+    assert!(Money::from_f64_exact(0.1, Currency::Cny).is_err());
+    assert_eq!("¥0.25", Money::from_f64_exact(0.25, Currency::Cny).unwrap().to_string());
+}
@@ -0,0 +1,106 @@
+use learn_rust::numeric::{self, CheckedOps, Float, Int, Narrow, Num, Promote, Widen, WrappingOps, Zero};
+
+// 泛型函数只能使用 trait 中的操作
+fn largest<T: Num>(values: &[T]) -> Option<T> {
+    values.iter().copied().fold(None, |max, v| match max {
+        Some(m) if m >= v => Some(m),
+        _ => Some(v),
+    })
+}
+
+fn describe<T: Int>() -> String {
+    format!("{}: {} bits, {}..={}, signed: {}", T::NAME, T::BITS, T::MIN, T::MAX, T::SIGNED)
+}
+
+fn type_of<T: Num>(_: T) -> &'static str {
+    T::NAME
+}
+
+#[test]
+fn test_traits() {
+    assert_eq!(Some(7u8), largest(&[3u8, 7, 5]));
+    assert_eq!(Some(2.5), largest(&[-1.0, 2.5]));
+    assert_eq!(None, largest::<i64>(&[]));
+
+    assert_eq!("i8: 8 bits, -128..=127, signed: true", describe::<i8>());
+    assert_eq!("u16: 16 bits, 0..=65535, signed: false", describe::<u16>());
+
+    assert!(0u32.is_zero());
+    assert!(<f64 as Zero>::ZERO.is_zero());
+    assert_eq!(None, CheckedOps::checked_add(i8::MAX, 1));
+    assert_eq!(i8::MIN, WrappingOps::wrapping_add(i8::MAX, 1));
+    assert_eq!(None, CheckedOps::checked_div(7u32, 0));
+    // 浮点数的结果不是有限的数就是溢出
+    assert_eq!(None, CheckedOps::checked_mul(f32::MAX, 2.0));
+    assert_eq!(None, CheckedOps::checked_div(1.0f64, 0.0));
+    assert_eq!(Some(0.5), CheckedOps::checked_div(1.0f64, 2.0));
+    assert!(<f32 as Float>::NAN.is_nan());
+}
+
+#[test]
+fn test_add_promotes() {
+    // 结果是两个类型都能无损转换到的最小的类型
+    assert_eq!("i32", type_of(numeric::add(2, 3i8)));
+    assert_eq!("i16", type_of(numeric::add(200u8, 100i8)));
+    assert_eq!(300, numeric::add(200u8, 100i8));
+    assert_eq!("u8", type_of(numeric::add(1u8, 2u8)));
+    assert_eq!("i64", type_of(numeric::add(u32::MAX, -1i32)));
+    assert_eq!(u32::MAX as i64 - 1, numeric::add(u32::MAX, -1i32));
+    assert_eq!("i128", type_of(numeric::add(u64::MAX, i64::MIN)));
+    assert_eq!("f32", type_of(numeric::add(1.5f32, 2u16)));
+    // i32 的所有值在 f32 中放不下，所以是 f64
+    assert_eq!("f64", type_of(numeric::add(0.5f32, 16_777_217i32)));
+    assert_eq!(16_777_217.5, numeric::add(0.5f32, 16_777_217i32));
+    assert_eq!("isize", type_of(numeric::add(1isize, 2u8)));
+    assert_eq!("u64", type_of(numeric::add(1usize, 2u32)));
+    assert_eq!("i128", type_of(numeric::add(1usize, -2isize)));
+
+    // 相同的类型仍然可能溢出
+    assert_eq!(None, numeric::checked_add(200u8, 100u8));
+    assert_eq!(Some(300), numeric::checked_add(200u8, 100u16));
+
+    assert_eq!((-1i16, 255i16), Promote::<u8>::promote(-1i8, 255u8));
+    let wide: i64 = (-5isize).widen();
+    assert_eq!(-5, wide);
+    let wide: f64 = 16_777_217u32.widen();
+    assert_eq!(16_777_217.0, wide);
+}
+
+#[test]
+fn test_narrow() {
+    // 收窄必须选择放不下时的处理方式
+    let checked: Option<u8> = 300i32.narrow_checked();
+    let wrapping: u8 = 300i32.narrow_wrapping();
+    let saturating: u8 = 300i32.narrow_saturating();
+    assert_eq!((None, 44, 255), (checked, wrapping, saturating));
+
+    let saturating: u8 = (-3i64).narrow_saturating();
+    assert_eq!(0, saturating);
+    let saturating: i8 = (-300i16).narrow_saturating();
+    assert_eq!(i8::MIN, saturating);
+    let wrapping: i8 = 255u8.narrow_wrapping();
+    assert_eq!(-1, wrapping);
+    let checked: Option<i16> = 100u128.narrow_checked();
+    assert_eq!(Some(100), checked);
+
+    // add 的结果再收窄回原来的类型
+    let total: Option<i8> = numeric::add(100i8, 100u8).narrow_checked();
+    assert_eq!(None, total);
+}
+
+#[test]
+fn test_sum_and_mean() {
+    // 用同一类中最宽的类型累加，不会在中途溢出
+    assert_eq!(Some(400), numeric::sum([200u8, 200]));
+    assert_eq!(Some(-256), numeric::sum(vec![-128i8, -128]));
+    assert_eq!(Some(i64::MAX as i128 * 2), numeric::sum([i64::MAX, i64::MAX]));
+    assert_eq!(None, numeric::sum([u128::MAX, 1]));
+    assert_eq!(Some(0), numeric::sum(Vec::<u32>::new()));
+    assert_eq!(Some(6.0), numeric::sum([1.5f32, 4.5]));
+    assert_eq!(None, numeric::sum([f64::MAX, f64::MAX]));
+
+    assert_eq!(Some(200.0), numeric::mean([200u8, 250, 150]));
+    assert_eq!(Some(-0.5), numeric::mean([-1i32, 0].iter().copied()));
+    assert_eq!(Some(2.25), numeric::mean([2.0f32, 2.5]));
+    assert_eq!(None, numeric::mean(Vec::<i16>::new()));
+}
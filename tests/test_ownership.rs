@@ -8,8 +8,13 @@
 
  */
 
+use std::cell::RefCell;
 use std::ops::Index;
 
+use learn_rust::call_trace::trace;
+use learn_rust::defer;
+use learn_rust::traced::{self, TracedString};
+
 #[test]
 fn test_ownership_over_scope() {
     // 所有权的第一个示例
@@ -19,6 +24,15 @@ fn test_ownership_over_scope() {
         assert_eq!("hello", s); // 在范围内，变量可用
     }
     // assert_eq!("hello", s); // 不在范围内，变量不可用
+
+    // 离开范围时值被 drop，defer! 利用这一点在 } 处执行代码，后声明的先执行
+    let log = RefCell::new(Vec::new());
+    {
+        defer! { log.borrow_mut().push("first"); }
+        defer! { log.borrow_mut().push("second"); }
+        log.borrow_mut().push("body");
+    }
+    assert_eq!(vec!["body", "second", "first"], log.into_inner());
 }
 
 #[test]
@@ -41,6 +55,17 @@ fn test_ownership_move() {
     let s2 = s1.clone();
 
     assert_eq!(s1, s2);
+
+    // 用 TracedString 代替 String，可以打印出真实发生的分配、转移、深拷贝与释放：
+    // cargo test --test test_ownership -- --nocapture
+    {
+        let s1 = TracedString::new("s1", "hello");
+        let s2 = s1.moved("s2");    // 只有所有权转移，堆上的数据没有复制
+        let s3 = s2.clone_as("s3"); // 深拷贝，重新分配了内存
+        assert_eq!(s2, s3);
+    }   // s3、s2 依次释放，s1 已经转移走了，什么都不发生
+    let log = traced::print_log();
+    assert_eq!(5, log.len());
 }
 
 #[test]
@@ -74,6 +99,10 @@ fn takes_ownership(_some_string: String) { // some_string comes into scope
 
 }
 
+fn takes_traced(some_string: TracedString) {
+    assert_eq!("hello", some_string);
+} // some_string 在这里被释放
+
 fn makes_copy(_some_integer: i32) { // some_integer comes into scope
 
 } // Here, some_integer goes out of scope. Nothing special happens.
@@ -89,6 +118,12 @@ fn test_ownership_into_function() {
     let x :i32 = 5;
     makes_copy(x); // 类型若实现 copy trait ，变量则可以后续继续使用
     assert_eq!(5, x);
+
+    // 所有权转移给了函数的参数，函数结束时参数离开 scope，字符串在函数中被释放
+    let s = TracedString::new("s", "hello");
+    takes_traced(s.moved("some_string"));
+    let log = traced::print_log();
+    assert_eq!("drop  some_string (capacity 5)", log[2].to_string());
 }
 
 fn get_hello_string() -> String {
@@ -97,10 +132,16 @@ fn get_hello_string() -> String {
 }
 
 // This function takes a String and returns one
+#[trace]
 fn takes_and_gives_back(a_string: String) -> String {
     return a_string;  // a_string is returned and moves out to the calling function
 }
 
+fn get_traced_hello() -> TracedString {
+    let s = TracedString::new("s", "hello");
+    s.moved("hello")    // 返回时所有权转移给调用者
+}
+
 #[test]
 fn test_ownership_function_return() {
     let hello = get_hello_string();
@@ -108,6 +149,15 @@ fn test_ownership_function_return() {
 
     let hello = takes_and_gives_back(hello);
     assert_eq!("hello", hello);
+
+    // 从分配到释放只有一个字符串，所有权从函数中的 s 转移给了外面的 hello
+    {
+        let hello = get_traced_hello();
+        assert_eq!("hello", hello);
+    }
+    let log = traced::print_log();
+    assert_eq!(3, log.len());
+    assert_eq!("drop  hello (capacity 5)", log[2].to_string());
 }
 
 // ==== References and Borrowing ====
@@ -117,6 +167,7 @@ fn test_ownership_function_return() {
     引用就像一个指针，因为它是一个地址，我们可以按照它来访问存储在该地址上的数据，该地址由其他变量拥有
  */
 
+#[trace]
 fn calculate_length(s: &String) -> usize {
     // 参数 s 是个引用，它没有所有权
     s.len()
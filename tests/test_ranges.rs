@@ -0,0 +1,126 @@
+use learn_rust::ranges::{FloatRange, IntRange, RangeError, Stepped};
+use proptest::prelude::*;
+
+#[test]
+fn test_int_step() {
+    let down = IntRange::new(10, 0, -3).unwrap();
+    assert_eq!(vec![10, 7, 4, 1], down.iter().collect::<Vec<i32>>());
+    assert_eq!(Some(4), down.checked_len());
+    assert_eq!("10..0 step -3", down.to_string());
+
+    assert_eq!(vec![0, 5, 10], (0..=10u8).stepped(5).unwrap().iter().collect::<Vec<_>>());
+    assert_eq!(vec![0, 5], (0..10u8).stepped(5).unwrap().iter().collect::<Vec<_>>());
+    assert_eq!(vec![-1, -3, -5], IntRange::inclusive(-1i64, -5, -2).unwrap().iter().collect::<Vec<_>>());
+
+    // 与标准库的 step_by 一致
+    assert!((1..100).step_by(7).eq(IntRange::new(1, 100, 7).unwrap()));
+    assert!((1..=100).rev().step_by(7).eq(IntRange::inclusive(100, 1, -7).unwrap()));
+
+    assert_eq!(Err(RangeError::ZeroStep), IntRange::new(0, 10, 0));
+    assert_eq!(Err(RangeError::StepOverflow), IntRange::new(0i128, 10, i128::MIN));
+}
+
+#[test]
+fn test_int_empty_and_len() {
+    // 方向相反或起点等于不包含的终点时为空
+    assert!(IntRange::new(0, 10, -1).unwrap().is_empty());
+    assert!(IntRange::new(5, 5, 1).unwrap().is_empty());
+    assert!(!IntRange::inclusive(5, 5, -1).unwrap().is_empty());
+    assert_eq!(Some(0), IntRange::new(10u8, 0, 1).unwrap().checked_len());
+    assert_eq!(None, IntRange::new(10u8, 0, 1).unwrap().iter().next());
+
+    // 整个取值范围也能正确计算长度，不会溢出
+    assert_eq!(Some(256), IntRange::inclusive(i8::MIN, i8::MAX, 1).unwrap().checked_len());
+    assert_eq!(Some(u128::MAX), IntRange::new(0, u128::MAX, 1).unwrap().len_u128());
+    assert_eq!(None, IntRange::inclusive(0, u128::MAX, 1).unwrap().len_u128());
+    assert_eq!(Some(3), IntRange::inclusive(i128::MIN, i128::MAX, i128::MAX).unwrap().len_u128());
+    assert_eq!(None, IntRange::inclusive(0u64, u64::MAX, 1).unwrap().checked_len().filter(|_| usize::BITS <= 64));
+
+    let full = IntRange::inclusive(i128::MIN, i128::MAX, i128::MAX).unwrap();
+    assert_eq!(vec![i128::MIN, -1, i128::MAX - 1], full.iter().collect::<Vec<_>>());
+    let mut edges = IntRange::inclusive(0, u128::MAX, 1).unwrap().iter();
+    assert_eq!(Some(u128::MAX), edges.next_back());
+    assert_eq!(Some(0), edges.next());
+}
+
+#[test]
+fn test_int_reverse_and_contains() {
+    let range = IntRange::new(1, 20, 4).unwrap();
+    assert_eq!(vec![1, 5, 9, 13, 17], range.iter().collect::<Vec<i16>>());
+    assert_eq!(vec![17, 13, 9, 5, 1], range.reversed().iter().collect::<Vec<_>>());
+    assert_eq!(vec![17, 13, 9, 5, 1], range.iter().rev().collect::<Vec<_>>());
+    assert_eq!(Some(17), range.last());
+    assert_eq!(Some(9), range.get(2));
+    assert_eq!(None, range.get(5));
+
+    assert!(range.contains(13));
+    assert!(!range.contains(14));
+    assert!(!range.contains(21));
+    assert!(IntRange::new(0isize, -10, -5).unwrap().contains(-5));
+    assert!(IntRange::new(5u32, 0, 1).unwrap().reversed().is_empty());
+}
+
+#[test]
+fn test_float_range() {
+    // 累加 0.1 十次不等于 1.0，直接计算则没有累积误差
+    let mut x = 0.0;
+    for _ in 0..10 {
+        x += 0.1;
+    }
+    assert_ne!(1.0, x);
+    let range = (0.0f64..=1.0).stepped(0.1).unwrap();
+    assert_eq!(11, range.len());
+    assert_eq!(Some(1.0), range.last());
+    assert_eq!(Some(0.30000000000000004), range.get(3));
+
+    // 1.3 - 1.0 除以 0.1 是 3.0000000000000004，不应该多出一个 1.3
+    assert_eq!(3, FloatRange::new(1.0, 1.3, 0.1).unwrap().len());
+    assert_eq!(4, FloatRange::inclusive(1.0, 1.3, 0.1).unwrap().len());
+    assert_eq!(Some(1.3), FloatRange::inclusive(1.0, 1.3, 0.1).unwrap().last());
+
+    let down = FloatRange::new(1.0f32, 0.0, -0.25).unwrap();
+    assert_eq!(vec![1.0, 0.75, 0.5, 0.25], down.to_vec());
+    assert_eq!(vec![0.25, 0.5, 0.75, 1.0], down.iter().rev().collect::<Vec<_>>());
+    assert!((0.0f64..1.0).stepped(-0.5).unwrap().is_empty());
+
+    assert_eq!(Err(RangeError::ZeroStep), FloatRange::new(0.0, 1.0, 0.0));
+    assert_eq!(Err(RangeError::NotFinite), FloatRange::new(0.0, f64::INFINITY, 1.0));
+    assert_eq!(Err(RangeError::NotFinite), FloatRange::new(f64::NAN, 1.0, 1.0));
+    assert_eq!(Err(RangeError::TooLong), FloatRange::new(0.0, f64::MAX, f64::MIN_POSITIVE));
+}
+
+#[test]
+fn test_linspace() {
+    assert_eq!(vec![0.0, 0.25, 0.5, 0.75, 1.0], FloatRange::linspace(0.0, 1.0, 5, true).unwrap().to_vec());
+    assert_eq!(vec![0.0, 0.2, 0.4, 0.6000000000000001, 0.8], FloatRange::linspace(0.0, 1.0, 5, false).unwrap().to_vec());
+    assert_eq!(vec![2.0], FloatRange::linspace(2.0, 3.0, 1, true).unwrap().to_vec());
+    assert!(FloatRange::linspace(2.0, 3.0, 0, true).unwrap().is_empty());
+
+    // 终点就是传入的值，不受舍入影响
+    let range = FloatRange::linspace(0.1f32, 0.7, 7, true).unwrap();
+    assert_eq!(Some(0.7), range.last());
+    assert_eq!("0.1..=0.7 step 0.099999994 (7 values)", range.to_string());
+}
+
+proptest! {
+    #[test]
+    fn prop_int_matches_std(start in -300i32..300, end in -300i32..300, step in 1i128..20) {
+        let forward: Vec<i32> = (start..end).step_by(step as usize).collect();
+        prop_assert_eq!(&forward, &IntRange::new(start, end, step).unwrap().iter().collect::<Vec<_>>());
+        prop_assert_eq!(Some(forward.len()), IntRange::new(start, end, step).unwrap().checked_len());
+
+        let backward: Vec<i32> = (end..=start).rev().step_by(step as usize).collect();
+        let range = IntRange::inclusive(start, end, -step).unwrap();
+        prop_assert_eq!(&backward, &range.iter().collect::<Vec<_>>());
+        prop_assert_eq!(backward.iter().rev().copied().collect::<Vec<_>>(), range.reversed().iter().collect::<Vec<_>>());
+        for value in -300..300 {
+            prop_assert_eq!(backward.contains(&value), range.contains(value));
+        }
+    }
+
+    #[test]
+    fn prop_u8_matches_std(start: u8, end: u8, step in 1i128..300) {
+        let expected: Vec<u8> = (start..=end).step_by(step as usize).collect();
+        prop_assert_eq!(expected, IntRange::inclusive(start, end, step).unwrap().iter().collect::<Vec<_>>());
+    }
+}
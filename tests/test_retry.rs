@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use learn_rust::retry::{Backoff, Clock, ManualClock, Retry, Stop, SystemClock};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn test_first_success() {
+    let clock = ManualClock::new();
+    // 前两次失败，第三次成功
+    let outcome = Retry::new(5).backoff(Backoff::Fixed(ms(100))).run(&clock, |attempt| {
+        clock.advance(ms(10));
+        if attempt < 3 {
+            Err(format!("attempt {} failed", attempt))
+        } else {
+            Ok(attempt * 2)
+        }
+    });
+
+    assert_eq!(Ok(6), outcome.result);
+    assert_eq!(3, outcome.stats.attempts);
+    assert_eq!(vec![ms(100), ms(100)], outcome.stats.delays);
+    assert_eq!(ms(200), outcome.stats.total_delay());
+    assert_eq!(ms(230), outcome.stats.elapsed);
+    assert_eq!(Stop::Succeeded, outcome.stats.stop);
+    assert_eq!(vec![ms(100), ms(100)], clock.sleeps());
+}
+
+#[test]
+fn test_last_error() {
+    let clock = ManualClock::new();
+    let backoff = Backoff::Exponential { initial: ms(100), factor: 2, max: ms(1000) };
+    let outcome = Retry::new(6).backoff(backoff).run(&clock, Err::<(), u32>);
+
+    assert_eq!(Err(6), outcome.result);
+    assert_eq!(Stop::Exhausted, outcome.stats.stop);
+    // 100, 200, 400, 800，之后不超过上限
+    assert_eq!(vec![ms(100), ms(200), ms(400), ms(800), ms(1000)], outcome.stats.delays);
+    assert_eq!(ms(2500), clock.elapsed());
+
+    // 至少尝试一次，最后一次失败后不再等待
+    let outcome = Retry::new(0).run(&clock, |_| Err::<(), _>("no"));
+    assert_eq!(1, outcome.stats.attempts);
+    assert!(outcome.stats.delays.is_empty());
+}
+
+#[test]
+fn test_predicate() {
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Timeout,
+        NotFound,
+    }
+
+    let clock = ManualClock::new();
+    let outcome = Retry::new(10).run_if(
+        &clock,
+        |e, _| *e == Error::Timeout,
+        |attempt| if attempt < 3 { Err::<(), _>(Error::Timeout) } else { Err(Error::NotFound) },
+    );
+    assert_eq!(Err(Error::NotFound), outcome.result);
+    assert_eq!(3, outcome.stats.attempts);
+    assert_eq!(Stop::NotRetryable, outcome.stats.stop);
+
+    // 谓词也可以根据尝试次数决定，比如只重试一次
+    let outcome = Retry::new(10).run_if(&clock, |_, attempt| attempt < 2, |_| Err::<(), _>(Error::Timeout));
+    assert_eq!(2, outcome.stats.attempts);
+}
+
+#[test]
+fn test_backoff_schedules() {
+    let delays: Vec<Duration> = Backoff::Fixed(ms(5)).delays().take(3).collect();
+    assert_eq!(vec![ms(5); 3], delays);
+
+    // 倍数溢出时停在上限
+    let huge = Backoff::Exponential { initial: ms(1), factor: 10, max: Duration::MAX };
+    let delays: Vec<Duration> = huge.delays().skip(30).take(2).collect();
+    assert_eq!(vec![Duration::MAX; 2], delays);
+
+    // 抖动：不超过指数退避的延迟，同样的 seed 得到同样的序列
+    let jittered = Backoff::Jittered { initial: ms(100), factor: 2, max: ms(1000), seed: 42 };
+    let first: Vec<Duration> = jittered.delays().take(20).collect();
+    assert_eq!(first, jittered.delays().take(20).collect::<Vec<_>>());
+    let caps = Backoff::Exponential { initial: ms(100), factor: 2, max: ms(1000) }.delays();
+    assert!(first.iter().zip(caps).all(|(d, cap)| *d <= cap));
+    let other = Backoff::Jittered { initial: ms(100), factor: 2, max: ms(1000), seed: 7 };
+    assert_ne!(first, other.delays().take(20).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_system_clock() {
+    let clock = SystemClock::new();
+    let outcome = Retry::new(3).backoff(Backoff::Fixed(ms(1))).run(&clock, |attempt| match attempt {
+        3 => Ok("done"),
+        _ => Err(()),
+    });
+    assert_eq!(Ok("done"), outcome.into_result());
+    assert!(clock.elapsed() >= ms(2));
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use learn_rust::ranges::IntRange;
+use learn_rust::rules::{Condition, Fallback, Mode, Predicate, RuleError, RuleSet};
+
+#[test]
+fn test_first_match() {
+    // 与课程中的 if / else if 相同
+    let rules = RuleSet::new(Mode::FirstMatch)
+        .divisible_by("divisible by 4", 4)
+        .divisible_by("divisible by 3", 3)
+        .divisible_by("divisible by 2", 2)
+        .fallback(Fallback::Label("not divisible by 4, 3, or 2".to_string()));
+
+    assert_eq!(Some("divisible by 3".to_string()), rules.classify(6));
+    assert_eq!(Some("divisible by 4".to_string()), rules.classify(12));
+    assert_eq!(vec!["divisible by 4"], rules.matches(12));
+    assert_eq!(Some("not divisible by 4, 3, or 2".to_string()), rules.classify(7));
+}
+
+#[test]
+fn test_fizzbuzz() {
+    let rules = RuleSet::fizzbuzz();
+    let out: Vec<String> = rules.stream(1..=15).map(|(_, label)| label).collect();
+    assert_eq!("1 2 Fizz 4 Buzz Fizz 7 8 Fizz Buzz 11 Fizz 13 14 FizzBuzz", out.join(" "));
+    assert_eq!(vec!["Fizz", "Buzz"], rules.matches(30));
+
+    // 变体：分隔符、额外规则、自定义判断
+    let rules = RuleSet::fizzbuzz()
+        .predicate("Bazz", "ends with 7", |n| n % 10 == 7)
+        .separator("-");
+    assert_eq!(Some("Fizz-Bazz".to_string()), rules.classify(27));
+    assert_eq!(Some("Fizz-Buzz".to_string()), rules.classify(-15));
+}
+
+#[test]
+fn test_stream_and_buckets() {
+    let rules = RuleSet::new(Mode::FirstMatch)
+        .in_range("child", 0, 12)
+        .in_range("teen", 13, 19)
+        .rule("adult", Condition::InRange { min: Some(20), max: None })
+        .fallback(Fallback::Nothing);
+
+    // 流式处理：无限的迭代器也可以，只取需要的部分
+    let first: Vec<(i128, String)> = rules.stream((-5i64..).step_by(9)).take(3).collect();
+    assert_eq!(vec![(4, "child".to_string()), (13, "teen".to_string()), (22, "adult".to_string())], first);
+    assert_eq!(3, rules.stream(IntRange::new(30u8, 0, -10).unwrap()).count());
+
+    let ages = [3u8, 15, 17, 42, 8, 70];
+    let buckets = rules.histogram(ages);
+    assert_eq!(vec![("child".to_string(), 2), ("teen".to_string(), 2), ("adult".to_string(), 2)], buckets);
+}
+
+#[test]
+fn test_conditions() {
+    let even_not_four = Condition::All(vec![
+        Condition::DivisibleBy(2),
+        Condition::Not(Box::new(Condition::DivisibleBy(4))),
+    ]);
+    assert!(even_not_four.matches(6));
+    assert!(!even_not_four.matches(8));
+    assert_eq!("(divisible by 2) and (not (divisible by 4))", even_not_four.to_string());
+
+    assert!(Condition::DivisibleBy(0).matches(0));
+    assert!(!Condition::DivisibleBy(0).matches(5));
+    assert!(Condition::DivisibleBy(-1).matches(i128::MIN));
+    assert!(Condition::Any(vec![Condition::DivisibleBy(5), Condition::DivisibleBy(7)]).matches(14));
+}
+
+const SPEC: &str = r#"
+# 分类练习
+mode = "all"
+separator = ", "
+fallback = "boring"
+
+[[rule]]
+label = "even"
+predicate = "even"
+
+[[rule]]
+label = "prime"
+predicate = "prime"
+
+[[rule]]
+label = "square teen"   # 两个条件同时满足
+predicate = "square"
+min = 13
+max = 19
+
+[[rule]]
+label = "6 or 10"
+divisible_by_any = [6, 1_0]
+not_divisible_by = 4
+"#;
+
+#[test]
+fn test_parse() {
+    let rules = RuleSet::parse(SPEC).unwrap();
+    assert_eq!(Mode::AllMatches, rules.mode());
+    assert_eq!(4, rules.rules().len());
+    assert_eq!(Some("even, prime".to_string()), rules.classify(2));
+    assert_eq!(Some("even, square teen".to_string()), rules.classify(16));
+    assert_eq!(Some("even, 6 or 10".to_string()), rules.classify(30));
+    assert_eq!(Some("even".to_string()), rules.classify(20));
+    assert_eq!(Some("boring".to_string()), rules.classify(9));
+
+    let spec = "mode = \"first\"\nfallback = \"none\"\n[[rule]]\nlabel = \"#\"\npredicate = \"lucky\"\n";
+    let lucky: Predicate = Arc::new(|n| n == 7);
+    let rules = RuleSet::parse_with(spec, &[("lucky", lucky)]).unwrap();
+    assert_eq!(Some("#".to_string()), rules.classify(7));
+    assert_eq!(None, rules.classify(8));
+
+    // 转义的引号不结束字符串，后面的 # 仍然在字符串中
+    let spec = "[[rule]]\nlabel = \"a\\\"#b\" # comment\nmin = 1\n";
+    let rules = RuleSet::parse(spec).unwrap();
+    assert_eq!(Some("a\"#b".to_string()), rules.classify(1));
+}
+
+#[test]
+fn test_large_primes() {
+    let rules = RuleSet::parse("fallback = \"none\"\n[[rule]]\nlabel = \"prime\"\npredicate = \"prime\"\n").unwrap();
+    let prime = |n: i128| rules.classify(n).is_some();
+    // 小于 2^63 的最大素数，试除需要 30 亿次
+    assert!(prime(9_223_372_036_854_775_783));
+    assert!(prime(618_970_019_642_690_137_449_562_111)); // 2^89 - 1
+    assert!(prime(i128::MAX)); // 2^127 - 1
+    assert!(!prime(18_446_744_073_709_551_617)); // 2^64 + 1 = 274177 * 67280421310721
+    // 对底数 2、3、5、7 都是强伪素数的合数
+    assert!(!prime(3_215_031_751));
+    assert!(!prime(i128::MAX - 2));
+    assert!(!prime(1));
+    assert!(!prime(-7));
+    let small: Vec<i128> = (0..50).filter(|n| prime(*n)).collect();
+    assert_eq!(vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47], small);
+}
+
+#[test]
+fn test_parse_errors() {
+    let err = |spec: &str| RuleSet::parse(spec).unwrap_err();
+    assert_eq!(RuleError::UnknownKey { line: 1, key: "colour".to_string() }, err("colour = 1"));
+    assert_eq!(RuleError::Syntax { line: 2, text: "label".to_string() }, err("[[rule]]\nlabel"));
+    assert_eq!(
+        RuleError::InvalidValue { line: 1, key: "mode".to_string(), expected: "\"first\" or \"all\"" },
+        err("mode = \"some\"")
+    );
+    assert_eq!(RuleError::UnknownPredicate { line: 2, name: "happy".to_string() }, err("[[rule]]\npredicate = \"happy\""));
+    assert_eq!(RuleError::IncompleteRule { index: 1, missing: "label" }, err("[[rule]]\nmin = 1\n[[rule]]"));
+    assert_eq!(RuleError::IncompleteRule { index: 2, missing: "condition" }, err("[[rule]]\nlabel = \"a\"\nmin = 1\n[[rule]]\nlabel = \"b\""));
+    assert_eq!("line 1: unknown key `colour`", err("colour = 1").to_string());
+}
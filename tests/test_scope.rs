@@ -1,4 +1,4 @@
-
+use learn_rust::scopes::Analysis;
 
 // {} 的代码块是一个 scope 表达式，表达式返回最后一个值
 #[test]
@@ -19,4 +19,11 @@ fn test_scope_shadow() {
     };
     assert_eq!(4, y);
     assert_eq!(5, x);
+
+    // 用 learn_rust::scopes 分析这个函数的源码，可以看到内层的 x 遮蔽了外层的 x，也可以运行
+    // `learn_rust scopes tests/test_scope.rs test_scope_shadow`
+    let analysis = Analysis::from_function(include_str!("test_scope.rs"), "test_scope_shadow").unwrap();
+    let xs = analysis.named("x");
+    assert_eq!(Some(xs[0].id), xs[1].shadows);
+    assert_ne!(xs[0].scope, xs[1].scope);
 }
@@ -0,0 +1,163 @@
+use learn_rust::scopes::{Analysis, BindingKind, Position, ScopeError, ScopeKind};
+
+const SRC: &str = r#"
+fn shadow(n: i32, _unused: bool) -> i32 {
+    let x = n;
+    let y = {
+        let x = 3;
+        x + 1
+    };
+    let x = x * 2;
+    let mut total = 0;
+    total = x + y;
+    total
+}
+
+fn patterns(values: &[Option<(i32, i32)>]) {
+    for (i, value) in values.iter().enumerate() {
+        if let Some((a, b)) = value {
+            println!("{i}: {}", a);
+        }
+    }
+    let p = Point { x: 1, y: 2 };
+    let Point { x: px, y } = p;
+    match px {
+        n if n > y => println!("{n:?}"),
+        0 | 1 => {}
+        other => {
+            let px = other;
+        }
+    }
+}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    fn norm(&self) -> i32 {
+        let square = |v: i32| v * v;
+        let add = |a, b| a + b;
+        fn helper(x: i32) -> i32 {
+            x
+        }
+        add(square(self.x), helper(self.y))
+    }
+}
+"#;
+
+fn pos(line: usize, column: usize) -> Position {
+    Position { line, column }
+}
+
+#[test]
+fn test_shadowing() {
+    let analysis = Analysis::from_function(SRC, "shadow").unwrap();
+    let xs = analysis.named("x");
+    assert_eq!(3, xs.len());
+    assert_eq!(pos(3, 9), xs[0].declared);
+    // 内层 scope 中的 x 遮蔽外层的 x
+    assert_eq!(Some(xs[0].id), xs[1].shadows);
+    assert_ne!(xs[0].scope, xs[1].scope);
+    assert_eq!(ScopeKind::Block, analysis.scope(xs[1].scope).unwrap().kind);
+    // `let x = x * 2` 右边读取的是第一个 x
+    assert_eq!(Some(xs[0].id), xs[2].shadows);
+    assert_eq!(vec![pos(8, 13)], xs[0].uses);
+    assert_eq!(Some(pos(10, 13)), xs[2].last_use());
+
+    let total = &analysis.named("total")[0];
+    assert!(total.mutable);
+    assert_eq!(vec![pos(10, 5)], total.assignments);
+    assert_eq!(vec![pos(11, 5)], total.uses);
+
+    // 参数也是绑定，名字以 _ 开头的不算未使用
+    let params: Vec<&str> = analysis.bindings.iter().filter(|b| b.kind == BindingKind::Parameter).map(|b| b.name.as_str()).collect();
+    assert_eq!(vec!["n", "_unused"], params);
+    assert!(analysis.unused().is_empty());
+    assert_eq!(2, analysis.shadowing().len());
+}
+
+#[test]
+fn test_patterns() {
+    let analysis = Analysis::from_function(SRC, "patterns").unwrap();
+    let names = |kind| -> Vec<String> {
+        analysis.bindings.iter().filter(|b| b.kind == kind).map(|b| b.name.clone()).collect()
+    };
+    assert_eq!(vec!["values"], names(BindingKind::Parameter));
+    assert_eq!(vec!["i", "value"], names(BindingKind::For));
+    // `Some` 与 `Point` 是路径，x: 是字段名，0 | 1 中没有绑定
+    assert_eq!(vec!["a", "b", "n", "other"], names(BindingKind::Pattern));
+    assert_eq!(vec!["p", "px", "y", "px"], names(BindingKind::Let));
+
+    // 格式化字符串中的 {i} 与 {n:?} 也是读取
+    assert_eq!(1, analysis.named("i")[0].uses.len());
+    assert_eq!(2, analysis.named("n")[0].uses.len());
+    // b 从来没有读取过，分支中的 px 也没有
+    let unused: Vec<(&str, usize)> = analysis.unused().iter().map(|b| (b.name.as_str(), b.declared.line)).collect();
+    assert_eq!(vec![("b", 16), ("px", 26)], unused);
+
+    let inner_px = analysis.named("px")[1];
+    assert_eq!(Some(analysis.named("px")[0].id), inner_px.shadows);
+    // 分支的 {} 与分支的模式是同一个 scope
+    let arm = analysis.scope(inner_px.scope).unwrap();
+    assert_eq!(ScopeKind::Arm, arm.kind);
+    assert_eq!(pos(25, 9), arm.start);
+    assert_eq!(ScopeKind::Function, analysis.scope(arm.parent.unwrap()).unwrap().kind);
+}
+
+#[test]
+fn test_closures_and_nested_functions() {
+    let analysis = Analysis::from_source(SRC).unwrap();
+    assert_eq!(vec!["shadow", "patterns", "norm", "helper"], analysis.functions());
+
+    let norm = Analysis::from_function(SRC, "norm").unwrap();
+    let closure_params: Vec<&str> =
+        norm.bindings.iter().filter(|b| b.kind == BindingKind::Closure).map(|b| b.name.as_str()).collect();
+    assert_eq!(vec!["v", "a", "b"], closure_params);
+    // self.x 与 self.y 是字段，不是变量
+    assert!(norm.named("x").is_empty());
+    assert_eq!(1, norm.named("square")[0].uses.len());
+
+    // 嵌套的函数看不到外层的变量，它的参数 x 不遮蔽任何东西
+    let helper = Analysis::from_function(SRC, "helper").unwrap();
+    assert_eq!(None, helper.named("x")[0].shadows);
+    assert_eq!(None, helper.scopes[0].parent);
+}
+
+#[test]
+fn test_lessons() {
+    // test_integer_type 中的 num 一个也没有读取过，test_char_type 中的 ch 每个都读取过
+    let src = include_str!("test_builtin_data_type.rs");
+    let analysis = Analysis::from_function(src, "test_integer_type").unwrap();
+    let nums = analysis.named("num");
+    assert!(nums.len() >= 6);
+    assert!(nums.iter().all(|b| b.is_unused()));
+    assert!(nums.windows(2).all(|w| w[1].shadows == Some(w[0].id)));
+
+    let analysis = Analysis::from_function(src, "test_char_type").unwrap();
+    assert!(analysis.named("ch").iter().all(|b| !b.is_unused()));
+
+    let src = include_str!("test_scope.rs");
+    let analysis = Analysis::from_function(src, "test_scope_shadow").unwrap();
+    let report = analysis.render();
+    assert!(report.contains("(outer)"));
+    // 只检查 x 的遮蔽关系，课程中增加别的变量不影响
+    let xs = analysis.named("x");
+    assert_eq!(2, xs.len());
+    assert_eq!(None, xs[0].shadows);
+    assert_eq!(Some(xs[0].id), xs[1].shadows);
+}
+
+#[test]
+fn test_json_and_errors() {
+    let analysis = Analysis::from_source("fn f(a: u8) { let _b = a; }").unwrap();
+    let json = analysis.to_json();
+    assert!(json.contains(r#"{"id": 0, "kind": "fn", "parent": null, "function": "f", "start": [1, 1], "end": [1, 27]}"#));
+    assert!(json.contains(r#""name": "a", "kind": "param", "mutable": false"#));
+    assert!(json.contains(r#""uses": [[1, 24]], "assignments": [], "last_use": [1, 24], "unused": false"#));
+
+    assert_eq!(Err(ScopeError::FunctionNotFound("g".to_string())), Analysis::from_function("fn f() {}", "g"));
+    assert_eq!(Err(ScopeError::UnbalancedDelimiter { line: 2 }), Analysis::from_source("fn f() {\n)"));
+    assert_eq!(Err(ScopeError::UnterminatedLiteral { line: 1 }), Analysis::from_source("fn f() { \"abc }"));
+}
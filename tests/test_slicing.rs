@@ -0,0 +1,132 @@
+use learn_rust::slicing::{Slice, SliceError, SliceExt, StrSliceExt};
+
+fn s(text: &str) -> Slice {
+    text.parse().unwrap()
+}
+
+fn pick(xs: &[i32], text: &str) -> Vec<i32> {
+    xs.py_slice(s(text)).to_vec()
+}
+
+#[test]
+fn test_negative_index() {
+    let xs = [1, 2, 3, 4, 5];
+    assert_eq!(&5, xs.py_index(-1));
+    assert_eq!(&1, xs.py_index(-5));
+    assert_eq!(&1, xs.py_index(0));
+    assert_eq!(None, xs.py_get(-6));
+    assert_eq!(None, xs.py_get(5));
+    assert_eq!(None, xs.py_get(isize::MIN));
+    // 越界时 py_index 与 xs[i] 一样 panic
+    assert!(std::panic::catch_unwind(|| *xs.py_index(100)).is_err());
+}
+
+#[test]
+fn test_slice_like_python() {
+    let xs = [1, 2, 3, 4, 5];
+    // 与 &xs[1..4] 相同，而且可以借用为切片
+    assert_eq!(Some(&xs[1..4]), xs.py_slice(Slice::range(1, 4)).as_slice());
+    assert_eq!(vec![2, 3, 4], pick(&xs, "1:4"));
+    assert_eq!(vec![4, 5], pick(&xs, "-2:"));
+    assert_eq!(vec![1, 2, 3, 4], pick(&xs, ":-1"));
+    assert_eq!(vec![1, 3, 5], pick(&xs, "::2"));
+    assert_eq!(vec![2, 4], pick(&xs, "1:-1:2"));
+    assert_eq!(vec![5, 4, 3, 2, 1], pick(&xs, "::-1"));
+    assert_eq!(vec![4, 3], pick(&xs, "3:1:-1"));
+    assert_eq!(vec![5, 3], pick(&xs, "-1:-4:-2"));
+    assert_eq!(vec![1, 2, 3, 4, 5], xs.py_slice(Slice::default()).to_vec());
+    assert_eq!(vec![5, 4, 3, 2, 1], xs.py_slice(Slice::reversed()).to_vec());
+
+    // 越界的边界会被截断
+    assert_eq!(vec![1, 2, 3, 4, 5], pick(&xs, "-100:100"));
+    assert_eq!(vec![4, 5], pick(&xs, "3:100"));
+    assert_eq!(Vec::<i32>::new(), pick(&xs, "100:"));
+    assert_eq!(Vec::<i32>::new(), pick(&xs, "3:1"));
+    assert_eq!(vec![5, 4, 3, 2, 1], pick(&xs, "100:-100:-1"));
+    assert_eq!(Vec::<i32>::new(), pick(&[], "::-1"));
+
+    // 迭代器可以从两端取
+    let mut strided = xs.py_slice(Slice::until(5).with_step(2));
+    assert_eq!(3, strided.len());
+    assert_eq!(None, strided.as_slice());
+    assert_eq!(Some(&5), strided.next_back());
+    assert_eq!(vec![&1, &3], strided.collect::<Vec<_>>());
+}
+
+#[test]
+fn test_slice_matches_python_indices() {
+    // 与 Python 的 range(*slice(start, stop, step).indices(len)) 逐个对比
+    let xs: Vec<i32> = (0..7).collect();
+    let bounds = [None, Some(-9), Some(-7), Some(-3), Some(-1), Some(0), Some(2), Some(6), Some(7), Some(9)];
+    for start in bounds {
+        for stop in bounds {
+            for step in [-3, -2, -1, 1, 2, 3] {
+                let len = xs.len() as isize;
+                let (mut i, end) = if step > 0 {
+                    let clamp = |x: isize| if x < 0 { (x + len).max(0) } else { x.min(len) };
+                    (start.map_or(0, clamp), stop.map_or(len, clamp))
+                } else {
+                    let clamp = |x: isize| if x < 0 { (x + len).max(-1) } else { x.min(len - 1) };
+                    (start.map_or(len - 1, clamp), stop.map_or(-1, clamp))
+                };
+                let mut expected = Vec::new();
+                while (step > 0 && i < end) || (step < 0 && i > end) {
+                    expected.push(i as i32);
+                    i += step;
+                }
+                let slice = Slice::new(start, stop, Some(step));
+                assert_eq!(expected, xs.py_slice(slice).to_vec(), "{}", slice);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_slice_errors() {
+    let xs = [1, 2, 3];
+    assert_eq!(SliceError::ZeroStep, xs.py_try_slice(s("::0")).unwrap_err());
+    assert!(std::panic::catch_unwind(|| xs.py_slice(s("::0")).count()).is_err());
+
+    assert_eq!(Slice::new(Some(1), None, Some(-1)), s(" 1 : : -1 "));
+    assert_eq!("1:-1:2", Slice::range(1, -1).with_step(2).to_string());
+    assert_eq!(":3", Slice::until(3).to_string());
+    assert!(matches!("3".parse::<Slice>(), Err(SliceError::Invalid(_))));
+    assert!(matches!("1:2:3:4".parse::<Slice>(), Err(SliceError::Invalid(_))));
+    assert!(matches!("a:".parse::<Slice>(), Err(SliceError::Invalid(_))));
+}
+
+#[test]
+fn test_str_slice_by_char() {
+    let text = "你好, world";
+    assert_eq!(Some('你'), text.py_get(0));
+    assert_eq!(Some('d'), text.py_get(-1));
+    assert_eq!(Some(','), text.py_get(-7));
+    assert_eq!(None, text.py_get(9));
+    assert_eq!(None, text.py_get(-10));
+    assert_eq!('好', text.py_index(1));
+
+    assert_eq!("你好", text.py_slice(s(":2")));
+    assert_eq!("world", text.py_slice(s("-5:")));
+    assert_eq!("dlrow ,好你", text.py_slice(Slice::reversed()));
+    assert_eq!("你,wrd", text.py_slice(s("::2")));
+    assert_eq!("", text.py_slice(s("20:")));
+    // step 为 1 时借用原字符串，不会分配
+    assert!(matches!(text.py_slice(s("1:4")), std::borrow::Cow::Borrowed("好, ")));
+    assert!(matches!(text.py_slice(s("::-1")), std::borrow::Cow::Owned(_)));
+}
+
+#[test]
+fn test_str_slice_by_byte() {
+    // "你" 占 3 个字节，&text[0..1] 会 panic
+    let text = "你好";
+    assert!(std::panic::catch_unwind(|| text[0..1].to_string()).is_err());
+
+    assert_eq!(Ok("你"), text.byte_slice(s(":3")));
+    assert_eq!(Ok("好"), text.byte_slice(s("-3:")));
+    assert_eq!(Ok("你好"), text.byte_slice(s("-100:100")));
+    assert_eq!(Ok(""), text.byte_slice(s("3:3")));
+    assert_eq!(Err(SliceError::NotCharBoundary(1)), text.byte_slice(s(":1")));
+    assert_eq!(Err(SliceError::NotCharBoundary(5)), text.byte_slice(s("-1:")));
+    assert!(matches!(text.byte_slice(s("::2")), Err(SliceError::Invalid(_))));
+    assert_eq!("byte index 1 is not a char boundary", SliceError::NotCharBoundary(1).to_string());
+}
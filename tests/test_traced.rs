@@ -0,0 +1,75 @@
+use learn_rust::traced::{self, Event, TracedString};
+
+fn lines(events: &[Event]) -> Vec<String> {
+    events.iter().map(|event| event.to_string()).collect()
+}
+
+#[test]
+fn test_alloc_move_drop() {
+    {
+        let s1 = TracedString::new("s1", "hello");
+        let s2 = s1.moved("s2");
+        let s3 = s2.moved("s3");
+        assert_eq!("hello", s3);
+        assert_eq!("s3", s3.name());
+    }
+    // 转移只记录一次 move，整个过程只有一次分配和一次释放
+    let expected = ["alloc s1 \"hello\" (capacity 5)", "move  s1 -> s2", "move  s2 -> s3", "drop  s3 (capacity 5)"];
+    assert_eq!(expected.to_vec(), lines(&traced::take_log()));
+    assert!(traced::take_log().is_empty());
+}
+
+#[test]
+fn test_clone() {
+    {
+        let s1 = TracedString::with_capacity("s1", 16);
+        let s2 = s1.clone_as("s2");
+        let s3 = s2.clone();
+        assert_eq!(s1, s2);
+        assert_eq!("s2", s3.name());
+        // 深拷贝只分配需要的大小
+        assert!(s1.capacity() >= 16);
+        assert_eq!(0, s2.capacity());
+    }
+    let log = traced::take_log();
+    assert_eq!(Event::Clone { from: "s1", name: "s2", capacity: 0 }, log[1]);
+    assert_eq!(Event::Clone { from: "s2", name: "s2", capacity: 0 }, log[2]);
+    // 后声明的先 drop
+    let drops: Vec<&str> = log.iter().filter_map(|event| match event {
+        Event::Drop { name, .. } => Some(*name),
+        _ => None,
+    }).collect();
+    assert_eq!(vec!["s2", "s2", "s1"], drops);
+}
+
+#[test]
+fn test_function_boundaries() {
+    fn consume(s: TracedString) -> usize {
+        s.len()
+    }
+
+    fn give_back(s: TracedString) -> TracedString {
+        s.moved("returned")
+    }
+
+    let s = TracedString::new("s", "abc");
+    let len = consume(s.moved("consume::s"));
+    assert_eq!(3, len);
+    let kept = give_back(TracedString::new("t", "xyz").moved("give_back::s"));
+    assert_eq!("xyz", kept.as_str());
+    let plain: String = kept.into_string();
+    assert_eq!("xyz", plain);
+
+    let expected = [
+        "alloc s \"abc\" (capacity 3)",
+        "move  s -> consume::s",
+        // 函数结束时参数被 drop
+        "drop  consume::s (capacity 3)",
+        "alloc t \"xyz\" (capacity 3)",
+        "move  t -> give_back::s",
+        "move  give_back::s -> returned",
+        "drop  returned (capacity 3)",
+    ];
+    assert_eq!(expected.to_vec(), lines(&traced::take_log()));
+    assert_eq!("TracedString { name: \"n\", value: \"v\" }", format!("{:?}", TracedString::new("n", "v")));
+}
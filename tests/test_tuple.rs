@@ -0,0 +1,66 @@
+use std::cmp::Ordering;
+
+use learn_rust::tuple::{Tuple, TupleAny, TupleDebug, TupleEq, TupleOrd};
+
+#[test]
+fn test_tuple_debug_same_as_std() {
+    // 12 个元素以内与标准库的输出一致
+    assert_eq!(format!("{:?}", ()), format!("{:?}", ().debug()));
+    assert_eq!(format!("{:?}", (1,)), format!("{:?}", (1,).debug()));
+    assert_eq!(format!("{:?}", (500, 6.4, true)), format!("{:?}", (500, 6.4, true).debug()));
+    assert_eq!(format!("{:#?}", (1, "a")), format!("{:#?}", (1, "a").debug()));
+}
+
+#[test]
+fn test_tuple_debug_long() {
+    let too_long_tuple = (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13);
+    assert_eq!("(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13)", format!("{:?}", too_long_tuple.debug()));
+    assert_eq!(13, too_long_tuple.len());
+
+    // 不同类型的元素
+    let mixed = (1u8, -2i64, 3.5, 'c', "str", String::from("s"), true, (), [1, 2], Some(1), None::<i32>, 12, 13, 14);
+    assert_eq!(
+        r#"(1, -2, 3.5, 'c', "str", "s", true, (), [1, 2], Some(1), None, 12, 13, 14)"#,
+        format!("{:?}", mixed.debug())
+    );
+
+    let max = (
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+        16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+    );
+    assert_eq!(32, max.len());
+    let expected: Vec<String> = (0..32).map(|i| i.to_string()).collect();
+    assert_eq!(format!("({})", expected.join(", ")), format!("{:?}", max.debug()));
+}
+
+#[test]
+fn test_tuple_compare() {
+    let a = (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13);
+    let mut b = a;
+    assert!(a.tuple_eq(&b));
+    b.12 = 14;
+    assert!(!a.tuple_eq(&b));
+    assert_eq!(Some(Ordering::Less), a.tuple_partial_cmp(&b));
+    assert_eq!(Some(Ordering::Greater), b.tuple_partial_cmp(&a));
+    // 前面的元素优先
+    b.0 = 0;
+    assert_eq!(Some(Ordering::Greater), a.tuple_partial_cmp(&b));
+
+    // 与标准库的字典序一致，包括 NaN 无法比较的情况
+    assert_eq!((1, 2.0).partial_cmp(&(1, f64::NAN)), (1, 2.0).tuple_partial_cmp(&(1, f64::NAN)));
+    assert_eq!(Some(Ordering::Equal), ().tuple_partial_cmp(&()));
+}
+
+#[test]
+fn test_tuple_iterate() {
+    let tup = (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, "thirteen");
+    let printed: Vec<String> = tup.debug_elements().iter().map(|e| format!("{:?}", e)).collect();
+    assert_eq!(13, printed.len());
+    assert_eq!("\"thirteen\"", printed[12]);
+
+    // 按 Any 遍历，可以向下转换回具体类型
+    let elements = tup.any_elements();
+    let sum: i32 = elements.iter().filter_map(|e| e.downcast_ref::<i32>()).sum();
+    assert_eq!(78, sum);
+    assert_eq!(Some(&"thirteen"), elements[12].downcast_ref::<&str>());
+}
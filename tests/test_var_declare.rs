@@ -1,3 +1,6 @@
+use learn_rust::const_tables;
+use learn_rust::globals::{self, Counter};
+use learn_rust::metrics;
 
 // 类型声明
 #[test]
@@ -53,6 +56,12 @@ fn test_const_declare() {
     let arr = [3, 3, 3, 3];
     // const COUNT : i32 = arr[1]; // arr[1] 是运行阶段才能确定的值，non-constant value
 
+    // const fn 可以在编译阶段执行，它的结果也可以赋给常量
+    const CHECKSUM : u32 = const_tables::crc32(b"123456789");
+    const DIGITS : u32 = const_tables::decimal_digits(1_000_000);
+    assert_eq!(0xCBF43926, CHECKSUM);
+    assert_eq!(7, DIGITS);
+
 }
 
 #[test]
@@ -76,5 +85,17 @@ fn test_static_declare() {
         assert_ne!(0, COUNT);
     }
 
+    // 不需要 unsafe 的写法：原子计数器，多个线程同时加 1 也不会少
+    static SAFE_COUNT: Counter = Counter::new(0);
+    globals::hammer(4, 100, || {
+        SAFE_COUNT.increment();
+    });
+    assert_eq!(400, SAFE_COUNT.get());
+
+    // 进程共用的指标注册表也是一个全局变量，按名字登记后在任何地方更新
+    let runs = metrics::global().counter("lesson_static_declare_runs_total", "lesson runs").unwrap();
+    runs.increment();
+    assert!(metrics::global().snapshot().to_prometheus().contains("lesson_static_declare_runs_total"));
+
 }
 